@@ -0,0 +1,324 @@
+use crate::{
+    batch::Idx,
+    buffer::{Vertex, VertexBuffer},
+    color::Color,
+    frame::Frame,
+    prelude::TexturePosition,
+    target::Target,
+};
+use bytemuck::{Pod, Zeroable};
+use glam::Vec2;
+use std::{
+    collections::{BTreeSet, BinaryHeap},
+    mem,
+};
+use wgpu::{VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
+
+//
+
+/// The corners of the unit quad that every
+/// instance expands, as a triangle strip
+pub const UNIT_QUAD: [Vec2; 4] = [
+    Vec2::new(-0.5, -0.5),
+    Vec2::new(-0.5, 0.5),
+    Vec2::new(0.5, -0.5),
+    Vec2::new(0.5, 0.5),
+];
+
+//
+
+/// One sprite in an [`InstancedBatch`]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Zeroable, Pod)]
+#[repr(C)]
+pub struct SpriteInstance {
+    /// center of the sprite
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub col: Color,
+    pub tex: TexturePosition,
+    /// counter clockwise rotation around
+    /// the center in radians
+    pub rotation: f32,
+}
+
+/// A sprite batch that uploads one
+/// [`SpriteInstance`] per sprite instead of
+/// 4 vertices and 5 indices like
+/// [`super::BatchRenderer`] does
+///
+/// Draw with
+/// [`crate::frame::render_pass::RenderPass::bind_instanced_vbo`]
+/// and `draw(0..4, 0..instances)`
+#[derive(Debug)]
+pub struct InstancedBatch<T = SpriteInstance>
+where
+    T: Vertex + Copy,
+{
+    quad: VertexBuffer<Vec2>,
+    instances: VertexBuffer<T>,
+    slots: Slots<T>,
+}
+
+// the instances on the CPU side
+#[derive(Debug)]
+struct Slots<T> {
+    // slots that need to be re-uploaded
+    modified: BTreeSet<usize>,
+    free: BinaryHeap<usize>,
+    used: Vec<Option<T>>,
+}
+
+//
+
+impl SpriteInstance {
+    pub fn new(pos: Vec2, size: Vec2, col: Color, tex: TexturePosition) -> Self {
+        Self {
+            pos,
+            size,
+            col,
+            tex,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+impl Vertex for SpriteInstance {
+    const LAYOUT: &'static [VertexBufferLayout<'static>] = &[
+        // unit quad
+        VertexBufferLayout {
+            array_stride: 8,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &[VertexAttribute {
+                format: VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 0,
+            }],
+        },
+        // instances
+        VertexBufferLayout {
+            array_stride: 52,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 0,
+                    shader_location: 1,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 8,
+                    shader_location: 2,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 16,
+                    shader_location: 3,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 32,
+                    shader_location: 4,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32,
+                    offset: 48,
+                    shader_location: 5,
+                },
+            ],
+        },
+    ];
+}
+
+impl<T> InstancedBatch<T>
+where
+    T: Vertex + Copy,
+{
+    pub fn new(target: &Target) -> Self {
+        Self {
+            quad: VertexBuffer::new_with(target, &UNIT_QUAD),
+            instances: VertexBuffer::new(target, 0),
+            slots: Slots::default(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.slots = Slots::default();
+    }
+
+    pub fn push_with(&mut self, instance: T) -> Idx {
+        self.slots.push_with(instance)
+    }
+
+    pub fn push(&mut self) -> Idx
+    where
+        T: Default,
+    {
+        self.push_with(Default::default())
+    }
+
+    pub fn drop(&mut self, idx: Idx) {
+        self.slots.drop(idx)
+    }
+
+    pub fn get(&self, idx: Idx) -> Option<&T> {
+        self.slots.get(idx)
+    }
+
+    pub fn get_mut(&mut self, idx: Idx) -> Option<&mut T> {
+        self.slots.get_mut(idx)
+    }
+
+    /// upload the modified instances
+    ///
+    /// returns the unit quad, the instances
+    /// and the number of instances to draw
+    pub fn generate(
+        &mut self,
+        target: &mut Target,
+        frame: &mut Frame,
+    ) -> (&'_ VertexBuffer<Vec2>, &'_ VertexBuffer<T>, u32) {
+        let len = self.slots.used.len();
+
+        // grow by copying the old data on the GPU
+        if self.instances.capacity() < len {
+            let capacity = (self.instances.capacity() * 2).max(len);
+            self.instances = self.instances.grow(target, frame, capacity);
+        }
+
+        for (start, data) in self.slots.update() {
+            self.instances.upload_at(target, frame, start as _, &data);
+        }
+
+        (&self.quad, &self.instances, len as _)
+    }
+}
+
+impl<T> Default for Slots<T> {
+    fn default() -> Self {
+        Self {
+            modified: Default::default(),
+            free: Default::default(),
+            used: Default::default(),
+        }
+    }
+}
+
+impl<T> Slots<T>
+where
+    T: Pod,
+{
+    fn push_with(&mut self, instance: T) -> Idx {
+        let spot = if let Some(spot) = self.free.pop() {
+            self.used[spot] = Some(instance);
+            spot
+        } else {
+            let spot = self.used.len();
+            self.used.push(Some(instance));
+            spot
+        };
+        self.modified.insert(spot);
+        Idx(spot)
+    }
+
+    fn drop(&mut self, idx: Idx) {
+        if let Some(m @ Some(_)) = self.used.get_mut(idx.0) {
+            *m = None;
+            // zeroed instances have no size
+            self.modified.insert(idx.0);
+            self.free.push(idx.0);
+        }
+    }
+
+    fn get(&self, idx: Idx) -> Option<&T> {
+        if let Some(Some(instance)) = self.used.get(idx.0) {
+            Some(instance)
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, idx: Idx) -> Option<&mut T> {
+        if let Some(Some(instance)) = self.used.get_mut(idx.0) {
+            self.modified.insert(idx.0);
+            Some(instance)
+        } else {
+            None
+        }
+    }
+
+    // the modified spans to upload
+    fn update(&mut self) -> Vec<(usize, Vec<T>)> {
+        let mut spans: Vec<(usize, Vec<T>)> = vec![];
+        for i in mem::take(&mut self.modified) {
+            let instance = self.used[i].unwrap_or_else(T::zeroed);
+            match spans.last_mut() {
+                Some((start, data)) if *start + data.len() == i => data.push(instance),
+                _ => spans.push((i, vec![instance])),
+            }
+        }
+        spans
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Slots, SpriteInstance};
+    use crate::prelude::{Color, TexturePosition};
+    use bytemuck::Zeroable;
+    use glam::Vec2;
+
+    fn sprite(x: f32) -> SpriteInstance {
+        SpriteInstance::new(
+            Vec2::new(x, 0.0),
+            Vec2::ONE,
+            Color::WHITE,
+            TexturePosition::default(),
+        )
+    }
+
+    #[test]
+    pub fn test_slots() {
+        let mut slots = Slots::default();
+        let a = slots.push_with(sprite(0.0));
+        let b = slots.push_with(sprite(1.0));
+        let c = slots.push_with(sprite(2.0));
+
+        let spans = slots.update();
+        assert_eq!(spans, [(0, vec![sprite(0.0), sprite(1.0), sprite(2.0)])]);
+        assert!(slots.update().is_empty());
+
+        // modify: only the modified slot is uploaded
+        slots.get_mut(c).unwrap().pos.x = 3.0;
+        assert_eq!(slots.update(), [(2, vec![sprite(3.0)])]);
+
+        // drop: the slot is zeroed, but still drawn
+        slots.drop(b);
+        assert_eq!(slots.get(b), None);
+        assert_eq!(slots.update(), [(1, vec![SpriteInstance::zeroed()])]);
+        assert_eq!(slots.used.len(), 3);
+
+        // push: the dropped slot is reused
+        let d = slots.push_with(sprite(4.0));
+        assert_eq!(d.0, b.0);
+        assert_eq!(slots.update(), [(1, vec![sprite(4.0)])]);
+        assert_eq!(slots.used.len(), 3);
+
+        // push: grows the draw count
+        slots.drop(a);
+        slots.drop(c);
+        let e = slots.push_with(sprite(5.0));
+        let f = slots.push_with(sprite(6.0));
+        let g = slots.push_with(sprite(7.0));
+        assert_eq!((e.0, f.0, g.0), (2, 0, 3));
+        assert_eq!(
+            slots.update(),
+            [(0, vec![sprite(6.0)]), (2, vec![sprite(5.0), sprite(7.0)])]
+        );
+        assert_eq!(slots.used.len(), 4);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    buffer::{DefaultVertex, LayeredVertex},
+    prelude::Mesh,
+};
+use std::iter::{Map, Repeat, Zip};
+use wgpu::PrimitiveTopology;
+
+//
+
+/// Any [`DefaultVertex`] mesh drawn from a
+/// layer of a [`crate::texture::Texture2DArray`]
+///
+/// `BatchRenderer<Layered<QuadMesh>, LayeredVertex>`
+/// can mix sprites from multiple atlases
+/// in a single draw call
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Layered<M> {
+    pub mesh: M,
+    pub layer: u32,
+}
+
+//
+
+impl<M> Layered<M> {
+    pub fn new(mesh: M, layer: u32) -> Self {
+        Self { mesh, layer }
+    }
+}
+
+impl<M> Mesh<LayeredVertex> for Layered<M>
+where
+    M: Mesh<DefaultVertex>,
+{
+    const PRIM: PrimitiveTopology = M::PRIM;
+
+    type VertexIter =
+        Map<Zip<M::VertexIter, Repeat<u32>>, fn((DefaultVertex, u32)) -> LayeredVertex>;
+    type IndexIter = M::IndexIter;
+
+    fn vertices(&self) -> Self::VertexIter {
+        self.mesh
+            .vertices()
+            .zip(std::iter::repeat(self.layer))
+            .map(|(vertex, layer)| LayeredVertex::from_default(vertex, layer))
+    }
+
+    fn indices(&self, offset: u32) -> Self::IndexIter {
+        self.mesh.indices(offset)
+    }
+
+    fn index_step(&self) -> u32 {
+        self.mesh.index_step()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Layered;
+    use crate::prelude::{Color, Mesh, QuadMesh, TexturePosition};
+    use glam::Vec2;
+
+    #[test]
+    pub fn test_layer() {
+        let quad = QuadMesh::new_centered(
+            Vec2::ZERO,
+            Vec2::ONE,
+            Color::WHITE,
+            TexturePosition::default(),
+        );
+        let layered = Layered::new(quad, 3);
+
+        let vertices: Vec<_> = layered.vertices().collect();
+        assert_eq!(vertices.len(), 4);
+        for (vertex, original) in vertices.iter().zip(quad.vertices()) {
+            assert_eq!(vertex.layer(), 3);
+            assert_eq!(vertex.pos(), original.pos());
+            assert_eq!(vertex.uv(), original.uv());
+        }
+
+        assert!(layered.indices(4).eq(quad.indices(4)));
+        assert_eq!(layered.index_step(), quad.index_step());
+    }
+}
@@ -1,7 +1,13 @@
 use self::{mesh::Mesh, quad::QuadMesh};
 use crate::prelude::{DefaultVertex, Frame, IndexBuffer, Target, Vertex, VertexBuffer};
 use serde::{Deserialize, Serialize};
-use std::{collections::BinaryHeap, marker::PhantomData};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, BinaryHeap},
+    marker::PhantomData,
+    mem,
+    ops::Range,
+};
 
 //
 
@@ -9,7 +15,11 @@ pub use wgpu::PrimitiveTopology;
 
 //
 
+pub mod instanced;
+pub mod layered;
 pub mod mesh;
+pub mod nine_slice;
+pub mod paged;
 pub mod prelude;
 pub mod quad;
 
@@ -21,21 +31,65 @@ where
     M: Mesh<V>,
     V: Vertex + Copy,
 {
-    vbo: VertexBuffer<V>,
-    ibo: IndexBuffer<u32>,
-    ibo_regen: bool,
+    // created on the first generate
+    // if not created with a target
+    vbo: Option<VertexBuffer<V>>,
+    ibo: Option<IndexBuffer<u32>>,
     ibo_len: u32,
 
-    modified: bool, /* HashSet<usize> */
-    free: BinaryHeap<usize>,
-    used: Vec<Option<M>>,
-
-    _p: PhantomData<M>,
+    slots: Slots<M, V>,
 }
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Idx(usize);
 
+/// Draw order of a mesh in a [`BatchRenderer`]
+///
+/// Meshes are drawn in ascending order, first by
+/// `layer` and then by `depth`. Meshes with equal
+/// keys are drawn in the order they were pushed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SortKey {
+    pub layer: i32,
+    pub depth: f32,
+}
+
+//
+
+// the vertex spans to upload and
+// the new indices if they have changed
+type Changes<V> = (Vec<(u32, Vec<V>)>, Option<Vec<u32>>);
+
+// the CPU side of a BatchRenderer
+#[derive(Debug)]
+struct Slots<M, V> {
+    ibo_regen: bool,
+
+    // end of the allocated vertex ranges
+    vbo_len: u32,
+    // unused vertex ranges below `vbo_len`,
+    // sorted and never touching each other
+    vbo_free: Vec<Range<u32>>,
+
+    // slots that need to be re-uploaded
+    modified: BTreeSet<usize>,
+    free: BinaryHeap<usize>,
+    used: Vec<Option<M>>,
+
+    // the vertex range of each slot
+    ranges: Vec<Range<u32>>,
+
+    // the sort key and the push order of each slot,
+    // the push order keeps equal keys stable
+    keys: Vec<(SortKey, u64)>,
+    next_seq: u64,
+    // live slots in the draw order
+    order: Vec<usize>,
+    resort: bool,
+
+    _p: PhantomData<V>,
+}
+
 //
 
 impl<M, V> BatchRenderer<M, V>
@@ -45,58 +99,182 @@ where
 {
     pub fn new(target: &Target) -> Self {
         Self {
-            vbo: VertexBuffer::new(target, 0),
-            ibo: IndexBuffer::new(target, 0),
-            ibo_regen: false,
+            vbo: Some(VertexBuffer::new(target, 0)),
+            ibo: Some(IndexBuffer::new(target, 0)),
+            ..Self::new_lazy()
+        }
+    }
+
+    /// a batch that creates its buffers
+    /// in the first [`Self::generate`]
+    pub fn new_lazy() -> Self {
+        Self {
+            vbo: None,
+            ibo: None,
             ibo_len: 0,
 
+            slots: Slots::default(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.ibo_len = 0;
+        self.slots.clear();
+    }
+
+    pub fn push_with(&mut self, mesh: M) -> Idx {
+        self.push_with_key(mesh, SortKey::default())
+    }
+
+    pub fn push_with_key(&mut self, mesh: M, key: SortKey) -> Idx {
+        self.slots.push_with_key(mesh, key)
+    }
+
+    pub fn push(&mut self) -> Idx
+    where
+        M: Default,
+    {
+        self.push_with(Default::default())
+    }
+
+    pub fn drop(&mut self, idx: Idx) {
+        self.slots.drop(idx)
+    }
+
+    pub fn get(&self, idx: Idx) -> Option<&M> {
+        self.slots.get(idx)
+    }
+
+    pub fn get_mut(&mut self, idx: Idx) -> Option<&mut M> {
+        self.slots.get_mut(idx)
+    }
+
+    pub fn get_key(&self, idx: Idx) -> Option<SortKey> {
+        self.get(idx)?;
+        Some(self.slots.keys[idx.0].0)
+    }
+
+    /// change the draw order of a mesh
+    ///
+    /// the meshes are sorted again only
+    /// if the key actually changed
+    pub fn set_key(&mut self, idx: Idx, key: SortKey) {
+        self.slots.set_key(idx, key)
+    }
+
+    /// upload the modified meshes
+    ///
+    /// only the vertices of modified meshes are
+    /// uploaded and the indices are regenerated
+    /// only if meshes were added, dropped,
+    /// resized or reordered
+    ///
+    /// the vertex ranges of dropped meshes and
+    /// meshes that were resized are reused
+    pub fn generate(
+        &mut self,
+        target: &mut Target,
+        frame: &mut Frame,
+    ) -> (&'_ VertexBuffer<V>, &'_ IndexBuffer<u32>, u32) {
+        let (spans, indices) = self.slots.update();
+
+        let vbo = self.vbo.get_or_insert_with(|| VertexBuffer::new(target, 0));
+        let ibo = self.ibo.get_or_insert_with(|| IndexBuffer::new(target, 0));
+
+        // grow by copying the old data on the GPU
+        let vbo_len = self.slots.vbo_len as usize;
+        if vbo.capacity() < vbo_len {
+            let capacity = (vbo.capacity() * 2).max(vbo_len);
+            *vbo = vbo.grow(target, frame, capacity);
+        }
+
+        for (start, data) in spans {
+            vbo.upload_at(target, frame, start as _, &data);
+        }
+
+        if let Some(indices) = indices {
+            self.ibo_len = indices.len() as _;
+
+            if ibo.capacity() >= indices.len() {
+                ibo.upload(target, frame, &indices);
+            } else {
+                *ibo = IndexBuffer::new_with(target, &indices);
+            }
+        }
+
+        (vbo, ibo, self.ibo_len)
+    }
+}
+
+impl<M, V> Default for Slots<M, V> {
+    fn default() -> Self {
+        Self {
+            ibo_regen: false,
+
+            vbo_len: 0,
+            vbo_free: Default::default(),
+
             modified: Default::default(),
             free: Default::default(),
             used: Default::default(),
+            ranges: Default::default(),
+
+            keys: Default::default(),
+            next_seq: 0,
+            order: Default::default(),
+            resort: false,
 
             _p: Default::default(),
         }
     }
+}
 
-    pub fn clear(&mut self) {
-        self.ibo_regen = true;
-        self.ibo_len = 0;
-        self.modified = false; //.clear();
-        self.free.clear();
-        self.used.clear();
+impl<M, V> Slots<M, V>
+where
+    M: Mesh<V>,
+    V: Vertex + Copy,
+{
+    fn clear(&mut self) {
+        *self = Self {
+            ibo_regen: true,
+            next_seq: self.next_seq,
+            ..Default::default()
+        };
     }
 
-    pub fn push_with(&mut self, mesh: M) -> Idx {
+    fn push_with_key(&mut self, mesh: M, key: SortKey) -> Idx {
         self.ibo_regen = true;
+        self.resort = true;
+        let seq = self.next_seq;
+        self.next_seq += 1;
         let spot = if let Some(spot) = self.free.pop() {
             self.used[spot] = Some(mesh);
+            self.keys[spot] = (key, seq);
             spot
         } else {
             let spot = self.used.len();
             self.used.push(Some(mesh));
+            self.ranges.push(0..0);
+            self.keys.push((key, seq));
             spot
         };
-        self.modified = true; //.insert(spot);
+        self.modified.insert(spot);
         Idx(spot)
     }
 
-    pub fn push(&mut self) -> Idx
-    where
-        M: Default,
-    {
-        self.push_with(Default::default())
-    }
-
-    pub fn drop(&mut self, idx: Idx) {
-        self.ibo_regen = true;
-        if let Some(m) = self.used.get_mut(idx.0) {
+    fn drop(&mut self, idx: Idx) {
+        if let Some(m @ Some(_)) = self.used.get_mut(idx.0) {
             *m = None;
+            self.ibo_regen = true;
+            self.resort = true;
+            self.modified.remove(&idx.0);
+            self.free.push(idx.0);
+            let range = mem::replace(&mut self.ranges[idx.0], 0..0);
+            self.dealloc(range);
         }
-        self.modified = true; //.insert(idx.0);
-        self.free.push(idx.0);
     }
 
-    pub fn get(&self, idx: Idx) -> Option<&M> {
+    fn get(&self, idx: Idx) -> Option<&M> {
         if let Some(Some(mesh)) = self.used.get(idx.0) {
             Some(mesh)
         } else {
@@ -104,73 +282,309 @@ where
         }
     }
 
-    pub fn get_mut(&mut self, idx: Idx) -> Option<&mut M> {
+    fn get_mut(&mut self, idx: Idx) -> Option<&mut M> {
         if let Some(Some(mesh)) = self.used.get_mut(idx.0) {
-            self.modified = true; //.insert(idx.0);
+            self.modified.insert(idx.0);
             Some(mesh)
         } else {
             None
         }
     }
 
-    pub fn generate(
-        &mut self,
-        target: &mut Target,
-        frame: &mut Frame,
-    ) -> (&'_ VertexBuffer<V>, &'_ IndexBuffer<u32>, u32) {
-        if self.ibo_regen {
-            let mut i = 0;
-            let new_data: Vec<u32> = self
-                .used
-                .iter()
-                .filter_map(|m| m.as_ref())
-                .flat_map(|m| {
-                    let offset = i;
-                    i += m.index_step();
-                    m.indices(offset)
-                })
-                .collect();
-            self.ibo_len = new_data.len() as _;
-
-            // TODO: Copy old data instead of regenerating it
-            if self.ibo.capacity() >= new_data.len() {
-                self.ibo.upload(target, frame, &new_data);
-            } else {
-                self.ibo = IndexBuffer::new_with(target, &new_data);
-            }
+    fn set_key(&mut self, idx: Idx, key: SortKey) {
+        if self.get(idx).is_none() {
+            return;
         }
 
-        if self.modified {
-            let new_data: Vec<V> = self
-                .used
-                .iter()
-                .filter_map(|m| m.as_ref())
-                .flat_map(|s| s.vertices())
-                .collect();
+        let (old, _) = &mut self.keys[idx.0];
+        if *old != key {
+            *old = key;
+            self.resort = true;
+        }
+    }
+
+    // allocates the vertex ranges of the modified meshes
+    fn update(&mut self) -> Changes<V> {
+        let modified = mem::take(&mut self.modified);
+
+        // allocate vertex ranges
+        for &i in modified.iter() {
+            let Some(mesh) = self.used[i].as_ref() else {
+                continue;
+            };
+
+            let len = mesh.index_step();
+            let range = self.ranges[i].clone();
+            let old_len = range.end - range.start;
+            if old_len == len {
+                continue;
+            }
 
-            if self.vbo.capacity() >= new_data.len() {
-                self.vbo.upload(target, frame, &new_data);
+            // the indices point to other vertices now
+            self.ibo_regen = true;
+            if len < old_len {
+                // shrink in place
+                self.ranges[i] = range.start..range.start + len;
+                self.dealloc(range.start + len..range.end);
             } else {
-                self.vbo = VertexBuffer::new_with(target, &new_data);
+                self.dealloc(range);
+                self.ranges[i] = self.alloc(len);
             }
+        }
+
+        // the modified spans,
+        // neighbouring meshes are merged
+        let mut spans: Vec<(u32, Vec<V>)> = vec![];
+        let mut sorted: Vec<(u32, &M)> = modified
+            .iter()
+            .filter_map(|&i| Some((self.ranges[i].start, self.used[i].as_ref()?)))
+            .collect();
+        sorted.sort_unstable_by_key(|(start, _)| *start);
+        for (start, mesh) in sorted {
+            match spans.last_mut() {
+                Some((span_start, data)) if *span_start + data.len() as u32 == start => {
+                    data.extend(mesh.vertices())
+                }
+                _ => spans.push((start, mesh.vertices().collect())),
+            }
+        }
+
+        if self.resort {
+            self.resort = false;
+            self.ibo_regen = true;
 
-            // TODO: Copy old data instead of regenerating it
-            /* if self.max * M::VERTICES >= self.vbo.len() {
-                self.vbo = VertexBuffer::new_with(target, &new_data);
+            let keys = &self.keys;
+            self.order.clear();
+            self.order
+                .extend((0..self.used.len()).filter(|&i| self.used[i].is_some()));
+            self.order.sort_unstable_by(|&a, &b| {
+                let (a_key, a_seq) = keys[a];
+                let (b_key, b_seq) = keys[b];
+                a_key.cmp(&b_key).then(a_seq.cmp(&b_seq))
+            });
+        }
+
+        let indices = if self.ibo_regen {
+            self.ibo_regen = false;
+
+            Some(
+                self.order
+                    .iter()
+                    .filter_map(|&i| Some(self.used[i].as_ref()?.indices(self.ranges[i].start)))
+                    .flatten()
+                    .collect(),
+            )
+        } else {
+            None
+        };
 
-                /* let new = VertexBuffer::empty_dynamic(facade, self.max * M::VERTICES * 2).unwrap();
-                self.vbo.copy_to(&new).unwrap();
-                self.vbo = new; */
+        (spans, indices)
+    }
+
+    // first fit from the freed ranges,
+    // or from the end of the vertex buffer
+    fn alloc(&mut self, len: u32) -> Range<u32> {
+        if len == 0 {
+            return 0..0;
+        }
+
+        if let Some(i) = self
+            .vbo_free
+            .iter()
+            .position(|free| free.end - free.start >= len)
+        {
+            let free = &mut self.vbo_free[i];
+            let range = free.start..free.start + len;
+            free.start += len;
+            if free.start == free.end {
+                self.vbo_free.remove(i);
             }
+            return range;
+        }
 
-            let mut map = self.vbo.map_write();
-            for modified in self.modified.drain() {
-                for (i, vert) in self.used[modified].vertices().enumerate() {
-                    map.set(modified * M::VERTICES + i, vert);
-                }
-            } */
+        let range = self.vbo_len..self.vbo_len + len;
+        self.vbo_len += len;
+        range
+    }
+
+    fn dealloc(&mut self, range: Range<u32>) {
+        if range.start == range.end {
+            return;
+        }
+
+        let i = self
+            .vbo_free
+            .partition_point(|free| free.start < range.start);
+        self.vbo_free.insert(i, range);
+
+        // merge with the neighbours
+        if i + 1 < self.vbo_free.len() && self.vbo_free[i].end == self.vbo_free[i + 1].start {
+            self.vbo_free[i].end = self.vbo_free.remove(i + 1).end;
         }
+        if i > 0 && self.vbo_free[i - 1].end == self.vbo_free[i].start {
+            self.vbo_free[i - 1].end = self.vbo_free.remove(i).end;
+        }
+
+        // give the tail back to the end of the buffer
+        if self
+            .vbo_free
+            .last()
+            .map_or(false, |free| free.end == self.vbo_len)
+        {
+            self.vbo_len = self.vbo_free.pop().unwrap().start;
+        }
+    }
+}
+
+impl SortKey {
+    pub const fn new(layer: i32, depth: f32) -> Self {
+        Self { layer, depth }
+    }
+
+    /// top-down y-sorting: meshes lower on
+    /// the screen are drawn on top
+    pub fn y_sorted(layer: i32, y: f32) -> Self {
+        Self { layer, depth: -y }
+    }
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for SortKey {}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.layer
+            .cmp(&other.layer)
+            .then(self.depth.total_cmp(&other.depth))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{mesh::Mesh, Idx, Slots, SortKey};
+    use crate::prelude::{Color, DefaultVertex};
+    use glam::Vec2;
+    use std::ops::Range;
+    use wgpu::PrimitiveTopology;
+
+    // `len` vertices with the x coordinate set to `id`
+    struct Points {
+        id: f32,
+        len: u32,
+    }
+
+    impl Mesh for Points {
+        const PRIM: PrimitiveTopology = PrimitiveTopology::PointList;
+
+        type VertexIter = std::vec::IntoIter<DefaultVertex>;
+        type IndexIter = Range<u32>;
+
+        fn vertices(&self) -> Self::VertexIter {
+            let vertex = DefaultVertex::new(Vec2::new(self.id, 0.0), Color::WHITE, Vec2::ZERO);
+            vec![vertex; self.len as usize].into_iter()
+        }
+
+        fn indices(&self, offset: u32) -> Self::IndexIter {
+            offset..offset + self.len
+        }
+
+        fn index_step(&self) -> u32 {
+            self.len
+        }
+    }
+
+    fn points(id: f32, len: u32) -> Points {
+        Points { id, len }
+    }
+
+    fn free(slots: &Slots<Points, DefaultVertex>) -> Vec<(u32, u32)> {
+        slots
+            .vbo_free
+            .iter()
+            .map(|free| (free.start, free.end))
+            .collect()
+    }
+
+    fn ranges(slots: &Slots<Points, DefaultVertex>, idx: &[Idx]) -> Vec<Range<u32>> {
+        idx.iter().map(|idx| slots.ranges[idx.0].clone()).collect()
+    }
+
+    #[test]
+    pub fn test_slots() {
+        let mut slots = Slots::default();
+        let a = slots.push_with_key(points(0.0, 2), SortKey::default());
+        let b = slots.push_with_key(points(1.0, 3), SortKey::default());
+        let c = slots.push_with_key(points(2.0, 1), SortKey::default());
+
+        let (spans, indices) = slots.update();
+        assert_eq!(ranges(&slots, &[a, b, c]), [0..2, 2..5, 5..6]);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].1.len(), 6);
+        assert_eq!(indices.unwrap(), (0..6).collect::<Vec<_>>());
+
+        // modify: only the vertices are uploaded
+        slots.get_mut(b).unwrap().id = 4.0;
+        let (spans, indices) = slots.update();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].0, 2);
+        assert_eq!(spans[0].1[0].pos(), Vec2::new(4.0, 0.0));
+        assert!(indices.is_none());
+
+        // shrink: in place and the indices are regenerated only once
+        slots.get_mut(b).unwrap().len = 1;
+        let (_, indices) = slots.update();
+        assert_eq!(ranges(&slots, &[a, b, c]), [0..2, 2..3, 5..6]);
+        assert_eq!(indices.unwrap(), [0, 1, 2, 5]);
+        slots.get_mut(b).unwrap().id = 5.0;
+        assert!(slots.update().1.is_none());
+
+        // grow: the shrunk range is merged with its freed tail
+        slots.get_mut(b).unwrap().len = 3;
+        let (spans, indices) = slots.update();
+        assert_eq!(ranges(&slots, &[a, b, c]), [0..2, 2..5, 5..6]);
+        assert_eq!(spans[0].0, 2);
+        assert_eq!(indices.unwrap(), (0..6).collect::<Vec<_>>());
+
+        // grow: moved to the end, the old range is freed
+        slots.get_mut(a).unwrap().len = 4;
+        slots.update();
+        assert_eq!(ranges(&slots, &[a, b, c]), [6..10, 2..5, 5..6]);
+        assert_eq!(free(&slots), [(0, 2)]);
+
+        // drop: the slot and its range are reused
+        slots.drop(b);
+        let d = slots.push_with_key(points(6.0, 4), SortKey::default());
+        let (_, indices) = slots.update();
+        assert_eq!(d.0, b.0);
+        assert_eq!(ranges(&slots, &[a, d, c]), [6..10, 0..4, 5..6]);
+        assert_eq!(free(&slots), [(4, 5)]);
+        assert_eq!(indices.unwrap(), [6, 7, 8, 9, 5, 0, 1, 2, 3]);
+
+        // dropping the last range shrinks the buffer
+        slots.drop(a);
+        slots.update();
+        assert_eq!(slots.vbo_len, 6);
+        assert_eq!(free(&slots), [(4, 5)]);
 
-        (&self.vbo, &self.ibo, self.ibo_len)
+        // sort keys: only the indices change
+        slots.set_key(c, SortKey::new(1, 0.0));
+        let (spans, indices) = slots.update();
+        assert!(spans.is_empty());
+        assert_eq!(indices.unwrap(), [0, 1, 2, 3, 5]);
+        slots.set_key(c, SortKey::new(1, 0.0));
+        assert!(slots.update().1.is_none());
+        slots.set_key(d, SortKey::new(2, 0.0));
+        assert_eq!(slots.update().1.unwrap(), [5, 0, 1, 2, 3]);
     }
 }
@@ -0,0 +1,304 @@
+use crate::{
+    color::Color,
+    prelude::{DefaultVertex, Mesh, TexturePosition},
+};
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::vec::IntoIter;
+use wgpu::PrimitiveTopology;
+
+//
+
+/// How the borders of a [`NineSlice`] are placed
+/// on a texture and how the rest of it is stretched
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NineSlice {
+    /// border insets in texture pixels
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+
+    /// the size of the sprite in texture pixels
+    pub tex_size: Vec2,
+
+    /// screen pixels per texture pixel
+    /// of the borders and tiles
+    pub scale: f32,
+
+    /// how the edges and the center fill
+    /// the space between the corners
+    pub center: NineSliceCenter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum NineSliceCenter {
+    /// stretch the edges and
+    /// the center of the texture
+    #[default]
+    Stretch,
+
+    /// repeat the edges and the center
+    /// of the texture, the last tiles
+    /// are cut off
+    Tile,
+}
+
+/// A quad that keeps the corners of its texture
+/// unscaled and stretches or tiles the rest
+///
+/// The texture is split into 3x3 parts by the
+/// [`NineSlice`] borders and emitted as one quad
+/// per part, or more if the center is tiled.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NineSliceMesh {
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub col: Color,
+    pub tex: TexturePosition,
+    pub slice: NineSlice,
+}
+
+// positions and texture coordinates
+// of a single row or column of quads
+type Segment = (f32, f32, f32, f32);
+
+//
+
+impl Default for NineSlice {
+    fn default() -> Self {
+        Self {
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+            tex_size: Vec2::ZERO,
+            scale: 1.0,
+            center: NineSliceCenter::Stretch,
+        }
+    }
+}
+
+impl NineSlice {
+    /// the same border on all sides
+    pub fn new(border: f32, tex_size: Vec2) -> Self {
+        Self::new_sides(border, border, border, border, tex_size)
+    }
+
+    pub fn new_sides(left: f32, top: f32, right: f32, bottom: f32, tex_size: Vec2) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            tex_size,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_center(mut self, center: NineSliceCenter) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// no borders, same as a plain quad
+    pub fn is_empty(&self) -> bool {
+        [self.left, self.top, self.right, self.bottom]
+            .iter()
+            .all(|&border| border <= 0.0)
+    }
+}
+
+impl NineSliceMesh {
+    pub fn new_top_left(
+        pos: Vec2,
+        size: Vec2,
+        col: Color,
+        tex: TexturePosition,
+        slice: NineSlice,
+    ) -> Self {
+        Self {
+            pos,
+            size,
+            col,
+            tex,
+            slice,
+        }
+    }
+
+    pub fn new_centered(
+        pos: Vec2,
+        size: Vec2,
+        col: Color,
+        tex: TexturePosition,
+        slice: NineSlice,
+    ) -> Self {
+        Self::new_top_left(pos - size * 0.5, size, col, tex, slice)
+    }
+
+    // same texture orientation as `QuadMesh`:
+    // `pos` is at the bottom of the texture
+    fn segments(&self) -> (Vec<Segment>, Vec<Segment>) {
+        let slice = &self.slice;
+        let tile = slice.center == NineSliceCenter::Tile;
+        let columns = segments(
+            (self.pos.x, self.size.x),
+            (self.tex.top_left.x, self.tex.bottom_right.x),
+            slice.tex_size.x,
+            (slice.left, slice.right),
+            slice.scale,
+            tile,
+        );
+        let rows = segments(
+            (self.pos.y, self.size.y),
+            (self.tex.bottom_right.y, self.tex.top_left.y),
+            slice.tex_size.y,
+            (slice.bottom, slice.top),
+            slice.scale,
+            tile,
+        );
+        (columns, rows)
+    }
+}
+
+impl Mesh<DefaultVertex> for NineSliceMesh {
+    const PRIM: PrimitiveTopology = PrimitiveTopology::TriangleStrip;
+
+    type VertexIter = IntoIter<DefaultVertex>;
+    type IndexIter = IntoIter<u32>;
+
+    fn vertices(&self) -> Self::VertexIter {
+        let (columns, rows) = self.segments();
+        let vertex = |x: f32, y: f32, u: f32, v: f32| {
+            DefaultVertex::new(Vec2::new(x, y), self.col, Vec2::new(u, v))
+        };
+
+        let mut vertices = Vec::with_capacity(columns.len() * rows.len() * 4);
+        for &(y0, y1, v0, v1) in rows.iter() {
+            for &(x0, x1, u0, u1) in columns.iter() {
+                vertices.extend([
+                    vertex(x0, y0, u0, v0),
+                    vertex(x0, y1, u0, v1),
+                    vertex(x1, y0, u1, v0),
+                    vertex(x1, y1, u1, v1),
+                ]);
+            }
+        }
+        vertices.into_iter()
+    }
+
+    fn indices(&self, offset: u32) -> Self::IndexIter {
+        (offset..offset + self.index_step())
+            .step_by(4)
+            .flat_map(|i| [i, i + 1, i + 2, i + 3, !0])
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn index_step(&self) -> u32 {
+        let (columns, rows) = self.segments();
+        (columns.len() * rows.len() * 4) as u32
+    }
+}
+
+// split `pos..pos+len` into the borders and the
+// stretched or tiled middle part
+//
+// the borders are squashed if they don't fit
+fn segments(
+    (pos, len): (f32, f32),
+    (uv_start, uv_end): (f32, f32),
+    tex_len: f32,
+    (border_start, border_end): (f32, f32),
+    scale: f32,
+    tile: bool,
+) -> Vec<Segment> {
+    if tex_len <= 0.0 || len <= 0.0 {
+        return vec![(pos, pos + len, uv_start, uv_end)];
+    }
+
+    let borders = (border_start + border_end) * scale;
+    let squash = if borders > len { len / borders } else { 1.0 };
+    let start = pos + border_start * scale * squash;
+    let end = pos + len - border_end * scale * squash;
+
+    let uv_per_pixel = (uv_end - uv_start) / tex_len;
+    let mid_start = uv_start + border_start * uv_per_pixel;
+    let mid_end = uv_end - border_end * uv_per_pixel;
+
+    let mut segments = vec![];
+    if start > pos {
+        segments.push((pos, start, uv_start, mid_start));
+    }
+
+    let tile_len = (tex_len - border_start - border_end) * scale;
+    if end > start && tile && tile_len > 0.0 {
+        let mut tile_start = start;
+        while tile_start < end {
+            let tile_end = (tile_start + tile_len).min(end);
+            let part = (tile_end - tile_start) / tile_len;
+            segments.push((
+                tile_start,
+                tile_end,
+                mid_start,
+                mid_start + (mid_end - mid_start) * part,
+            ));
+            tile_start = tile_end;
+        }
+    } else if end > start {
+        segments.push((start, end, mid_start, mid_end));
+    }
+
+    if pos + len > end {
+        segments.push((end, pos + len, mid_end, uv_end));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NineSlice, NineSliceCenter, NineSliceMesh};
+    use crate::prelude::{Color, Mesh, TexturePosition};
+    use glam::Vec2;
+
+    #[test]
+    pub fn test_nine_slice() {
+        let slice = NineSlice::new(4.0, Vec2::new(16.0, 16.0));
+        let mesh = NineSliceMesh::new_top_left(
+            Vec2::ZERO,
+            Vec2::new(100.0, 50.0),
+            Color::WHITE,
+            TexturePosition::default(),
+            slice,
+        );
+        assert_eq!(mesh.index_step(), 9 * 4);
+        assert_eq!(mesh.indices(0).len(), 9 * 5);
+
+        // corners keep their size and texture coordinates
+        let vertices: Vec<_> = mesh.vertices().collect();
+        assert_eq!(vertices[1].pos(), Vec2::new(0.0, 4.0));
+        assert_eq!(vertices[1].uv(), Vec2::new(0.0, 0.75));
+        assert_eq!(vertices[35].pos(), Vec2::new(100.0, 50.0));
+        assert_eq!(vertices[35].uv(), Vec2::new(1.0, 0.0));
+
+        // 92 / 8 = 11.5 tiles horizontally and 42 / 8 = 5.25 vertically
+        let tiled = NineSliceMesh {
+            slice: slice.with_center(NineSliceCenter::Tile),
+            ..mesh
+        };
+        assert_eq!(tiled.index_step(), (12 + 2) * (6 + 2) * 4);
+
+        // borders are squashed to fit
+        let small = NineSliceMesh {
+            size: Vec2::new(4.0, 4.0),
+            ..mesh
+        };
+        assert_eq!(small.index_step(), 4 * 4);
+        assert_eq!(small.vertices().nth(3).unwrap().pos(), Vec2::new(2.0, 2.0));
+    }
+}
@@ -0,0 +1,150 @@
+use super::{mesh::Mesh, quad::QuadMesh, BatchRenderer, Idx, SortKey};
+use crate::prelude::{
+    DefaultVertex, Frame, IndexBuffer, PagedTexturePosition, Target, TexturePosition, Vertex,
+    VertexBuffer,
+};
+use serde::{Deserialize, Serialize};
+
+//
+
+/// One [`BatchRenderer`] per texture atlas page
+///
+/// Meshes using different pages of a multi-page
+/// [`crate::packer::texture::TextureAtlasMap`]
+/// can't share a draw call, so each page gets
+/// its own batch. Push meshes to the page of
+/// their [`PagedTexturePosition`] from
+/// [`crate::packer::texture::TextureAtlasMap::get_paged`]
+/// and bind the page texture from
+/// [`crate::packer::texture::TextureAtlasMap::page`]
+/// before drawing each batch.
+#[derive(Debug)]
+pub struct PagedBatchRenderer<M = QuadMesh, V = DefaultVertex>
+where
+    M: Mesh<V>,
+    V: Vertex + Copy,
+{
+    pages: Vec<BatchRenderer<M, V>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PagedIdx {
+    page: u32,
+    idx: Idx,
+}
+
+//
+
+impl<M, V> PagedBatchRenderer<M, V>
+where
+    M: Mesh<V>,
+    V: Vertex + Copy,
+{
+    pub fn new(target: &Target, pages: u32) -> Self {
+        Self {
+            pages: (0..pages).map(|_| BatchRenderer::new(target)).collect(),
+        }
+    }
+
+    /// add batches for new pages
+    ///
+    /// pushing to a new page adds
+    /// the batches automatically
+    pub fn resize(&mut self, target: &Target, pages: u32) {
+        while self.pages.len() < pages as usize {
+            self.pages.push(BatchRenderer::new(target));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for page in self.pages.iter_mut() {
+            page.clear();
+        }
+    }
+
+    pub fn push_with(&mut self, page: u32, mesh: M) -> PagedIdx {
+        self.push_with_key(page, mesh, SortKey::default())
+    }
+
+    /// `page` is usually [`PagedTexturePosition::page`]
+    /// of the texture of `mesh`
+    pub fn push_with_key(&mut self, page: u32, mesh: M, key: SortKey) -> PagedIdx {
+        while self.pages.len() <= page as usize {
+            self.pages.push(BatchRenderer::new_lazy());
+        }
+        let idx = self.pages[page as usize].push_with_key(mesh, key);
+        PagedIdx { page, idx }
+    }
+
+    /// push a mesh to the page of `tex`,
+    /// `mesh` builds it from the position in that page
+    pub fn push_textured(
+        &mut self,
+        tex: PagedTexturePosition,
+        mesh: impl FnOnce(TexturePosition) -> M,
+    ) -> PagedIdx {
+        self.push_with(tex.page, mesh(tex.pos))
+    }
+
+    pub fn drop(&mut self, idx: PagedIdx) {
+        if let Some(page) = self.pages.get_mut(idx.page as usize) {
+            page.drop(idx.idx);
+        }
+    }
+
+    pub fn get(&self, idx: PagedIdx) -> Option<&M> {
+        self.pages.get(idx.page as usize)?.get(idx.idx)
+    }
+
+    pub fn get_mut(&mut self, idx: PagedIdx) -> Option<&mut M> {
+        self.pages.get_mut(idx.page as usize)?.get_mut(idx.idx)
+    }
+
+    pub fn page(&mut self, page: u32) -> Option<&mut BatchRenderer<M, V>> {
+        self.pages.get_mut(page as usize)
+    }
+
+    pub fn page_count(&self) -> u32 {
+        self.pages.len() as _
+    }
+
+    /// upload the modified meshes of every page
+    ///
+    /// the batches are in page order
+    pub fn generate(
+        &mut self,
+        target: &mut Target,
+        frame: &mut Frame,
+    ) -> Vec<(&'_ VertexBuffer<V>, &'_ IndexBuffer<u32>, u32)> {
+        self.pages
+            .iter_mut()
+            .map(|page| page.generate(target, frame))
+            .collect()
+    }
+}
+
+impl PagedIdx {
+    pub fn page(&self) -> u32 {
+        self.page
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PagedBatchRenderer;
+    use crate::prelude::{Color, PagedTexturePosition, QuadMesh, TexturePosition};
+    use glam::Vec2;
+
+    #[test]
+    pub fn test_lazy_pages() {
+        let mut batcher: PagedBatchRenderer = PagedBatchRenderer { pages: vec![] };
+
+        let tex = PagedTexturePosition::new(2, TexturePosition::default());
+        let idx = batcher.push_textured(tex, |tex| {
+            QuadMesh::new_top_left(Vec2::ZERO, Vec2::ONE, Color::WHITE, tex)
+        });
+        assert_eq!(idx.page(), 2);
+        assert_eq!(batcher.page_count(), 3);
+        assert!(batcher.get(idx).is_some());
+    }
+}
@@ -1 +1 @@
-pub use super::{mesh::*, quad::*, *};
+pub use super::{instanced::*, layered::*, mesh::*, nine_slice::*, paged::*, quad::*, *};
@@ -1,9 +1,9 @@
 use crate::{
     color::Color,
-    prelude::{DefaultVertex, Mesh, TexturePosition},
+    prelude::{DefaultVertex, Mesh, SortKey, TexturePosition},
     util::RemapRange,
 };
-use glam::{Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles};
+use glam::{Mat2, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles};
 use std::array::IntoIter;
 use wgpu::PrimitiveTopology;
 
@@ -17,13 +17,54 @@ pub struct QuadMesh {
     pub tex: TexturePosition,
 }
 
+/// A quad that can be rotated, scaled
+/// and flipped around its origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformedQuadMesh {
+    /// position of the origin
+    pub pos: Vec2,
+    pub size: Vec2,
+    /// pivot point relative to the size,
+    /// `(0.5, 0.5)` is the center
+    pub origin: Vec2,
+    pub scale: Vec2,
+    /// counter clockwise rotation
+    /// around the origin in radians
+    pub rotation: f32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub col: Color,
+    pub tex: TexturePosition,
+}
+
+/// A diamond shaped isometric tile
+///
+/// `pos` and `size` are the bounding box of the
+/// diamond and the corners of the texture are
+/// mapped onto the corners of the diamond: the
+/// left, top, bottom and right corners show the
+/// top left, bottom left, top right and bottom
+/// right corners of `tex`
 #[derive(Debug, Clone, Copy, Default)]
 pub struct IsoQuadMesh {
     pub pos: Vec2,
     pub size: Vec2,
     pub col: Color,
-    // TODO:
-    // pub tex: TexturePosition,
+    pub tex: TexturePosition,
+}
+
+/// Conversions between isometric
+/// grid coordinates and world space
+///
+/// The grid x axis points to the bottom right
+/// and the grid y axis to the bottom left
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IsoGrid {
+    /// world position of the
+    /// center of the tile `(0, 0)`
+    pub origin: Vec2,
+    /// bounding box size of a single tile
+    pub tile_size: Vec2,
 }
 
 //
@@ -116,6 +157,177 @@ impl Mesh<DefaultVertex> for QuadMesh {
     }
 }
 
+impl TransformedQuadMesh {
+    /// a quad centered at `pos`
+    pub fn new_centered(pos: Vec2, size: Vec2, col: Color, tex: TexturePosition) -> Self {
+        Self {
+            pos,
+            size,
+            origin: Vec2::splat(0.5),
+            scale: Vec2::ONE,
+            rotation: 0.0,
+            flip_x: false,
+            flip_y: false,
+            col,
+            tex,
+        }
+    }
+
+    /// move the origin without moving the quad
+    pub fn with_origin(mut self, origin: Vec2) -> Self {
+        self.pos +=
+            Mat2::from_angle(self.rotation) * ((origin - self.origin) * self.size * self.scale);
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec2) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+}
+
+impl Default for TransformedQuadMesh {
+    fn default() -> Self {
+        Self::new_centered(Vec2::ZERO, Vec2::ZERO, Color::default(), Default::default())
+    }
+}
+
+impl From<QuadMesh> for TransformedQuadMesh {
+    fn from(quad: QuadMesh) -> Self {
+        Self {
+            pos: quad.pos,
+            origin: Vec2::ZERO,
+            ..Self::new_centered(quad.pos, quad.size, quad.col, quad.tex)
+        }
+    }
+}
+
+impl Mesh<DefaultVertex> for TransformedQuadMesh {
+    const PRIM: PrimitiveTopology = PrimitiveTopology::TriangleStrip;
+
+    type VertexIter = IntoIter<DefaultVertex, 4>;
+    type IndexIter = IntoIter<u32, 5>;
+
+    fn vertices(&self) -> Self::VertexIter {
+        let rotation = Mat2::from_angle(self.rotation);
+        let size = self.size * self.scale;
+        let vertex = |corner: Vec2| {
+            let pos = self.pos + rotation * ((corner - self.origin) * size);
+
+            // same texture orientation as `QuadMesh`
+            let u = if self.flip_x {
+                1.0 - corner.x
+            } else {
+                corner.x
+            };
+            let v = if self.flip_y {
+                1.0 - corner.y
+            } else {
+                corner.y
+            };
+            let tex = Vec2::new(
+                u.remap(0.0..1.0, self.tex.top_left.x..self.tex.bottom_right.x),
+                v.remap(0.0..1.0, self.tex.bottom_right.y..self.tex.top_left.y),
+            );
+
+            DefaultVertex::new(pos, self.col, tex)
+        };
+
+        IntoIterator::into_iter([
+            vertex(Vec2::new(0.0, 0.0)),
+            vertex(Vec2::new(0.0, 1.0)),
+            vertex(Vec2::new(1.0, 0.0)),
+            vertex(Vec2::new(1.0, 1.0)),
+        ])
+    }
+
+    fn indices(&self, offset: u32) -> Self::IndexIter {
+        IntoIterator::into_iter([offset, offset + 1, offset + 2, offset + 3, !0])
+    }
+
+    fn index_step(&self) -> u32 {
+        4
+    }
+}
+
+impl IsoQuadMesh {
+    pub fn new(pos: Vec2, size: Vec2, col: Color, tex: TexturePosition) -> Self {
+        Self {
+            pos,
+            size,
+            col,
+            tex,
+        }
+    }
+}
+
+impl IsoGrid {
+    pub fn new(origin: Vec2, tile_size: Vec2) -> Self {
+        Self { origin, tile_size }
+    }
+
+    /// world position of the center of a tile
+    pub fn to_world(&self, grid: Vec2) -> Vec2 {
+        let half = self.tile_size * 0.5;
+        self.origin + Vec2::new((grid.x - grid.y) * half.x, -(grid.x + grid.y) * half.y)
+    }
+
+    /// grid position at a world position
+    ///
+    /// tile `(x, y)` covers the grid
+    /// positions `x-0.5..x+0.5, y-0.5..y+0.5`
+    ///
+    /// ```
+    /// # use srs2dge_core::{glam::Vec2, prelude::IsoGrid};
+    /// let grid = IsoGrid::new(Vec2::new(4.0, 2.0), Vec2::new(32.0, 16.0));
+    /// let tile = Vec2::new(3.0, -2.0);
+    /// assert_eq!(grid.to_grid(grid.to_world(tile)), tile);
+    /// ```
+    pub fn to_grid(&self, world: Vec2) -> Vec2 {
+        let half = self.tile_size * 0.5;
+        let world = world - self.origin;
+        let a = world.x / half.x; // x - y
+        let b = -world.y / half.y; // x + y
+        Vec2::new(a + b, b - a) * 0.5
+    }
+
+    /// the tile at a world position
+    pub fn tile_at(&self, world: Vec2) -> Vec2 {
+        self.to_grid(world).round()
+    }
+
+    /// a tile mesh at a grid position
+    pub fn tile(&self, grid: Vec2, col: Color, tex: TexturePosition) -> IsoQuadMesh {
+        IsoQuadMesh::new(
+            self.to_world(grid) - self.tile_size * 0.5,
+            self.tile_size,
+            col,
+            tex,
+        )
+    }
+
+    /// draw order of a tile, tiles closer to
+    /// the viewer are drawn on top
+    ///
+    /// compatible with [`SortKey::y_sorted`]
+    /// for objects standing on the tiles
+    pub fn sort_key(&self, layer: i32, grid: Vec2) -> SortKey {
+        SortKey::y_sorted(layer, self.to_world(grid).y)
+    }
+}
+
 impl Mesh<DefaultVertex> for IsoQuadMesh {
     const PRIM: PrimitiveTopology = PrimitiveTopology::TriangleStrip;
 
@@ -123,12 +335,22 @@ impl Mesh<DefaultVertex> for IsoQuadMesh {
     type IndexIter = IntoIter<u32, 5>;
 
     fn vertices(&self) -> Self::VertexIter {
+        let tex = |uv: Vec2| {
+            uv.remap(
+                Vec2::ZERO..Vec2::ONE,
+                self.tex.top_left..self.tex.bottom_right,
+            )
+        };
+        let vertex = |corner: Vec2, uv: Vec2| {
+            DefaultVertex::new(self.pos + self.size * corner, self.col, tex(uv))
+        };
+
         let c = Vec3::new(0.0, 0.5, 1.0);
         IntoIterator::into_iter([
-            DefaultVertex::new(self.pos + self.size * c.xy(), self.col, c.xx()),
-            DefaultVertex::new(self.pos + self.size * c.yz(), self.col, c.xz()),
-            DefaultVertex::new(self.pos + self.size * c.yx(), self.col, c.zx()),
-            DefaultVertex::new(self.pos + self.size * c.zy(), self.col, c.zz()),
+            vertex(c.xy(), c.xx()),
+            vertex(c.yz(), c.xz()),
+            vertex(c.yx(), c.zx()),
+            vertex(c.zy(), c.zz()),
         ])
     }
 
@@ -142,3 +364,56 @@ impl Mesh<DefaultVertex> for IsoQuadMesh {
 }
 
 //
+
+#[cfg(test)]
+mod test {
+    use super::{IsoQuadMesh, TransformedQuadMesh};
+    use crate::prelude::{Color, Mesh, PositionedRect, Rect, TexturePosition};
+    use glam::Vec2;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    pub fn test_origin() {
+        let quad = TransformedQuadMesh::new_centered(
+            Vec2::new(1.0, 2.0),
+            Vec2::new(4.0, 2.0),
+            Color::WHITE,
+            TexturePosition::default(),
+        )
+        .with_scale(Vec2::new(2.0, 1.0))
+        .with_rotation(FRAC_PI_2);
+
+        // moving the origin of a rotated quad keeps the quad in place
+        let moved = quad.with_origin(Vec2::ZERO);
+        assert!((moved.pos - Vec2::new(2.0, -2.0)).length() < 1e-5);
+        for (a, b) in quad.vertices().zip(moved.vertices()) {
+            assert!((a.pos() - b.pos()).length() < 1e-5);
+            assert_eq!(a.uv(), b.uv());
+        }
+
+        // and it rotates around the new origin
+        let rotated = moved.with_rotation(0.0);
+        let bottom_left = rotated.vertices().next().unwrap().pos();
+        assert_eq!(bottom_left, Vec2::new(2.0, -2.0));
+    }
+
+    #[test]
+    pub fn test_iso_uv() {
+        let tile = IsoQuadMesh::new(
+            Vec2::ZERO,
+            Vec2::new(2.0, 1.0),
+            Color::WHITE,
+            TexturePosition::new(Rect::new(4, 4), PositionedRect::new(2, 0, 2, 2)),
+        );
+        let vertices: Vec<_> = tile.vertices().map(|v| (v.pos(), v.uv())).collect();
+        assert_eq!(
+            vertices,
+            [
+                (Vec2::new(0.0, 0.5), Vec2::new(0.5, 0.0)),
+                (Vec2::new(1.0, 1.0), Vec2::new(0.5, 0.5)),
+                (Vec2::new(1.0, 0.0), Vec2::new(1.0, 0.0)),
+                (Vec2::new(2.0, 0.5), Vec2::new(1.0, 0.5)),
+            ]
+        );
+    }
+}
@@ -11,7 +11,9 @@ pub mod ty;
 
 //
 
-const USAGE: u32 = BufferUsages::INDEX.bits() | BufferUsages::COPY_DST.bits();
+// `COPY_SRC` for growing with [`super::Buffer::grow`]
+const USAGE: u32 =
+    BufferUsages::INDEX.bits() | BufferUsages::COPY_DST.bits() | BufferUsages::COPY_SRC.bits();
 
 //
 
@@ -1 +1,105 @@
+use super::{Buffer, BufferSlice};
+use bytemuck::{Pod, Zeroable};
+use std::ops::Range;
+use wgpu::BufferUsages;
 
+//
+
+// `STORAGE` so that compute shaders can write the draw arguments
+const USAGE: u32 =
+    BufferUsages::INDIRECT.bits() | BufferUsages::STORAGE.bits() | BufferUsages::COPY_DST.bits();
+
+//
+
+pub type IndirectBuffer<T = DispatchIndirect> = Buffer<T, USAGE>;
+pub type IndirectBufferSlice<'b, T = DispatchIndirect> = BufferSlice<'b, T, USAGE>;
+
+//
+
+/// The arguments for
+/// [`crate::frame::compute_pass::ComputePass::dispatch_indirect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Zeroable, Pod)]
+#[repr(C)]
+pub struct DispatchIndirect {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+/// The arguments for
+/// [`crate::frame::render_pass::RenderPass::draw_indirect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Zeroable, Pod)]
+#[repr(C)]
+pub struct DrawIndirect {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub base_vertex: u32,
+    pub base_instance: u32,
+}
+
+/// The arguments for
+/// [`crate::frame::render_pass::RenderPass::draw_indexed_indirect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Zeroable, Pod)]
+#[repr(C)]
+pub struct DrawIndexedIndirect {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub base_index: u32,
+    pub vertex_offset: i32,
+    pub base_instance: u32,
+}
+
+//
+
+impl DispatchIndirect {
+    pub const fn new(x: u32, y: u32, z: u32) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl DrawIndirect {
+    /// a reversed range (`start > end`)
+    /// counts as empty
+    pub const fn new(vertices: Range<u32>, instances: Range<u32>) -> Self {
+        Self {
+            vertex_count: vertices.end.saturating_sub(vertices.start),
+            instance_count: instances.end.saturating_sub(instances.start),
+            base_vertex: vertices.start,
+            base_instance: instances.start,
+        }
+    }
+}
+
+impl DrawIndexedIndirect {
+    /// a reversed range (`start > end`)
+    /// counts as empty
+    pub const fn new(indices: Range<u32>, base_vertex: i32, instances: Range<u32>) -> Self {
+        Self {
+            index_count: indices.end.saturating_sub(indices.start),
+            instance_count: instances.end.saturating_sub(instances.start),
+            base_index: indices.start,
+            vertex_offset: base_vertex,
+            base_instance: instances.start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DrawIndexedIndirect, DrawIndirect};
+    use std::ops::Range;
+
+    fn reversed(start: u32, end: u32) -> Range<u32> {
+        Range { start, end }
+    }
+
+    #[test]
+    pub fn test_reversed() {
+        let draw = DrawIndirect::new(reversed(6, 2), reversed(1, 0));
+        assert_eq!((draw.vertex_count, draw.instance_count), (0, 0));
+        assert_eq!((draw.base_vertex, draw.base_instance), (6, 1));
+
+        let draw = DrawIndexedIndirect::new(reversed(3, 1), -2, 0..4);
+        assert_eq!((draw.index_count, draw.instance_count), (0, 4));
+    }
+}
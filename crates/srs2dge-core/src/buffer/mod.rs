@@ -13,6 +13,7 @@ use wgpu::{
 
 pub use index::*;
 pub use indirect::*;
+pub use storage::*;
 pub use uniform::*;
 pub use vertex::*;
 
@@ -21,6 +22,7 @@ pub use vertex::*;
 pub mod index;
 pub mod indirect;
 pub mod prelude;
+pub mod storage;
 pub mod uniform;
 pub mod vertex;
 
@@ -107,6 +109,22 @@ where
         }
     }
 
+    /// create a new buffer with `elements` capacity
+    /// and copy the contents of this buffer into it
+    ///
+    /// the copy happens on the GPU, so the
+    /// buffer usage has to include `COPY_SRC`
+    pub fn grow(&self, target: &Target, frame: &mut Frame, elements: usize) -> Self {
+        let new = Self::new(target, elements);
+        let size = Self::size_of(self.elements.min(elements)) as BufferAddress;
+        // copies have to be aligned
+        let size = size - size % wgpu::COPY_BUFFER_ALIGNMENT;
+        if size != 0 {
+            frame.copy_buffer(&self.buffer, &new.buffer, size);
+        }
+        new
+    }
+
     fn map<'f>(
         &self,
         target: &mut Target,
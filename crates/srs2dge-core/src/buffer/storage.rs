@@ -0,0 +1,25 @@
+use super::{Buffer, BufferSlice};
+use wgpu::BufferUsages;
+
+//
+
+const USAGE: u32 =
+    BufferUsages::STORAGE.bits() | BufferUsages::COPY_DST.bits() | BufferUsages::COPY_SRC.bits();
+
+const READ_ONLY_USAGE: u32 = BufferUsages::STORAGE.bits() | BufferUsages::COPY_DST.bits();
+
+//
+
+/// Read-write storage buffer
+///
+/// `var<storage, read_write>` in wgsl
+///
+/// Can be copied from to read back the results
+pub type StorageBuffer<T> = Buffer<T, USAGE>;
+pub type StorageBufferSlice<'b, T> = BufferSlice<'b, T, USAGE>;
+
+/// Read-only storage buffer
+///
+/// `var<storage, read>` in wgsl
+pub type ReadOnlyStorageBuffer<T> = Buffer<T, READ_ONLY_USAGE>;
+pub type ReadOnlyStorageBufferSlice<'b, T> = BufferSlice<'b, T, READ_ONLY_USAGE>;
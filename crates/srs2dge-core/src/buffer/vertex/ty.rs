@@ -14,6 +14,17 @@ pub struct DefaultVertex {
     col: Color,
 }
 
+/// [`DefaultVertex`] with the layer of
+/// a [`crate::texture::Texture2DArray`]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Zeroable, Pod)]
+#[repr(C)]
+pub struct LayeredVertex {
+    pos: Vec2,
+    uv: Vec2,
+    col: Color,
+    layer: u32,
+}
+
 //
 
 pub trait Vertex: Pod {
@@ -72,6 +83,66 @@ impl Vertex for DefaultVertex {
     }];
 }
 
+impl LayeredVertex {
+    pub fn new(pos: Vec2, col: Color, uv: Vec2, layer: u32) -> Self {
+        Self {
+            pos,
+            uv,
+            col,
+            layer,
+        }
+    }
+
+    pub fn from_default(vertex: DefaultVertex, layer: u32) -> Self {
+        Self::new(vertex.pos, vertex.col, vertex.uv, layer)
+    }
+
+    pub fn pos(&self) -> Vec2 {
+        self.pos
+    }
+
+    pub fn uv(&self) -> Vec2 {
+        self.uv
+    }
+
+    pub fn col(&self) -> Color {
+        self.col
+    }
+
+    pub fn layer(&self) -> u32 {
+        self.layer
+    }
+}
+
+impl Vertex for LayeredVertex {
+    const LAYOUT: &'static [VertexBufferLayout<'static>] = &[VertexBufferLayout {
+        array_stride: 36,
+        step_mode: VertexStepMode::Vertex,
+        attributes: &[
+            VertexAttribute {
+                format: VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 0,
+            },
+            VertexAttribute {
+                format: VertexFormat::Float32x2,
+                offset: 8,
+                shader_location: 1,
+            },
+            VertexAttribute {
+                format: VertexFormat::Float32x4,
+                offset: 16,
+                shader_location: 2,
+            },
+            VertexAttribute {
+                format: VertexFormat::Uint32,
+                offset: 32,
+                shader_location: 3,
+            },
+        ],
+    }];
+}
+
 //
 
 impl Vertex for () {
@@ -0,0 +1,371 @@
+use crate::packer::rect::{PositionedRect, Rect};
+use glam::{Mat4, Vec2};
+use serde::{Deserialize, Serialize};
+use winit::dpi::{PhysicalPosition, Pixel};
+
+//
+
+/// How the [`Camera2D::view_size`] is fit into the window
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ScalingMode {
+    /// keep the aspect ratio and show the
+    /// whole view, the rest of the window
+    /// is outside of [`Camera2D::viewport`]
+    Letterbox,
+
+    /// fill the window and
+    /// ignore the aspect ratio
+    Stretch,
+
+    /// keep the aspect ratio and show the
+    /// whole view and more on one axis
+    #[default]
+    Expand,
+
+    /// like [`Self::Letterbox`] but every texture
+    /// pixel is an integer number of screen pixels
+    /// and the camera snaps to screen pixels
+    IntegerPixel {
+        /// texture pixels per world unit
+        pixels_per_unit: f32,
+    },
+}
+
+/// A 2D camera that produces the MVP matrix
+/// for the shader presets and gizmos
+///
+/// The y axis points up (unless [`Self::y_down`])
+/// and the camera looks at [`Self::position`].
+/// Call [`Self::resize`] whenever the window
+/// size changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Camera2D {
+    /// the world space center of the view
+    pub position: Vec2,
+
+    /// `2.0` shows half of the view
+    pub zoom: f32,
+
+    /// counter clockwise, in radians
+    pub rotation: f32,
+
+    /// the world space area that fits in the window
+    pub view_size: Vec2,
+
+    pub scaling: ScalingMode,
+
+    /// world space min and max corners
+    /// that the view is kept inside of
+    pub bounds: Option<(Vec2, Vec2)>,
+
+    /// the y axis points down like in the
+    /// default projection of the gizmos
+    #[serde(default)]
+    pub y_down: bool,
+
+    #[serde(skip)]
+    window: Rect,
+}
+
+//
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+            view_size: Vec2::new(2.0, 2.0),
+            scaling: ScalingMode::Expand,
+            bounds: None,
+            y_down: false,
+            window: Rect::new(1, 1),
+        }
+    }
+}
+
+impl Camera2D {
+    pub fn new(view_size: Vec2, scaling: ScalingMode) -> Self {
+        Self {
+            view_size,
+            scaling,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_bounds(mut self, min: Vec2, max: Vec2) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    pub fn with_y_down(mut self, y_down: bool) -> Self {
+        self.y_down = y_down;
+        self
+    }
+
+    pub fn with_window_size(mut self, size: impl Into<Rect>) -> Self {
+        self.resize(size);
+        self
+    }
+
+    /// set the window size in physical pixels,
+    /// for example from `WindowState::size`
+    pub fn resize(&mut self, size: impl Into<Rect>) {
+        self.window = size.into();
+    }
+
+    pub fn get_window_size(&self) -> Rect {
+        self.window
+    }
+
+    /// screen pixels per world unit
+    pub fn scale(&self) -> Vec2 {
+        let fit = self.fit();
+        match self.scaling {
+            ScalingMode::Stretch => fit * self.zoom,
+            ScalingMode::Letterbox | ScalingMode::Expand => {
+                Vec2::splat(fit.min_element() * self.zoom)
+            }
+            ScalingMode::IntegerPixel { pixels_per_unit } => {
+                let texel = (fit.min_element() * self.zoom / pixels_per_unit)
+                    .floor()
+                    .max(1.0);
+                Vec2::splat(texel * pixels_per_unit)
+            }
+        }
+    }
+
+    /// the world space size of the whole window
+    pub fn visible_size(&self) -> Vec2 {
+        self.window_size() / self.scale()
+    }
+
+    /// the area of the window in physical pixels
+    /// that shows the view, the rest are the bars
+    /// of [`ScalingMode::Letterbox`] and
+    /// [`ScalingMode::IntegerPixel`]
+    ///
+    /// use it with `RenderPass::set_scissor_rect`
+    /// to hide everything outside of the view
+    pub fn viewport(&self) -> PositionedRect {
+        let window = self.window_size();
+        let fit = self.fit().min_element();
+        let size = match self.scaling {
+            ScalingMode::Stretch | ScalingMode::Expand => window,
+            ScalingMode::Letterbox => self.view_size * fit,
+            ScalingMode::IntegerPixel { pixels_per_unit } => {
+                self.view_size * (fit / pixels_per_unit).floor().max(1.0) * pixels_per_unit
+            }
+        };
+        let size = size.round().min(window);
+        let pos = ((window - size) * 0.5).floor();
+        PositionedRect::new(pos.x as _, pos.y as _, size.x as _, size.y as _)
+    }
+
+    /// the world space center of the view after
+    /// the bounds and pixel snapping are applied
+    pub fn eye(&self) -> Vec2 {
+        let mut eye = self.position;
+
+        if let Some((min, max)) = self.bounds {
+            // the rotated view has to fit
+            let half = self.extent();
+            let clamp = |pos: f32, min: f32, max: f32, half: f32| {
+                if max - min <= half * 2.0 {
+                    (min + max) * 0.5
+                } else {
+                    pos.clamp(min + half, max - half)
+                }
+            };
+            eye = Vec2::new(
+                clamp(eye.x, min.x, max.x, half.x),
+                clamp(eye.y, min.y, max.y, half.y),
+            );
+        }
+
+        if let ScalingMode::IntegerPixel { .. } = self.scaling {
+            // the world origin lands on a screen pixel
+            let half = self.window_size() * 0.5;
+            let scale = self.scale();
+            let flip = self.flip();
+            eye = Vec2::new(
+                (half.x - (half.x - eye.x * scale.x).round()) / scale.x,
+                flip * ((half.y + flip * eye.y * scale.y).round() - half.y) / scale.y,
+            );
+        }
+
+        eye
+    }
+
+    /// the world space min and max corners of
+    /// the visible area, for culling
+    pub fn view_bounds(&self) -> (Vec2, Vec2) {
+        let extent = self.extent();
+        let eye = self.eye();
+        (eye - extent, eye + extent)
+    }
+
+    /// world space to camera space
+    pub fn view(&self) -> Mat4 {
+        Mat4::from_rotation_z(-self.rotation) * Mat4::from_translation(-self.eye().extend(0.0))
+    }
+
+    /// camera space to clip space
+    pub fn projection(&self) -> Mat4 {
+        let half = self.visible_size() * 0.5;
+        let y = half.y * self.flip();
+        Mat4::orthographic_rh(-half.x, half.x, -y, y, -100.0, 100.0)
+    }
+
+    /// the Model View Projection matrix
+    pub fn mvp(&self) -> Mat4 {
+        self.projection() * self.view()
+    }
+
+    /// physical pixels (from the top left
+    /// corner of the window) to world space
+    pub fn screen_to_world<T>(&self, pos: PhysicalPosition<T>) -> Vec2
+    where
+        T: Pixel,
+    {
+        let pos = pos.cast::<f32>();
+        let half = self.window_size() * 0.5;
+        let camera = Vec2::new(pos.x - half.x, (half.y - pos.y) * self.flip()) / self.scale();
+        Vec2::from_angle(self.rotation).rotate(camera) + self.eye()
+    }
+
+    /// world space to physical pixels
+    /// (from the top left corner of the window)
+    pub fn world_to_screen(&self, pos: Vec2) -> PhysicalPosition<f32> {
+        let half = self.window_size() * 0.5;
+        let camera = Vec2::from_angle(-self.rotation).rotate(pos - self.eye()) * self.scale();
+        PhysicalPosition::new(half.x + camera.x, half.y - camera.y * self.flip())
+    }
+
+    fn window_size(&self) -> Vec2 {
+        Vec2::new(self.window.width as _, self.window.height as _).max(Vec2::ONE)
+    }
+
+    // half of the size of the rotated visible area
+    // along the world axes
+    fn extent(&self) -> Vec2 {
+        let half = self.visible_size() * 0.5;
+        let rotation = Vec2::from_angle(self.rotation);
+        rotation
+            .rotate(half)
+            .abs()
+            .max(rotation.rotate(half * Vec2::new(1.0, -1.0)).abs())
+    }
+
+    // the direction of the screen y axis in world space
+    fn flip(&self) -> f32 {
+        if self.y_down {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    // scale that fits the view in the window
+    fn fit(&self) -> Vec2 {
+        self.window_size() / self.view_size.max(Vec2::splat(f32::EPSILON))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Camera2D, ScalingMode};
+    use crate::packer::rect::{PositionedRect, Rect};
+    use glam::{Mat4, Vec2, Vec4Swizzles};
+    use winit::dpi::PhysicalPosition;
+
+    #[test]
+    pub fn test_camera() {
+        let camera = Camera2D::default()
+            .with_window_size(Rect::new(800, 400))
+            .with_position(Vec2::new(3.0, -1.0))
+            .with_rotation(0.5)
+            .with_zoom(2.0);
+        assert_eq!(camera.visible_size(), Vec2::new(2.0, 1.0));
+
+        // screen and world conversions match the mvp
+        let world = camera.screen_to_world(PhysicalPosition::new(600, 100));
+        let screen = camera.world_to_screen(world);
+        assert!((screen.x - 600.0).abs() < 1e-3 && (screen.y - 100.0).abs() < 1e-3);
+        let ndc = (camera.mvp() * world.extend(0.0).extend(1.0)).xy();
+        assert!((ndc - Vec2::new(0.5, 0.5)).length() < 1e-4);
+
+        // letterbox bars on the sides
+        let camera = Camera2D::new(Vec2::new(4.0, 3.0), ScalingMode::Letterbox)
+            .with_window_size(Rect::new(1000, 600));
+        assert_eq!(camera.viewport(), PositionedRect::new(100, 0, 800, 600));
+        assert_eq!(camera.visible_size(), Vec2::new(5.0, 3.0));
+
+        let camera = Camera2D {
+            scaling: ScalingMode::Stretch,
+            ..camera
+        };
+        assert_eq!(camera.visible_size(), Vec2::new(4.0, 3.0));
+
+        // 3 screen pixels per texture pixel
+        let camera = Camera2D::new(
+            Vec2::new(320.0, 180.0),
+            ScalingMode::IntegerPixel {
+                pixels_per_unit: 1.0,
+            },
+        )
+        .with_window_size(Rect::new(1000, 600))
+        .with_position(Vec2::new(0.1, 0.0));
+        assert_eq!(camera.scale(), Vec2::splat(3.0));
+        assert_eq!(camera.viewport(), PositionedRect::new(20, 30, 960, 540));
+        assert_eq!(camera.eye(), Vec2::ZERO);
+
+        // kept inside of the bounds
+        let camera = Camera2D::default()
+            .with_window_size(Rect::new(400, 400))
+            .with_bounds(Vec2::new(-5.0, -1.0), Vec2::new(5.0, 1.0))
+            .with_position(Vec2::new(10.0, 3.0));
+        assert_eq!(camera.eye(), Vec2::new(4.0, 0.0));
+        assert_eq!(
+            camera.view_bounds(),
+            (Vec2::new(3.0, -1.0), Vec2::new(5.0, 1.0))
+        );
+
+        // the rotated view is kept inside of the bounds too
+        let camera = camera
+            .with_bounds(Vec2::new(-5.0, -5.0), Vec2::new(5.0, 5.0))
+            .with_position(Vec2::new(10.0, 10.0))
+            .with_rotation(0.5);
+        let (min, max) = camera.view_bounds();
+        assert!((max.x - 5.0).abs() < 1e-5 && (max.y - 5.0).abs() < 1e-5);
+        assert!(min.x > -5.0 && min.y > -5.0);
+
+        // y down matches the default gizmos projection
+        let camera = Camera2D::default()
+            .with_window_size(Rect::new(800, 400))
+            .with_y_down(true);
+        assert_eq!(
+            camera.mvp(),
+            Mat4::orthographic_rh(-2.0, 2.0, 1.0, -1.0, -100.0, 100.0)
+        );
+        let world = camera.screen_to_world(PhysicalPosition::new(600, 100));
+        assert_eq!(world, Vec2::new(1.0, -0.5));
+        let screen = camera.world_to_screen(world);
+        assert_eq!((screen.x, screen.y), (600.0, 100.0));
+    }
+}
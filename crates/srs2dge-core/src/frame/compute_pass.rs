@@ -1,17 +1,172 @@
+use crate::{
+    buffer::{DispatchIndirect, IndirectBuffer},
+    shader::ComputeShader,
+};
+use std::mem;
 use tracing::span::EnteredSpan;
+use wgpu::BindGroup;
 
 //
 
-pub struct ComputePass<'e> {
-    _inner: wgpu::ComputePass<'e>,
+pub struct ComputePass<'e, const PIPELINE_BOUND: bool = false> {
+    pub(crate) inner: wgpu::ComputePass<'e>,
 
-    _span: EnteredSpan,
+    // tracing
+    span: EnteredSpan,
 }
 
 //
 
-impl<'e> ComputePass<'e> {
-    pub(crate) fn new(_inner: wgpu::ComputePass<'e>, _span: EnteredSpan) -> Self {
-        Self { _inner, _span }
+impl<'e, const PIPELINE_BOUND: bool> ComputePass<'e, PIPELINE_BOUND> {
+    pub fn bind_shader<'s>(mut self, shader: &'s ComputeShader) -> ComputePass<'e, true>
+    where
+        's: 'e,
+    {
+        self.inner.set_pipeline(&shader.pipeline);
+        self.pass()
+    }
+
+    pub fn bind_group<'g>(self, bind_group: &'g BindGroup) -> Self
+    where
+        'g: 'e,
+    {
+        self.bind_group_at(0, bind_group)
+    }
+
+    /// bind `bind_group` to `@group(index)`
+    pub fn bind_group_at<'g>(mut self, index: u32, bind_group: &'g BindGroup) -> Self
+    where
+        'g: 'e,
+    {
+        self.inner.set_bind_group(index, bind_group, &[]);
+        self.pass()
+    }
+
+    pub fn done(self) -> ComputePass<'e> {
+        self.pass()
+    }
+
+    pub(crate) fn new(inner: wgpu::ComputePass<'e>, span: EnteredSpan) -> Self {
+        Self { inner, span }
+    }
+
+    fn pass<const N: bool>(self) -> ComputePass<'e, N> {
+        ComputePass {
+            inner: self.inner,
+            span: self.span,
+        }
+    }
+}
+
+// implement for all computepasses where a shader is bound
+impl<'e> ComputePass<'e, true> {
+    /// dispatch `x * y * z` workgroups
+    pub fn dispatch(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.inner.dispatch_workgroups(x, y, z);
+        self
+    }
+
+    /// dispatch workgroups with the
+    /// [`DispatchIndirect`] arguments read
+    /// from `buffer` at index `offset`
+    pub fn dispatch_indirect<'b>(
+        mut self,
+        buffer: &'b IndirectBuffer<DispatchIndirect>,
+        offset: u64,
+    ) -> Self
+    where
+        'b: 'e,
+    {
+        self.inner.dispatch_workgroups_indirect(
+            buffer.inner(),
+            offset * mem::size_of::<DispatchIndirect>() as u64,
+        );
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{ReadOnlyStorageBuffer, StorageBuffer},
+        prelude::{ComputeShader, ShaderModule},
+        Engine,
+    };
+    use tokio::sync::oneshot::channel;
+    use wgpu::{
+        BindGroupDescriptor, BindGroupEntry, BufferDescriptor, BufferUsages, MapMode,
+        RequestAdapterOptions,
+    };
+
+    const SHADER: &str = "
+        @group(0) @binding(0) var<storage, read> input: array<u32>;
+        @group(1) @binding(0) var<storage, read_write> output: array<u32>;
+
+        @compute @workgroup_size(4)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            output[id.x] = input[id.x] * 2u;
+        }
+    ";
+
+    #[test]
+    pub fn test_dispatch() {
+        crate::init_tokio().block_on(async {
+            let engine = Engine::new();
+            let adapter = engine
+                .get_instance()
+                .request_adapter(&RequestAdapterOptions::default())
+                .await;
+            if adapter.is_none() {
+                tracing::warn!("No GPU, skipping the compute dispatch test");
+                return;
+            }
+
+            let mut target = engine.new_target_headless().await;
+            let module = ShaderModule::new_wgsl_source(&target, SHADER.into()).unwrap();
+            let shader = ComputeShader::builder()
+                .with_compute(&module, "main")
+                .build(&target);
+
+            let input = ReadOnlyStorageBuffer::new_with(&target, &[1u32, 2, 3, 4]);
+            let output = StorageBuffer::<u32>::new(&target, 4);
+            let bind_group = |index: u32, buffer: &wgpu::Buffer| {
+                target.device.create_bind_group(&BindGroupDescriptor {
+                    label: None,
+                    layout: &shader.bind_group_layout_at(index),
+                    entries: &[BindGroupEntry {
+                        binding: 0,
+                        resource: buffer.as_entire_binding(),
+                    }],
+                })
+            };
+            let (input_group, output_group) =
+                (bind_group(0, input.inner()), bind_group(1, output.inner()));
+
+            let read_buffer = target.device.create_buffer(&BufferDescriptor {
+                label: None,
+                size: 16,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let mut frame = target.get_frame();
+            frame
+                .compute_pass()
+                .bind_shader(&shader)
+                .bind_group_at(0, &input_group)
+                .bind_group_at(1, &output_group)
+                .dispatch(1, 1, 1);
+            frame.copy_buffer(output.inner(), &read_buffer, 16);
+            frame.primary_render_pass();
+            drop(frame);
+
+            let range = read_buffer.slice(..);
+            let (tx, rx) = channel();
+            range.map_async(MapMode::Read, |o| tx.send(o).unwrap());
+            rx.await.unwrap().unwrap();
+
+            let result: Vec<u32> = bytemuck::cast_slice(&range.get_mapped_range()).to_vec();
+            assert_eq!(result, [2, 4, 6, 8]);
+        });
     }
 }
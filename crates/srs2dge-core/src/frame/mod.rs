@@ -3,7 +3,10 @@ use crate::{
     color::Color,
     label,
     target::{prelude::BeltPool, surface::Surface},
-    texture::{has_render_attachment, Texture},
+    texture::{
+        has_render_attachment, DepthStencilTexture, MultisampleTexture, RenderTargetTexture,
+        Texture, DEPTH_STENCIL_FORMAT,
+    },
 };
 use std::{
     ops::{Deref, DerefMut},
@@ -12,9 +15,9 @@ use std::{
 use tracing::{debug, debug_span, span::EnteredSpan, warn};
 use wgpu::{
     util::StagingBelt, Buffer, BufferAddress, BufferSize, BufferViewMut, CommandEncoder,
-    CommandEncoderDescriptor, ComputePassDescriptor, Device, LoadOp, Operations, Queue,
-    RenderPassColorAttachment, RenderPassDescriptor, SurfaceTexture, TextureFormat, TextureView,
-    TextureViewDescriptor,
+    CommandEncoderDescriptor, ComputePassDescriptor, Device, Features, LoadOp, Operations, Queue,
+    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+    SurfaceTexture, TextureFormat, TextureView, TextureViewDescriptor,
 };
 
 //
@@ -27,11 +30,20 @@ pub mod render_pass;
 
 pub struct Frame {
     // render target
-    main_texture: SafeDrop<SurfaceTexture>,
+    // (`None` with headless targets)
+    main_texture: Option<SurfaceTexture>,
     main_view: TextureView,
     main_format: TextureFormat,
     main_dim: (u32, u32),
 
+    // depth/stencil attachment
+    // (`None` if not enabled in the target)
+    depth_stencil: Option<TextureView>,
+
+    // MSAA attachment, resolved into main_view
+    // (`None` if not enabled in the target)
+    multisample: Option<(TextureView, u32)>,
+
     // command buffer
     encoder: SafeDrop<CommandEncoder>,
 
@@ -44,6 +56,9 @@ pub struct Frame {
     // state
     has_primary: bool,
 
+    // device supports `MULTI_DRAW_INDIRECT`
+    multi_draw: bool,
+
     // tracing
     _span: EnteredSpan,
 
@@ -72,9 +87,57 @@ impl Frame {
         let main_format = surface.format();
         let main_dim = surface.get_dim();
 
+        Self::new_inner(
+            device,
+            queue,
+            (Some(main_texture), main_view, main_format, main_dim),
+            belts,
+            _span,
+        )
+    }
+
+    /// a frame that draws into an
+    /// offscreen texture instead of a window
+    pub fn new_headless(
+        device: &Device,
+        queue: Arc<Queue>,
+        texture: &RenderTargetTexture,
+        belts: Arc<BeltPool>,
+        frame_id: usize,
+    ) -> Self {
+        let _span = debug_span!("Begin headless frame", frame_id).entered();
+
+        let main_view = texture.texture.create_view(&TextureViewDescriptor {
+            label: label!(),
+            ..Default::default()
+        });
+        let main_format = texture.get_format();
+        let main_dim = texture.get_dim().into();
+
+        Self::new_inner(
+            device,
+            queue,
+            (None, main_view, main_format, main_dim),
+            belts,
+            _span,
+        )
+    }
+
+    fn new_inner(
+        device: &Device,
+        queue: Arc<Queue>,
+        (main_texture, main_view, main_format, main_dim): (
+            Option<SurfaceTexture>,
+            TextureView,
+            TextureFormat,
+            (u32, u32),
+        ),
+        belts: Arc<BeltPool>,
+        _span: EnteredSpan,
+    ) -> Self {
         let encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: label!() });
+        let multi_draw = device.features().contains(Features::MULTI_DRAW_INDIRECT);
 
-        let main_texture = main_texture.into();
         let encoder = encoder.into();
         let belt = belts.recv().into();
 
@@ -84,6 +147,10 @@ impl Frame {
             main_format,
             main_dim,
 
+            depth_stencil: None,
+
+            multisample: None,
+
             encoder,
 
             queue,
@@ -92,6 +159,8 @@ impl Frame {
 
             has_primary: false,
 
+            multi_draw,
+
             _span,
 
             belt,
@@ -108,24 +177,58 @@ impl Frame {
     pub fn primary_render_pass(&mut self) -> RenderPass<(), (), (), (), false> {
         let span = debug_span!("Primary render pass").entered();
 
-        let pass = self.encoder.begin_render_pass(&RenderPassDescriptor {
-            label: label!(),
-            color_attachments: &[Some(RenderPassColorAttachment {
-                view: &self.main_view,
-                resolve_target: None,
-                ops: Operations {
-                    load: LoadOp::Clear(self.clear_color.into()),
-                    store: true,
-                },
-            })],
-            depth_stencil_attachment: None,
-        });
+        let (color, sample_count) = Self::primary_views(&self.main_view, &self.multisample);
+        let pass = Self::begin_render_pass(&mut self.encoder, color, self.clear_color, None);
 
         debug!("Created");
 
         self.has_primary = true;
 
-        RenderPass::new(pass, self.main_format, span)
+        RenderPass::new(
+            pass,
+            self.main_format,
+            None,
+            sample_count,
+            self.multi_draw,
+            span,
+        )
+    }
+
+    /// primary render pass with the depth/stencil
+    /// attachment of the [`crate::target::Target`]
+    ///
+    /// returns `None` if depth/stencil was not enabled with
+    /// [`crate::target::Target::set_depth_stencil`]
+    pub fn primary_render_pass_with_depth_stencil(
+        &mut self,
+    ) -> Option<RenderPass<(), (), (), (), false>> {
+        let span = debug_span!("Primary render pass with depth/stencil").entered();
+
+        let Some(depth_stencil) = self.depth_stencil.as_ref() else {
+            debug!("Discarded");
+            return None;
+        };
+
+        let (color, sample_count) = Self::primary_views(&self.main_view, &self.multisample);
+        let pass = Self::begin_render_pass(
+            &mut self.encoder,
+            color,
+            self.clear_color,
+            Some(depth_stencil),
+        );
+
+        debug!("Created");
+
+        self.has_primary = true;
+
+        Some(RenderPass::new(
+            pass,
+            self.main_format,
+            Some(DEPTH_STENCIL_FORMAT),
+            sample_count,
+            self.multi_draw,
+            span,
+        ))
     }
 
     pub fn secondary_render_pass<'a, const USAGE: u32>(
@@ -144,22 +247,53 @@ where
             return None;
         }
 
-        let pass = self.encoder.begin_render_pass(&RenderPassDescriptor {
-            label: label!(),
-            color_attachments: &[Some(RenderPassColorAttachment {
-                view: target,
-                resolve_target: None,
-                ops: Operations {
-                    load: LoadOp::Clear(self.clear_color.into()),
-                    store: true,
-                },
-            })],
-            depth_stencil_attachment: None,
-        });
+        let pass =
+            Self::begin_render_pass(&mut self.encoder, (target, None), self.clear_color, None);
+
+        debug!("Created");
+
+        Some(RenderPass::new(
+            pass,
+            target.get_format(),
+            None,
+            target.get_sample_count(),
+            self.multi_draw,
+            span,
+        ))
+    }
+
+    /// secondary render pass with a depth/stencil attachment
+    ///
+    /// `depth_stencil` has to be as large as `target`
+    pub fn secondary_render_pass_with_depth_stencil<'a, const USAGE: u32>(
+        &'a mut self,
+        target: &'a Texture<USAGE>,
+        depth_stencil: &'a DepthStencilTexture,
+    ) -> Option<RenderPass<'a, (), (), (), (), false>> {
+        let span = debug_span!("Secondary render pass with depth/stencil").entered();
+
+        if !has_render_attachment(USAGE) {
+            debug!("Discarded");
+            return None;
+        }
+
+        let pass = Self::begin_render_pass(
+            &mut self.encoder,
+            (target, None),
+            self.clear_color,
+            Some(depth_stencil),
+        );
 
         debug!("Created");
 
-        Some(RenderPass::new(pass, target.get_format(), span))
+        Some(RenderPass::new(
+            pass,
+            target.get_format(),
+            Some(DEPTH_STENCIL_FORMAT),
+            target.get_sample_count(),
+            self.multi_draw,
+            span,
+        ))
     }
 
     pub fn compute_pass(&mut self) -> ComputePass {
@@ -178,6 +312,67 @@ where
         self.main_dim
     }
 
+    pub(crate) fn set_depth_stencil(&mut self, depth_stencil: &DepthStencilTexture) {
+        self.depth_stencil = Some(depth_stencil.texture.create_view(&TextureViewDescriptor {
+            label: label!(),
+            ..Default::default()
+        }));
+    }
+
+    pub(crate) fn set_multisample(&mut self, multisample: &MultisampleTexture) {
+        let view = multisample.texture.create_view(&TextureViewDescriptor {
+            label: label!(),
+            ..Default::default()
+        });
+        self.multisample = Some((view, multisample.get_sample_count()));
+    }
+
+    /// the view to draw into, the view to resolve
+    /// into and the sample count of primary passes
+    fn primary_views<'a>(
+        main_view: &'a TextureView,
+        multisample: &'a Option<(TextureView, u32)>,
+    ) -> ((&'a TextureView, Option<&'a TextureView>), u32) {
+        match multisample {
+            Some((view, sample_count)) => ((view, Some(main_view)), *sample_count),
+            None => ((main_view, None), 1),
+        }
+    }
+
+    fn begin_render_pass<'a>(
+        encoder: &'a mut CommandEncoder,
+        (view, resolve_target): (&'a TextureView, Option<&'a TextureView>),
+        clear_color: Color,
+        depth_stencil: Option<&'a TextureView>,
+    ) -> wgpu::RenderPass<'a> {
+        encoder.begin_render_pass(&RenderPassDescriptor {
+            label: label!(),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: Operations {
+                    load: LoadOp::Clear(clear_color.into()),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: depth_stencil.map(|view| RenderPassDepthStencilAttachment {
+                view,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: Some(Operations {
+                    load: LoadOp::Clear(0),
+                    store: true,
+                }),
+            }),
+        })
+    }
+
+    pub(crate) fn copy_buffer(&mut self, from: &Buffer, to: &Buffer, size: BufferAddress) {
+        self.encoder.copy_buffer_to_buffer(from, 0, to, 0, size);
+    }
+
     pub(crate) fn write_buffer(
         &mut self,
         target: &Buffer,
@@ -211,7 +406,9 @@ impl Drop for Frame {
         // submit
         self.queue.submit([encoder.finish()]);
         // self.queue.on_submitted_work_done(|| main_texture.present());
-        main_texture.present();
+        if let Some(main_texture) = main_texture {
+            main_texture.present();
+        }
 
         // reset
         self.belts.send(belt);
@@ -1,8 +1,13 @@
 use crate::{
-    buffer::{index::Index, IndexBuffer, Vertex, VertexBuffer},
+    buffer::{
+        index::Index, DrawIndexedIndirect, DrawIndirect, IndexBuffer, IndirectBuffer, Vertex,
+        VertexBuffer,
+    },
+    packer::rect::PositionedRect,
     shader::Shader,
 };
-use std::{marker::PhantomData, ops::Range};
+use bytemuck::Pod;
+use std::{marker::PhantomData, mem, ops::Range};
 use tracing::span::EnteredSpan;
 use wgpu::{BindGroup, TextureFormat};
 
@@ -11,6 +16,9 @@ use wgpu::{BindGroup, TextureFormat};
 pub struct RenderPass<'e, Sv = (), Bv = (), Si = (), Bi = (), const PIPELINE_BOUND: bool = false> {
     pub(crate) inner: wgpu::RenderPass<'e>,
     pub(crate) format: TextureFormat,
+    pub(crate) depth_stencil: Option<TextureFormat>,
+    pub(crate) sample_count: u32,
+    multi_draw: bool,
 
     // tracing
     span: EnteredSpan,
@@ -36,6 +44,25 @@ impl<'e, Sv, Bv, Si, Bi, const PIPELINE_BOUND: bool>
         self.pass()
     }
 
+    /// bind the per vertex `vertices` to slot 0
+    /// and the per instance `instances` to slot 1
+    ///
+    /// see [`crate::batch::instanced::InstancedBatch`]
+    pub fn bind_instanced_vbo<'b, Q, T>(
+        mut self,
+        vertices: &'b VertexBuffer<Q>,
+        instances: &'b VertexBuffer<T>,
+    ) -> RenderPass<'e, Sv, T, Si, Bi, PIPELINE_BOUND>
+    where
+        'b: 'e,
+        Q: Pod,
+        T: Vertex + 'static,
+    {
+        self.inner.set_vertex_buffer(0, vertices.inner().slice(..));
+        self.inner.set_vertex_buffer(1, instances.inner().slice(..));
+        self.pass()
+    }
+
     pub fn bind_ibo<'b, T>(
         mut self,
         buffer: &'b IndexBuffer<T>,
@@ -60,6 +87,10 @@ impl<'e, Sv, Bv, Si, Bi, const PIPELINE_BOUND: bool>
     {
         if self.format != shader.format {
             panic!("Shader output incompatible with this render target");
+        } else if self.depth_stencil != shader.depth_stencil {
+            panic!("Shader depth/stencil state incompatible with this render pass");
+        } else if self.sample_count != shader.sample_count {
+            panic!("Shader sample count incompatible with this render pass");
         } else {
             self.inner.set_pipeline(&shader.pipeline);
         }
@@ -74,6 +105,21 @@ impl<'e, Sv, Bv, Si, Bi, const PIPELINE_BOUND: bool>
         self.pass()
     }
 
+    /// the reference value for stencil tests
+    pub fn set_stencil_reference(mut self, reference: u32) -> Self {
+        self.inner.set_stencil_reference(reference);
+        self
+    }
+
+    /// draw only inside of `rect` (in physical pixels)
+    ///
+    /// see [`crate::camera::Camera2D::viewport`]
+    pub fn set_scissor_rect(mut self, rect: PositionedRect) -> Self {
+        self.inner
+            .set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+        self
+    }
+
     pub fn done(self) -> RenderPass<'e> {
         self.pass()
     }
@@ -81,11 +127,17 @@ impl<'e, Sv, Bv, Si, Bi, const PIPELINE_BOUND: bool>
     pub(crate) fn new(
         inner: wgpu::RenderPass<'e>,
         format: TextureFormat,
+        depth_stencil: Option<TextureFormat>,
+        sample_count: u32,
+        multi_draw: bool,
         span: EnteredSpan,
     ) -> Self {
         Self {
             inner,
             format,
+            depth_stencil,
+            sample_count,
+            multi_draw,
             span,
             _p: PhantomData::default(),
         }
@@ -95,6 +147,9 @@ impl<'e, Sv, Bv, Si, Bi, const PIPELINE_BOUND: bool>
         RenderPass {
             inner: self.inner,
             format: self.format,
+            depth_stencil: self.depth_stencil,
+            sample_count: self.sample_count,
+            multi_draw: self.multi_draw,
             span: self.span,
             _p: PhantomData::default(),
         }
@@ -118,12 +173,99 @@ impl<'e, V, I> RenderPass<'e, V, V, I, I, true> {
         self
     }
 
-    // TODO:
-    /* pub fn draw_indirect(&mut self, vertices: Range<u32>, instances: Range<u32>) {
-        self.inner.draw_indirect(vertices, instances)
+    /// draw with the [`DrawIndirect`] arguments
+    /// read from `buffer` at index `offset`
+    pub fn draw_indirect<'b>(
+        mut self,
+        buffer: &'b IndirectBuffer<DrawIndirect>,
+        offset: u64,
+    ) -> Self
+    where
+        'b: 'e,
+    {
+        self.inner
+            .draw_indirect(buffer.inner(), Self::byte_offset::<DrawIndirect>(offset));
+        self
+    }
+
+    /// draw with the [`DrawIndexedIndirect`] arguments
+    /// read from `buffer` at index `offset`
+    pub fn draw_indexed_indirect<'b>(
+        mut self,
+        buffer: &'b IndirectBuffer<DrawIndexedIndirect>,
+        offset: u64,
+    ) -> Self
+    where
+        'b: 'e,
+    {
+        self.inner.draw_indexed_indirect(
+            buffer.inner(),
+            Self::byte_offset::<DrawIndexedIndirect>(offset),
+        );
+        self
+    }
+
+    /// [`Self::draw_indirect`] `count` times with
+    /// consecutive arguments starting from index `offset`
+    ///
+    /// falls back to separate draw calls if
+    /// the device doesn't support multi draw
+    pub fn multi_draw_indirect<'b>(
+        mut self,
+        buffer: &'b IndirectBuffer<DrawIndirect>,
+        offset: u64,
+        count: u32,
+    ) -> Self
+    where
+        'b: 'e,
+    {
+        if self.multi_draw {
+            self.inner.multi_draw_indirect(
+                buffer.inner(),
+                Self::byte_offset::<DrawIndirect>(offset),
+                count,
+            );
+        } else {
+            for i in offset..offset + count as u64 {
+                self.inner
+                    .draw_indirect(buffer.inner(), Self::byte_offset::<DrawIndirect>(i));
+            }
+        }
+        self
+    }
+
+    /// [`Self::draw_indexed_indirect`] `count` times with
+    /// consecutive arguments starting from index `offset`
+    ///
+    /// falls back to separate draw calls if
+    /// the device doesn't support multi draw
+    pub fn multi_draw_indexed_indirect<'b>(
+        mut self,
+        buffer: &'b IndirectBuffer<DrawIndexedIndirect>,
+        offset: u64,
+        count: u32,
+    ) -> Self
+    where
+        'b: 'e,
+    {
+        if self.multi_draw {
+            self.inner.multi_draw_indexed_indirect(
+                buffer.inner(),
+                Self::byte_offset::<DrawIndexedIndirect>(offset),
+                count,
+            );
+        } else {
+            for i in offset..offset + count as u64 {
+                self.inner.draw_indexed_indirect(
+                    buffer.inner(),
+                    Self::byte_offset::<DrawIndexedIndirect>(i),
+                );
+            }
+        }
+        self
     }
 
-    pub fn draw_indirect_indexed(&mut self, vertices: Range<u32>, instances: Range<u32>) {
-        self.inner.draw(vertices, instances)
-    } */
+    fn byte_offset<T>(offset: u64) -> u64 {
+        offset * mem::size_of::<T>() as u64
+    }
 }
@@ -0,0 +1,399 @@
+//! Render graph for multi-pass frames
+//!
+//! Passes declare the transient textures they read
+//! and the one texture they write. Every texture
+//! (including the swapchain) has at most one writer,
+//! so each pass sees the finished output of the
+//! passes it reads from. The graph allocates
+//! the textures, resizes them with the window, orders
+//! the passes and records them into a single [`Frame`].
+//!
+//! Usage in pseudocode:
+//! ```ignore
+//! let mut graph = RenderGraph::new()
+//!     .with_texture("scene", None, 1.0)
+//!     .with_texture("blur", None, 0.5)
+//!     .with_pass("scene", &[], "scene", ScenePass { .. })
+//!     .with_pass("blur", &["scene"], "blur", BlurPass { .. })
+//!     .with_composite("crt", &["scene", "blur"], CrtPass { .. });
+//!
+//! let mut frame = target.get_frame();
+//! graph.draw(&mut target, &mut frame).unwrap();
+//! ```
+
+use crate::{
+    frame::{render_pass::RenderPass, Frame},
+    packer::rect::Rect,
+    target::Target,
+    texture::RenderTargetTexture,
+};
+use std::collections::{BTreeSet, HashMap};
+use wgpu::TextureFormat;
+
+//
+
+/// The name of the swapchain (or the headless
+/// render target) in a [`RenderGraph`]
+pub const SWAPCHAIN: &str = "swapchain";
+
+//
+
+/// A single node in a [`RenderGraph`]
+pub trait GraphPass {
+    /// called right before [`Self::draw`]
+    ///
+    /// upload uniforms and create the bind
+    /// groups for this frame here, the
+    /// transient textures might have been
+    /// recreated since the last frame
+    fn prepare(&mut self, _target: &mut Target, _frame: &mut Frame, _textures: &GraphTextures) {}
+
+    /// record the draw commands
+    ///
+    /// the render pass already targets
+    /// the written texture
+    fn draw<'e>(&'e self, pass: RenderPass<'e>, textures: &'e GraphTextures);
+}
+
+//
+
+/// Transient textures owned by a [`RenderGraph`]
+#[derive(Debug, Default)]
+pub struct GraphTextures {
+    textures: HashMap<String, TransientTexture>,
+}
+
+/// Passes and transient textures
+/// that make up a frame
+#[derive(Default)]
+pub struct RenderGraph {
+    textures: GraphTextures,
+    passes: Vec<PassNode>,
+
+    // cached pass order
+    order: Option<Vec<usize>>,
+}
+
+//
+
+#[derive(Debug)]
+struct TransientTexture {
+    // `None` = the target format
+    format: Option<TextureFormat>,
+    // relative to the window size
+    scale: f32,
+
+    texture: Option<RenderTargetTexture>,
+}
+
+struct PassNode {
+    name: String,
+    reads: Vec<String>,
+    writes: String,
+    pass: Box<dyn GraphPass>,
+}
+
+//
+
+impl GraphTextures {
+    /// get a transient texture
+    ///
+    /// `None` if it doesn't exist or
+    /// before the first frame
+    pub fn get(&self, name: &str) -> Option<&RenderTargetTexture> {
+        self.textures.get(name)?.texture.as_ref()
+    }
+
+    fn resize(&mut self, target: &Target, dim: Rect) {
+        let default_format = target.get_format();
+
+        for (name, transient) in self.textures.iter_mut() {
+            let format = transient.format.unwrap_or(default_format);
+            let dim = Rect::new(
+                ((dim.width as f32 * transient.scale) as u32).max(1),
+                ((dim.height as f32 * transient.scale) as u32).max(1),
+            );
+
+            let outdated = transient
+                .texture
+                .as_ref()
+                .map(|texture| (texture.get_dim(), texture.get_format()))
+                != Some((dim, format));
+            if outdated {
+                tracing::debug!("Allocating transient texture '{name}' {dim:?}");
+                transient.texture = Some(RenderTargetTexture::new_format(
+                    target,
+                    dim,
+                    format,
+                    Some(name),
+                ));
+            }
+        }
+    }
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add a transient texture
+    ///
+    /// `format` defaults to the target format and
+    /// `scale` is relative to the window size
+    pub fn with_texture(
+        mut self,
+        name: impl Into<String>,
+        format: Option<TextureFormat>,
+        scale: f32,
+    ) -> Self {
+        self.textures.textures.insert(
+            name.into(),
+            TransientTexture {
+                format,
+                scale,
+                texture: None,
+            },
+        );
+        self
+    }
+
+    /// add a pass that reads from the `reads` textures
+    /// and renders into the `writes` texture
+    ///
+    /// `writes` can be [`SWAPCHAIN`], only one
+    /// pass can write each texture
+    pub fn with_pass(
+        mut self,
+        name: impl Into<String>,
+        reads: &[&str],
+        writes: impl Into<String>,
+        pass: impl GraphPass + 'static,
+    ) -> Self {
+        self.passes.push(PassNode {
+            name: name.into(),
+            reads: reads.iter().map(|s| s.to_string()).collect(),
+            writes: writes.into(),
+            pass: Box::new(pass),
+        });
+        self.order = None;
+        self
+    }
+
+    /// add the final pass that reads from
+    /// the `reads` textures and renders
+    /// into the swapchain
+    ///
+    /// the composite pass is always recorded last
+    /// and there can only be one
+    pub fn with_composite(
+        self,
+        name: impl Into<String>,
+        reads: &[&str],
+        pass: impl GraphPass + 'static,
+    ) -> Self {
+        self.with_pass(name, reads, SWAPCHAIN, pass)
+    }
+
+    pub fn get_textures(&self) -> &GraphTextures {
+        &self.textures
+    }
+
+    /// names of the passes in the order they are recorded
+    pub fn order(&mut self) -> Result<Vec<&str>, String> {
+        let order = self.sorted()?.to_vec();
+        Ok(order
+            .into_iter()
+            .map(|i| self.passes[i].name.as_str())
+            .collect())
+    }
+
+    /// resize the transient textures
+    /// and record all passes
+    pub fn draw(&mut self, target: &mut Target, frame: &mut Frame) -> Result<(), String> {
+        let order = self.sorted()?.to_vec();
+
+        self.textures.resize(target, frame.get_dim().into());
+
+        for i in order {
+            let node = &mut self.passes[i];
+            node.pass.prepare(target, frame, &self.textures);
+
+            let pass = if node.writes == SWAPCHAIN {
+                frame.primary_render_pass()
+            } else {
+                let texture = self
+                    .textures
+                    .get(&node.writes)
+                    .ok_or_else(|| format!("Transient texture '{}' is missing", node.writes))?;
+                frame
+                    .secondary_render_pass(texture)
+                    .ok_or("Transient texture is not a render attachment")?
+            };
+
+            node.pass.draw(pass, &self.textures);
+        }
+
+        Ok(())
+    }
+
+    fn sorted(&mut self) -> Result<&[usize], String> {
+        if self.order.is_none() {
+            self.order = Some(self.sort()?);
+        }
+        Ok(self.order.as_deref().unwrap())
+    }
+
+    // Kahn's algorithm, ties are
+    // broken by the insertion order
+    fn sort(&self) -> Result<Vec<usize>, String> {
+        let exists = |name: &str| self.textures.textures.contains_key(name);
+
+        for (i, node) in self.passes.iter().enumerate() {
+            if let Some(other) = self.passes[..i]
+                .iter()
+                .find(|other| other.writes == node.writes)
+            {
+                return Err(if node.writes == SWAPCHAIN {
+                    format!(
+                        "Passes '{}' and '{}' are both composite passes",
+                        other.name, node.name
+                    )
+                } else {
+                    format!(
+                        "Passes '{}' and '{}' both write texture '{}'",
+                        other.name, node.name, node.writes
+                    )
+                });
+            }
+            if node.writes != SWAPCHAIN && !exists(&node.writes) {
+                return Err(format!(
+                    "Pass '{}' writes unknown texture '{}'",
+                    node.name, node.writes
+                ));
+            }
+            if let Some(read) = node.reads.iter().find(|read| !exists(read)) {
+                return Err(format!(
+                    "Pass '{}' reads unknown texture '{read}'",
+                    node.name
+                ));
+            }
+            if node.reads.contains(&node.writes) {
+                return Err(format!(
+                    "Pass '{}' reads and writes the same texture '{}'",
+                    node.name, node.writes
+                ));
+            }
+        }
+
+        // dependencies[i] = passes that have to be recorded before pass i
+        //
+        // every texture has a single writer, which is recorded before
+        // all of its readers, so no pass can overwrite a texture that
+        // another pass still has to read (no write after read hazards)
+        let dependencies: Vec<BTreeSet<usize>> = self
+            .passes
+            .iter()
+            .map(|node| {
+                self.passes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| {
+                        node.reads.contains(&other.writes)
+                            || (node.writes == SWAPCHAIN && other.writes != SWAPCHAIN)
+                    })
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+
+        let mut order = Vec::with_capacity(self.passes.len());
+        let mut done = vec![false; self.passes.len()];
+        while order.len() != self.passes.len() {
+            let next = (0..self.passes.len())
+                .find(|&i| !done[i] && dependencies[i].iter().all(|&dep| done[dep]))
+                .ok_or("Render graph has a cycle")?;
+
+            done[next] = true;
+            order.push(next);
+        }
+
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GraphPass, GraphTextures, RenderGraph};
+    use crate::frame::render_pass::RenderPass;
+
+    struct Empty;
+
+    impl GraphPass for Empty {
+        fn draw<'e>(&'e self, _: RenderPass<'e>, _: &'e GraphTextures) {}
+    }
+
+    #[test]
+    pub fn test_order() {
+        let mut graph = RenderGraph::new()
+            .with_texture("scene", None, 1.0)
+            .with_texture("blur", None, 0.5)
+            .with_composite("crt", &["scene", "blur"], Empty)
+            .with_pass("blur", &["scene"], "blur", Empty)
+            .with_pass("scene", &[], "scene", Empty);
+
+        assert_eq!(graph.order().unwrap(), ["scene", "blur", "crt"]);
+    }
+
+    #[test]
+    pub fn test_invalid() {
+        let mut graph = RenderGraph::new()
+            .with_texture("a", None, 1.0)
+            .with_texture("b", None, 1.0)
+            .with_pass("a", &["b"], "a", Empty)
+            .with_pass("b", &["a"], "b", Empty);
+        assert!(graph.order().is_err());
+
+        let mut graph = RenderGraph::new().with_composite("crt", &["missing"], Empty);
+        assert!(graph.order().is_err());
+    }
+
+    #[test]
+    pub fn test_single_writer() {
+        // the second writer would overwrite
+        // "scene" while "blur" still reads it
+        let mut graph = RenderGraph::new()
+            .with_texture("scene", None, 1.0)
+            .with_texture("blur", None, 1.0)
+            .with_pass("scene", &[], "scene", Empty)
+            .with_pass("blur", &["scene"], "blur", Empty)
+            .with_pass("overlay", &["blur"], "scene", Empty)
+            .with_composite("crt", &["scene"], Empty);
+        assert_eq!(
+            graph.order(),
+            Err("Passes 'scene' and 'overlay' both write texture 'scene'".to_string())
+        );
+
+        let mut graph = RenderGraph::new()
+            .with_texture("scene", None, 1.0)
+            .with_pass("scene", &[], "scene", Empty)
+            .with_composite("crt", &["scene"], Empty)
+            .with_composite("ui", &[], Empty);
+        assert_eq!(
+            graph.order(),
+            Err("Passes 'crt' and 'ui' are both composite passes".to_string())
+        );
+
+        // readers of a texture always see the finished
+        // output of its writer, even if added before it
+        let mut graph = RenderGraph::new()
+            .with_texture("scene", None, 1.0)
+            .with_texture("blur", None, 1.0)
+            .with_texture("bloom", None, 1.0)
+            .with_pass("bloom", &["scene", "blur"], "bloom", Empty)
+            .with_pass("blur", &["scene"], "blur", Empty)
+            .with_pass("scene", &[], "scene", Empty)
+            .with_composite("crt", &["bloom"], Empty);
+        assert_eq!(graph.order().unwrap(), ["scene", "blur", "bloom", "crt"]);
+    }
+}
@@ -8,10 +8,13 @@
 
 use cfg_if::cfg_if;
 use main_game_loop::event::EventLoopTarget;
+use packer::rect::Rect;
 use std::sync::{Arc, RwLock};
 use target::Target;
 use tokio::runtime::{Builder, Runtime};
-use wgpu::{util::backend_bits_from_env, Adapter, Backends, Device, Instance, Queue};
+use wgpu::{
+    util::backend_bits_from_env, Adapter, Backends, Device, Instance, Queue, TextureFormat,
+};
 use winit::{
     error::OsError,
     window::{Window, WindowBuilder},
@@ -33,13 +36,18 @@ pub use winit;
 
 pub mod batch;
 pub mod buffer;
+pub mod camera;
 pub mod color;
 pub mod frame;
+pub mod graph;
 pub mod packer;
 pub mod prelude;
 pub mod shader;
+#[cfg(any(test, feature = "snapshot"))]
+pub mod snapshot;
 pub mod target;
 pub mod texture;
+pub mod tilemap;
 pub mod util;
 
 //
@@ -196,6 +204,20 @@ impl Engine {
         Target::new_headless(self.instance.clone(), self.device_storage.clone()).await
     }
 
+    /// Create a new render target that doesn't require a window
+    ///
+    /// Frames are drawn into an offscreen
+    /// texture with the given size and format
+    pub async fn new_target_headless_with(&self, dim: Rect, format: TextureFormat) -> Target {
+        Target::new_headless_with(
+            self.instance.clone(),
+            self.device_storage.clone(),
+            dim,
+            format,
+        )
+        .await
+    }
+
     /// returns the wgpu instance
     pub fn get_instance(&self) -> Arc<Instance> {
         self.instance.clone()
@@ -0,0 +1,339 @@
+//! Selectable packing heuristics
+
+use super::{
+    guillotine::GuillotinePacker,
+    maxrects::MaxRectsPacker,
+    packer2d::Packer,
+    rect::{PositionedRect, Rect},
+    skyline::SkylinePacker,
+};
+
+//
+
+/// A rect packing heuristic
+pub trait RectPacker {
+    fn area(&self) -> Rect;
+
+    /// Push a rectangle into this packer.
+    fn push(&mut self, rect: Rect) -> Option<PositionedRect>;
+
+    /// Free the area of a pushed rectangle.
+    fn remove(&mut self, rect: PositionedRect);
+
+    /// Expand the area to the right and to the bottom.
+    fn alloc_more(&mut self, rect: Rect);
+}
+
+//
+
+/// The heuristic used by a [`DynPacker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PackerAlgorithm {
+    /// [`Packer`], rows of rects
+    #[default]
+    Rows,
+
+    /// [`MaxRectsPacker`], best-short-side-fit
+    MaxRects,
+
+    /// [`SkylinePacker`], bottom-left
+    Skyline,
+
+    /// [`GuillotinePacker`], best-area-fit
+    Guillotine,
+}
+
+/// Occupancy statistics of a [`DynPacker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PackerStats {
+    /// the whole packing area
+    pub area: Rect,
+
+    /// pixels covered by packed rects (without padding)
+    pub used: u64,
+
+    /// number of packed rects
+    pub rects: usize,
+}
+
+/// A packer with a heuristic selected at runtime
+///
+/// Also keeps track of [`PackerStats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynPacker {
+    inner: Inner,
+    used: u64,
+    rects: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Inner {
+    Rows(Packer, bool),
+    MaxRects(MaxRectsPacker),
+    Skyline(SkylinePacker),
+    Guillotine(GuillotinePacker),
+}
+
+//
+
+impl PackerStats {
+    /// the fraction of the area covered by packed rects
+    pub fn occupancy(&self) -> f32 {
+        let area = self.area.width as u64 * self.area.height as u64;
+        if area == 0 {
+            0.0
+        } else {
+            (self.used as f64 / area as f64) as f32
+        }
+    }
+}
+
+impl Default for DynPacker {
+    fn default() -> Self {
+        Self::new(PackerAlgorithm::default(), Rect::default(), 0, false)
+    }
+}
+
+impl DynPacker {
+    /// `rotation` allows rotating rects by 90°,
+    /// rotated rects are returned with their
+    /// width and height swapped
+    pub fn new(algorithm: PackerAlgorithm, rect: Rect, padding: u8, rotation: bool) -> Self {
+        let inner = match algorithm {
+            PackerAlgorithm::Rows => Inner::Rows(Packer::new(rect).with_padding(padding), rotation),
+            PackerAlgorithm::MaxRects => Inner::MaxRects(
+                MaxRectsPacker::new(rect)
+                    .with_padding(padding)
+                    .with_rotation(rotation),
+            ),
+            PackerAlgorithm::Skyline => Inner::Skyline(
+                SkylinePacker::new(rect)
+                    .with_padding(padding)
+                    .with_rotation(rotation),
+            ),
+            PackerAlgorithm::Guillotine => Inner::Guillotine(
+                GuillotinePacker::new(rect)
+                    .with_padding(padding)
+                    .with_rotation(rotation),
+            ),
+        };
+        Self {
+            inner,
+            used: 0,
+            rects: 0,
+        }
+    }
+
+    pub fn algorithm(&self) -> PackerAlgorithm {
+        match self.inner {
+            Inner::Rows(..) => PackerAlgorithm::Rows,
+            Inner::MaxRects(_) => PackerAlgorithm::MaxRects,
+            Inner::Skyline(_) => PackerAlgorithm::Skyline,
+            Inner::Guillotine(_) => PackerAlgorithm::Guillotine,
+        }
+    }
+
+    pub fn stats(&self) -> PackerStats {
+        PackerStats {
+            area: self.area(),
+            used: self.used,
+            rects: self.rects,
+        }
+    }
+
+    /// Expands each side length to be the next power of two of the biggest side.
+    ///
+    /// see [`Packer::next_pow2_square`]
+    pub fn next_pow2_square(&mut self) {
+        let area = self.area();
+        let side = (area.width.max(area.height) + 1).next_power_of_two();
+        self.alloc_more(Rect::new(side - area.width, side - area.height));
+    }
+
+    /// Repeatedly pushes a rect until it succeeds.
+    /// With each fail, it expands the area.
+    ///
+    /// It will stop if any side length reaches this limit.
+    pub fn push_until(&mut self, rect: Rect, limit: u16) -> Option<PositionedRect> {
+        loop {
+            match self.push(rect) {
+                Some(pos) => return Some(pos),
+                None => {
+                    let lim = limit as u32;
+                    let area = self.area();
+                    if area.width >= lim || area.height >= lim {
+                        return None;
+                    }
+                    self.next_pow2_square();
+                }
+            }
+        }
+    }
+
+    fn inner(&self) -> &dyn RectPacker {
+        match &self.inner {
+            Inner::Rows(packer, _) => packer,
+            Inner::MaxRects(packer) => packer,
+            Inner::Skyline(packer) => packer,
+            Inner::Guillotine(packer) => packer,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn RectPacker {
+        match &mut self.inner {
+            Inner::Rows(packer, _) => packer,
+            Inner::MaxRects(packer) => packer,
+            Inner::Skyline(packer) => packer,
+            Inner::Guillotine(packer) => packer,
+        }
+    }
+}
+
+impl RectPacker for DynPacker {
+    fn area(&self) -> Rect {
+        self.inner().area()
+    }
+
+    fn push(&mut self, rect: Rect) -> Option<PositionedRect> {
+        let pos = match &mut self.inner {
+            // rows only rotate when the rect doesn't fit otherwise
+            Inner::Rows(packer, true) => packer
+                .push(rect)
+                .or_else(|| packer.push(Rect::new(rect.height, rect.width))),
+            _ => self.inner_mut().push(rect),
+        }?;
+
+        if pos.width != 0 && pos.height != 0 {
+            self.used += pos.width as u64 * pos.height as u64;
+            self.rects += 1;
+        }
+        Some(pos)
+    }
+
+    fn remove(&mut self, rect: PositionedRect) {
+        if rect.width != 0 && rect.height != 0 {
+            self.used = self
+                .used
+                .saturating_sub(rect.width as u64 * rect.height as u64);
+            self.rects = self.rects.saturating_sub(1);
+        }
+        self.inner_mut().remove(rect);
+    }
+
+    fn alloc_more(&mut self, rect: Rect) {
+        self.inner_mut().alloc_more(rect);
+    }
+}
+
+impl RectPacker for Packer {
+    fn area(&self) -> Rect {
+        Packer::area(self)
+    }
+
+    fn push(&mut self, rect: Rect) -> Option<PositionedRect> {
+        Packer::push(self, rect)
+    }
+
+    fn remove(&mut self, rect: PositionedRect) {
+        Packer::remove(self, rect)
+    }
+
+    fn alloc_more(&mut self, rect: Rect) {
+        Packer::alloc_more(self, rect)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DynPacker, PackerAlgorithm, RectPacker};
+    use crate::packer::rect::{PositionedRect, Rect};
+    use rand::Rng;
+
+    const ALGORITHMS: [PackerAlgorithm; 4] = [
+        PackerAlgorithm::Rows,
+        PackerAlgorithm::MaxRects,
+        PackerAlgorithm::Skyline,
+        PackerAlgorithm::Guillotine,
+    ];
+
+    fn overlaps(a: &PositionedRect, b: &PositionedRect) -> bool {
+        a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+    }
+
+    #[test]
+    pub fn test_no_overlap() {
+        let mut rng = rand::thread_rng();
+        for algorithm in ALGORITHMS {
+            for rotation in [false, true] {
+                let mut packer = DynPacker::new(algorithm, Rect::new(500, 500), 2, rotation);
+                let mut packed: Vec<PositionedRect> = vec![];
+                for _ in 0..200 {
+                    let rect = Rect::new(rng.gen_range(1..80), rng.gen_range(1..80));
+                    let Some(pos) = packer.push(rect) else {
+                        continue;
+                    };
+
+                    assert!(
+                        pos.rect() == rect
+                            || (rotation && pos.rect() == Rect::new(rect.height, rect.width)),
+                        "{algorithm:?}"
+                    );
+                    assert!(pos.x + pos.width + 2 <= 500 && pos.y + pos.height + 2 <= 500);
+                    assert!(
+                        packed.iter().all(|other| !overlaps(other, &pos)),
+                        "{algorithm:?} overlap"
+                    );
+                    packed.push(pos);
+                }
+
+                let used: u64 = packed
+                    .iter()
+                    .map(|r| r.width as u64 * r.height as u64)
+                    .sum();
+                assert_eq!(packer.stats().used, used);
+                assert_eq!(packer.stats().rects, packed.len());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_remove_reuse() {
+        for algorithm in ALGORITHMS {
+            let mut packer = DynPacker::new(algorithm, Rect::new(20, 20), 0, false);
+            packer.push(Rect::new(20, 10)).unwrap();
+            let b = packer.push(Rect::new(20, 10)).unwrap();
+            assert_eq!(packer.push(Rect::new(20, 10)), None, "{algorithm:?}");
+            assert_eq!(packer.stats().occupancy(), 1.0);
+
+            packer.remove(b);
+            assert!(
+                packer.push(Rect::new(20, 10)).is_some(),
+                "{algorithm:?} did not reuse the removed area"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_rotation() {
+        for algorithm in ALGORITHMS {
+            let mut packer = DynPacker::new(algorithm, Rect::new(10, 20), 0, true);
+            let pos = packer.push(Rect::new(20, 10)).unwrap();
+            assert_eq!(pos, PositionedRect::new(0, 0, 10, 20), "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    pub fn test_push_until() {
+        for algorithm in ALGORITHMS {
+            let mut packer = DynPacker::new(algorithm, Rect::new(0, 0), 0, false);
+            assert!(packer.push_until(Rect::new(30, 30), 1024).is_some());
+            assert_eq!(packer.area(), Rect::new(32, 32));
+            assert_eq!(packer.push_until(Rect::new(2000, 2000), 1024), None);
+
+            // both sides grow to the same size
+            let mut packer = DynPacker::new(algorithm, Rect::new(100, 50), 0, false);
+            packer.next_pow2_square();
+            assert_eq!(packer.area(), Rect::new(128, 128), "{algorithm:?}");
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Guillotine packer with the best-area-fit heuristic
+
+use super::{
+    algorithm::RectPacker,
+    rect::{PositionedRect, Rect},
+};
+
+//
+
+/// Packs rects into the smallest free rect
+/// that fits and splits the leftover space
+/// with a single cut along the shorter axis
+///
+/// Faster than [`super::maxrects::MaxRectsPacker`]
+/// and reuses removed areas well.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GuillotinePacker {
+    rect: Rect,
+    free: Vec<PositionedRect>,
+    pub padding: u8,
+    pub rotation: bool,
+}
+
+//
+
+impl GuillotinePacker {
+    pub fn new(rect: Rect) -> Self {
+        let free = if rect.width != 0 && rect.height != 0 {
+            vec![rect.positioned(0, 0)]
+        } else {
+            vec![]
+        };
+        Self {
+            rect,
+            free,
+            padding: 0,
+            rotation: false,
+        }
+    }
+
+    pub fn with_padding(mut self, padding: u8) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// allow rotating rects by 90°
+    ///
+    /// rotated rects are returned with
+    /// their width and height swapped
+    pub fn with_rotation(mut self, rotation: bool) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    fn push_free(&mut self, rect: PositionedRect) {
+        if rect.width != 0 && rect.height != 0 {
+            self.free.push(rect);
+        }
+    }
+
+    // merge free rects that share a whole edge
+    fn merge(&mut self) {
+        let mut merged = true;
+        while merged {
+            merged = false;
+            'outer: for i in 0..self.free.len() {
+                for j in 0..self.free.len() {
+                    if i == j {
+                        continue;
+                    }
+                    let (a, b) = (self.free[i], self.free[j]);
+
+                    let joined = if a.y == b.y && a.height == b.height && a.x + a.width == b.x {
+                        Some(PositionedRect::new(a.x, a.y, a.width + b.width, a.height))
+                    } else if a.x == b.x && a.width == b.width && a.y + a.height == b.y {
+                        Some(PositionedRect::new(a.x, a.y, a.width, a.height + b.height))
+                    } else {
+                        None
+                    };
+
+                    if let Some(joined) = joined {
+                        self.free[i] = joined;
+                        self.free.swap_remove(j);
+                        merged = true;
+                        break 'outer;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl RectPacker for GuillotinePacker {
+    fn area(&self) -> Rect {
+        self.rect
+    }
+
+    fn push(&mut self, rect: Rect) -> Option<PositionedRect> {
+        if rect.width == 0 || rect.height == 0 {
+            return Some(rect.positioned(0, 0));
+        }
+
+        let pad = self.padding as u32;
+        let orientations = [
+            (rect.width + pad, rect.height + pad, false),
+            (rect.height + pad, rect.width + pad, true),
+        ];
+        let orientations = if self.rotation && rect.width != rect.height {
+            &orientations[..]
+        } else {
+            &orientations[..1]
+        };
+
+        let (_, i, (w, h, rotated)) = self
+            .free
+            .iter()
+            .enumerate()
+            .flat_map(|(i, free)| orientations.iter().map(move |o| (i, free, *o)))
+            .filter(|(_, free, (w, h, _))| free.width >= *w && free.height >= *h)
+            .map(|(i, free, o)| {
+                let leftover = free.width as u64 * free.height as u64 - o.0 as u64 * o.1 as u64;
+                (leftover, i, o)
+            })
+            .min_by_key(|(leftover, i, _)| (*leftover, *i))?;
+
+        let free = self.free.swap_remove(i);
+        let (lw, lh) = (free.width - w, free.height - h);
+
+        // cut along the shorter leftover axis
+        if lw < lh {
+            self.push_free(PositionedRect::new(free.x + w, free.y, lw, h));
+            self.push_free(PositionedRect::new(free.x, free.y + h, free.width, lh));
+        } else {
+            self.push_free(PositionedRect::new(free.x + w, free.y, lw, free.height));
+            self.push_free(PositionedRect::new(free.x, free.y + h, w, lh));
+        }
+
+        let rect = if rotated {
+            Rect::new(rect.height, rect.width)
+        } else {
+            rect
+        };
+        Some(rect.positioned(free.x, free.y))
+    }
+
+    fn remove(&mut self, rect: PositionedRect) {
+        let pad = self.padding as u32;
+        let width = (rect.width + pad).min(self.rect.width.saturating_sub(rect.x));
+        let height = (rect.height + pad).min(self.rect.height.saturating_sub(rect.y));
+
+        self.push_free(PositionedRect::new(rect.x, rect.y, width, height));
+        self.merge();
+    }
+
+    fn alloc_more(&mut self, rect: Rect) {
+        let old = self.rect;
+        self.rect.width += rect.width;
+        self.rect.height += rect.height;
+
+        self.push_free(PositionedRect::new(old.width, 0, rect.width, old.height));
+        self.push_free(PositionedRect::new(
+            0,
+            old.height,
+            self.rect.width,
+            rect.height,
+        ));
+        self.merge();
+    }
+}
@@ -0,0 +1,242 @@
+//! MaxRects packer with the best-short-side-fit heuristic
+
+use super::{
+    algorithm::RectPacker,
+    rect::{PositionedRect, Rect},
+};
+
+//
+
+/// Packs rects into the free rect that
+/// leaves the shortest leftover side
+///
+/// Tracks all maximal free rects, which makes it
+/// the densest but also the slowest of the packers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MaxRectsPacker {
+    rect: Rect,
+    free: Vec<PositionedRect>,
+    pub padding: u8,
+    pub rotation: bool,
+}
+
+//
+
+impl MaxRectsPacker {
+    pub fn new(rect: Rect) -> Self {
+        let free = if rect.width != 0 && rect.height != 0 {
+            vec![rect.positioned(0, 0)]
+        } else {
+            vec![]
+        };
+        Self {
+            rect,
+            free,
+            padding: 0,
+            rotation: false,
+        }
+    }
+
+    pub fn with_padding(mut self, padding: u8) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// allow rotating rects by 90°
+    ///
+    /// rotated rects are returned with
+    /// their width and height swapped
+    pub fn with_rotation(mut self, rotation: bool) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// mark an area as used without pushing
+    ///
+    /// `rect` is padded like pushed rects
+    pub fn reserve(&mut self, rect: PositionedRect) {
+        let pad = self.padding as u32;
+        self.place(PositionedRect::new(
+            rect.x,
+            rect.y,
+            rect.width + pad,
+            rect.height + pad,
+        ));
+    }
+
+    fn place(&mut self, used: PositionedRect) {
+        let mut split = Vec::with_capacity(self.free.len() + 4);
+        for free in self.free.drain(..) {
+            if !intersects(&free, &used) {
+                split.push(free);
+                continue;
+            }
+
+            // left
+            if used.x > free.x {
+                split.push(PositionedRect::new(
+                    free.x,
+                    free.y,
+                    used.x - free.x,
+                    free.height,
+                ));
+            }
+            // right
+            if used.x + used.width < free.x + free.width {
+                split.push(PositionedRect::new(
+                    used.x + used.width,
+                    free.y,
+                    free.x + free.width - used.x - used.width,
+                    free.height,
+                ));
+            }
+            // top
+            if used.y > free.y {
+                split.push(PositionedRect::new(
+                    free.x,
+                    free.y,
+                    free.width,
+                    used.y - free.y,
+                ));
+            }
+            // bottom
+            if used.y + used.height < free.y + free.height {
+                split.push(PositionedRect::new(
+                    free.x,
+                    used.y + used.height,
+                    free.width,
+                    free.y + free.height - used.y - used.height,
+                ));
+            }
+        }
+        self.free = split;
+        self.prune();
+    }
+
+    // remove free rects that are inside of other free rects
+    fn prune(&mut self) {
+        let mut i = 0;
+        while i < self.free.len() {
+            let a = self.free[i];
+            let contained = self
+                .free
+                .iter()
+                .enumerate()
+                .any(|(j, b)| i != j && contains(b, &a) && (a != *b || j < i));
+            if contained {
+                self.free.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+impl RectPacker for MaxRectsPacker {
+    fn area(&self) -> Rect {
+        self.rect
+    }
+
+    fn push(&mut self, rect: Rect) -> Option<PositionedRect> {
+        if rect.width == 0 || rect.height == 0 {
+            return Some(rect.positioned(0, 0));
+        }
+
+        let pad = self.padding as u32;
+        let orientations = [
+            (rect.width + pad, rect.height + pad, false),
+            (rect.height + pad, rect.width + pad, true),
+        ];
+        let orientations = if self.rotation && rect.width != rect.height {
+            &orientations[..]
+        } else {
+            &orientations[..1]
+        };
+
+        // (short side leftover, long side leftover), position, rotated
+        let (_, used, rotated) = self
+            .free
+            .iter()
+            .flat_map(|free| orientations.iter().map(move |o| (free, *o)))
+            .filter(|(free, (w, h, _))| free.width >= *w && free.height >= *h)
+            .map(|(free, (w, h, rotated))| {
+                let (lw, lh) = (free.width - w, free.height - h);
+                (
+                    (lw.min(lh), lw.max(lh)),
+                    PositionedRect::new(free.x, free.y, w, h),
+                    rotated,
+                )
+            })
+            .min_by_key(|(score, used, _)| (*score, used.y, used.x))?;
+
+        self.place(used);
+
+        let rect = if rotated {
+            Rect::new(rect.height, rect.width)
+        } else {
+            rect
+        };
+        Some(rect.positioned(used.x, used.y))
+    }
+
+    fn remove(&mut self, rect: PositionedRect) {
+        let pad = self.padding as u32;
+        let width = (rect.width + pad).min(self.rect.width.saturating_sub(rect.x));
+        let height = (rect.height + pad).min(self.rect.height.saturating_sub(rect.y));
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let freed = PositionedRect::new(rect.x, rect.y, width, height);
+        // the freed area is not merged with
+        // the free rects next to it
+        self.free.push(freed);
+        self.prune();
+    }
+
+    fn alloc_more(&mut self, rect: Rect) {
+        let old = self.rect;
+        self.rect.width += rect.width;
+        self.rect.height += rect.height;
+
+        // free rects touching the old edges continue into the new area
+        for free in self.free.iter_mut() {
+            if free.x + free.width == old.width {
+                free.width += rect.width;
+            }
+            if free.y + free.height == old.height {
+                free.height += rect.height;
+            }
+        }
+        if rect.width != 0 {
+            self.free.push(PositionedRect::new(
+                old.width,
+                0,
+                rect.width,
+                self.rect.height,
+            ));
+        }
+        if rect.height != 0 {
+            self.free.push(PositionedRect::new(
+                0,
+                old.height,
+                self.rect.width,
+                rect.height,
+            ));
+        }
+        self.prune();
+    }
+}
+
+//
+
+fn intersects(a: &PositionedRect, b: &PositionedRect) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+fn contains(outer: &PositionedRect, inner: &PositionedRect) -> bool {
+    inner.x >= outer.x
+        && inner.y >= outer.y
+        && inner.x + inner.width <= outer.x + outer.width
+        && inner.y + inner.height <= outer.y + outer.height
+}
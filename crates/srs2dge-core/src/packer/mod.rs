@@ -1,4 +1,8 @@
+pub mod algorithm;
+pub mod guillotine;
+pub mod maxrects;
 pub mod packer2d;
 pub mod prelude;
 pub mod rect;
+pub mod skyline;
 pub mod texture;
@@ -1 +1,3 @@
-pub use super::{packer2d::*, rect::*, texture::*, *};
+pub use super::{
+    algorithm::*, guillotine::*, maxrects::*, packer2d::*, rect::*, skyline::*, texture::*, *,
+};
@@ -1,15 +1,16 @@
+use serde::{Deserialize, Serialize};
 use wgpu::Extent3d;
 use winit::dpi::PhysicalSize;
 
 //
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Rect {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct PositionedRect {
     pub x: u32,
     pub y: u32,
@@ -0,0 +1,198 @@
+//! Skyline packer with the bottom-left heuristic
+
+use super::{
+    algorithm::RectPacker,
+    rect::{PositionedRect, Rect},
+};
+
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Segment {
+    x: u32,
+    // the first free row under this segment
+    y: u32,
+    width: u32,
+}
+
+/// Packs rects on top of a skyline
+/// (the bottom edge of the packed rects)
+///
+/// Fast and dense for rects of similar
+/// heights. The space under overhanging
+/// rects is never reused.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SkylinePacker {
+    rect: Rect,
+    skyline: Vec<Segment>,
+    pub padding: u8,
+    pub rotation: bool,
+}
+
+//
+
+impl SkylinePacker {
+    pub fn new(rect: Rect) -> Self {
+        let skyline = if rect.width != 0 {
+            vec![Segment {
+                x: 0,
+                y: 0,
+                width: rect.width,
+            }]
+        } else {
+            vec![]
+        };
+        Self {
+            rect,
+            skyline,
+            padding: 0,
+            rotation: false,
+        }
+    }
+
+    pub fn with_padding(mut self, padding: u8) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// allow rotating rects by 90°
+    ///
+    /// rotated rects are returned with
+    /// their width and height swapped
+    pub fn with_rotation(mut self, rotation: bool) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// the y where a `width` x `height` rect
+    /// could be placed starting from segment `i`
+    fn fit(&self, i: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[i].x;
+        if x + width > self.rect.width {
+            return None;
+        }
+
+        let y = self.skyline[i..]
+            .iter()
+            .take_while(|segment| segment.x < x + width)
+            .map(|segment| segment.y)
+            .max()?;
+
+        (y + height <= self.rect.height).then_some(y)
+    }
+
+    /// set the skyline to `y` in `x..x+width`
+    fn raise(&mut self, x: u32, y: u32, width: u32) {
+        self.split_at(x);
+        self.split_at(x + width);
+        for segment in self.skyline.iter_mut() {
+            if segment.x >= x && segment.x < x + width {
+                segment.y = y;
+            }
+        }
+        self.merge();
+    }
+
+    fn split_at(&mut self, x: u32) {
+        let Some(i) = self
+            .skyline
+            .iter()
+            .position(|segment| segment.x < x && x < segment.x + segment.width)
+        else {
+            return;
+        };
+
+        let segment = self.skyline[i];
+        self.skyline[i].width = x - segment.x;
+        self.skyline.insert(
+            i + 1,
+            Segment {
+                x,
+                y: segment.y,
+                width: segment.x + segment.width - x,
+            },
+        );
+    }
+
+    fn merge(&mut self) {
+        self.skyline.dedup_by(|next, prev| {
+            if next.y == prev.y {
+                prev.width += next.width;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+impl RectPacker for SkylinePacker {
+    fn area(&self) -> Rect {
+        self.rect
+    }
+
+    fn push(&mut self, rect: Rect) -> Option<PositionedRect> {
+        if rect.width == 0 || rect.height == 0 {
+            return Some(rect.positioned(0, 0));
+        }
+
+        let pad = self.padding as u32;
+        let orientations = [
+            (rect.width + pad, rect.height + pad, false),
+            (rect.height + pad, rect.width + pad, true),
+        ];
+        let orientations = if self.rotation && rect.width != rect.height {
+            &orientations[..]
+        } else {
+            &orientations[..1]
+        };
+
+        // lowest top edge first, then leftmost
+        let (_, x, y, (w, h, rotated)) = (0..self.skyline.len())
+            .flat_map(|i| orientations.iter().map(move |o| (i, *o)))
+            .filter_map(|(i, (w, h, rotated))| {
+                let y = self.fit(i, w, h)?;
+                Some((y + h, self.skyline[i].x, y, (w, h, rotated)))
+            })
+            .min_by_key(|(bottom, x, _, _)| (*bottom, *x))?;
+
+        self.raise(x, y + h, w);
+
+        let rect = if rotated {
+            Rect::new(rect.height, rect.width)
+        } else {
+            rect
+        };
+        Some(rect.positioned(x, y))
+    }
+
+    /// lowers the skyline if `rect`
+    /// is at the top of it
+    fn remove(&mut self, rect: PositionedRect) {
+        let pad = self.padding as u32;
+        let width = (rect.width + pad).min(self.rect.width.saturating_sub(rect.x));
+        let bottom = rect.y + rect.height + pad;
+
+        self.split_at(rect.x);
+        self.split_at(rect.x + width);
+        for segment in self.skyline.iter_mut() {
+            if segment.x >= rect.x && segment.x < rect.x + width && segment.y == bottom {
+                segment.y = rect.y;
+            }
+        }
+        self.merge();
+    }
+
+    fn alloc_more(&mut self, rect: Rect) {
+        if rect.width != 0 {
+            self.skyline.push(Segment {
+                x: self.rect.width,
+                y: 0,
+                width: rect.width,
+            });
+            self.merge();
+        }
+        self.rect.width += rect.width;
+        self.rect.height += rect.height;
+    }
+}
@@ -1,10 +1,14 @@
-use super::SerializeableTextureAtlasMap;
-use crate::prelude::{
-    PositionedRect, Rect, Target, TextureAtlas, TextureAtlasBuilder, TexturePosition,
+use super::{SerializeableTextureAtlasMap, USAGE};
+use crate::{
+    prelude::{
+        MaxRectsPacker, PackerAlgorithm, PackerStats, PagedTexturePosition, PositionedRect, Rect,
+        RectPacker, Target, Texture, TextureAtlas, TextureAtlasBuilder, TexturePosition,
+    },
+    texture::mipmap::extrude,
 };
-use image::{load_from_memory, ImageResult, RgbaImage};
+use image::{imageops, load_from_memory, ImageResult, RgbaImage};
 use std::{
-    collections::{BinaryHeap, HashMap},
+    collections::{BinaryHeap, HashMap, HashSet},
     hash::Hash,
     ops::Deref,
 };
@@ -15,6 +19,9 @@ use std::{
 ///
 /// A helper struct to find [`TexturePosition`]:s
 /// stored in this texture atlas by their keys.
+///
+/// Images overflow to additional pages
+/// once a page reaches the limit.
 #[derive(Debug, Clone)]
 pub struct TextureAtlasMapBuilder<K> {
     /// texture side length limit
@@ -23,6 +30,15 @@ pub struct TextureAtlasMapBuilder<K> {
     /// a padding for each texture
     padding: u8,
 
+    /// the packing heuristic
+    algorithm: PackerAlgorithm,
+
+    /// allow rotating images by 90°
+    rotation: bool,
+
+    /// generate padding-aware mip levels
+    mipmaps: bool,
+
     /// all images before combining
     images: BinaryHeap<SortBySize<K>>,
 
@@ -40,6 +56,32 @@ where
 {
     pub(super) inner: TextureAtlas,
     pub(super) map: HashMap<K, TexturePosition>,
+
+    // pages after the first one
+    pub(super) pages: Vec<TextureAtlas>,
+    // page indices of images that are not on the first page
+    pub(super) page_of: HashMap<K, u32>,
+    // images stored rotated 90° clockwise
+    pub(super) rotated: HashSet<K>,
+
+    // created on the first insert or remove
+    pub(super) dynamic: Option<DynamicState<K>>,
+
+    // padding and side length limit
+    // of images inserted at runtime
+    pub(super) padding: u8,
+    pub(super) limit: u16,
+
+    // incremented every time the
+    // texture positions change
+    pub(super) generation: u64,
+}
+
+/// packer state of runtime inserts and removes
+#[derive(Debug)]
+pub(super) struct DynamicState<K> {
+    packer: MaxRectsPacker,
+    rects: HashMap<K, PositionedRect>,
 }
 
 //
@@ -50,6 +92,9 @@ impl<K> Default for TextureAtlasMapBuilder<K> {
             images: Default::default(),
             limit: u16::MAX,
             padding: 2,
+            algorithm: PackerAlgorithm::default(),
+            rotation: false,
+            mipmaps: false,
             label: None,
         }
     }
@@ -72,6 +117,31 @@ impl<K> TextureAtlasMapBuilder<K> {
         self
     }
 
+    /// the packing heuristic
+    pub fn with_algorithm(mut self, algorithm: PackerAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// allow rotating images by 90° (clockwise)
+    ///
+    /// [`TextureAtlasMap::get_paged`] tells
+    /// which images were rotated
+    ///
+    /// see [`TextureAtlasBuilder::with_rotation`]
+    pub fn with_rotation(mut self, rotation: bool) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// generate mip levels for the atlas
+    ///
+    /// see [`TextureAtlasBuilder::with_mipmaps`]
+    pub fn with_mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
     /// a label for the resulting texture
     ///
     /// used in debugging
@@ -80,6 +150,33 @@ impl<K> TextureAtlasMapBuilder<K> {
         self
     }
 
+    /// occupancy statistics of the packed
+    /// images, without building the atlas
+    ///
+    /// `None` if the limit is reached
+    pub fn stats(&self) -> Option<PackerStats> {
+        let mut sizes: Vec<_> = self.images.iter().collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut builder = self.atlas_builder(None);
+        for SortBySize { image, .. } in sizes {
+            let (width, height) = image.dimensions();
+            builder.push(Rect { width, height })?;
+        }
+
+        Some(builder.stats())
+    }
+
+    fn atlas_builder(&self, label: Option<String>) -> TextureAtlasBuilder {
+        TextureAtlasBuilder::new()
+            .with_padding(self.padding)
+            .with_limit(self.limit)
+            .with_algorithm(self.algorithm)
+            .with_rotation(self.rotation)
+            .with_mipmaps(self.mipmaps)
+            .with_label(label)
+    }
+
     /// insert a new image and its key
     /// to this texture atlas map builder
     pub fn with(mut self, key: K, image: RgbaImage) -> Self {
@@ -106,38 +203,55 @@ where
 {
     /// build the texture atlas map headlessly
     pub fn build_serializeable(mut self) -> SerializeableTextureAtlasMap<K> {
-        let mut builder = TextureAtlasBuilder::new()
-            .with_padding(self.padding)
-            .with_limit(self.limit)
-            .with_label(self.label);
+        let label = self.label.take();
+        let mut builder = self.atlas_builder(label);
         let mut images = vec![];
+        let mut rotated = HashSet::new();
 
         // sort images by size for (more) optimal packing
         while let Some(SortBySize { key, image }) = self.images.pop() {
             let (width, height) = image.dimensions();
 
-            let v = builder
-                .push(Rect { width, height })
-                .expect("Texture atlas limit reached");
-            images.push((key, v, image));
+            let (page, v) = builder
+                .push_paged(Rect { width, height })
+                .expect("Image is larger than the texture atlas limit");
+            if v.width != width {
+                rotated.insert(key.clone());
+            }
+            images.push((key, page, v, image));
         }
 
-        type Map<K> = Vec<(K, PositionedRect)>;
-        type Iter = Vec<(RgbaImage, PositionedRect)>;
+        type Map<K> = Vec<(K, usize, PositionedRect)>;
+        type Iter = Vec<(RgbaImage, usize, PositionedRect)>;
         let (map, iter): (Map<K>, Iter) = images
             .into_iter()
-            .map(|(key, pos, img)| ((key, pos), (img, pos)))
+            .map(|(key, page, pos, img)| ((key, page, pos), (img, page, pos)))
             .unzip();
 
-        let inner = builder.build_serializeable(iter);
-        let size = inner.get_dim();
+        tracing::debug!(
+            "Packed {} images into {} page(s) with {:?}, last page occupancy: {:.1}%",
+            map.len(),
+            builder.page_count(),
+            self.algorithm,
+            builder.stats().occupancy() * 100.0
+        );
+
+        let pages = builder.build_pages_serializeable(iter);
+        let sizes: Vec<Rect> = pages.iter().map(|page| page.get_dim()).collect();
+        let page_of = map
+            .iter()
+            .filter(|(_, page, _)| *page != 0)
+            .map(|(key, page, _)| (key.clone(), *page as u32))
+            .collect();
         let map = map
             .into_iter()
-            .map(|(key, rect)| (key, TexturePosition::new(size, rect)))
+            .map(|(key, page, rect)| (key, TexturePosition::new(sizes[page], rect)))
             .collect();
-        let (image, label) = inner.split();
 
-        SerializeableTextureAtlasMap::new(image, map, label)
+        SerializeableTextureAtlasMap::new_paged(pages, map, page_of)
+            .with_rotated(rotated)
+            .with_padding(self.padding)
+            .with_limit(self.limit)
     }
 
     /// build the texture atlas map and
@@ -155,9 +269,205 @@ where
         TextureAtlasMapBuilder::new()
     }
 
+    /// the position of an image
+    ///
+    /// doesn't tell if the image was rotated,
+    /// see [`Self::get_paged`]
     pub fn get(&self, key: &K) -> Option<TexturePosition> {
         self.map.get(key).copied()
     }
+
+    /// the position, the page index and
+    /// the rotation of an image
+    ///
+    /// [`Self::get`] doesn't tell which
+    /// page the position is in
+    pub fn get_paged(&self, key: &K) -> Option<PagedTexturePosition> {
+        let pos = self.get(key)?;
+        let page = self.page_of.get(key).copied().unwrap_or(0);
+        Some(PagedTexturePosition::new(page, pos).with_rotated(self.rotated.contains(key)))
+    }
+
+    /// a single page of the atlas,
+    /// the first page is also
+    /// available through [`Deref`]
+    pub fn page(&self, page: u32) -> Option<&TextureAtlas> {
+        match page {
+            0 => Some(&self.inner),
+            _ => self.pages.get(page as usize - 1),
+        }
+    }
+
+    /// number of pages, always at least one
+    pub fn page_count(&self) -> u32 {
+        self.pages.len() as u32 + 1
+    }
+
+    /// incremented every time an image is
+    /// inserted or removed and when the atlas grows
+    ///
+    /// all [`TexturePosition`]s from an older
+    /// generation might be stale and have to
+    /// be fetched again with [`Self::get`]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// insert (or replace) an image while the atlas is on the GPU
+    ///
+    /// the image is always inserted into the first page,
+    /// which grows if the image doesn't fit, up to the
+    /// limit of the builder
+    ///
+    /// a replaced image is removed only on success,
+    /// but the atlas might have grown before a failure
+    pub fn insert(
+        &mut self,
+        target: &Target,
+        key: K,
+        image: &RgbaImage,
+    ) -> Result<TexturePosition, &'static str> {
+        let rect = Rect::new(image.width(), image.height());
+        let limit = target
+            .device
+            .limits()
+            .max_texture_dimension_2d
+            .min(self.limit as u32);
+        if rect.width > limit || rect.height > limit {
+            return Err("Texture atlas limit reached");
+        }
+
+        // reserve the area first, so that a failed
+        // insert doesn't lose the replaced image
+        let pos = loop {
+            if let Some(pos) = self.dynamic().packer.push(rect) {
+                break pos;
+            }
+
+            let dim = self.inner.get_dim();
+            let side = (dim.width.max(dim.height) + 1).next_power_of_two();
+            if side > limit {
+                return Err("Texture atlas limit reached");
+            }
+            self.grow(target, Rect::new(side, side));
+        };
+
+        if pos.width != 0 && pos.height != 0 {
+            let (spot, image) = self.extruded(image, pos);
+            if let Err(err) = self.inner.texture.write(target, spot, image.into()) {
+                // give the reserved area back
+                self.dynamic().packer.remove(pos);
+                return Err(err);
+            }
+        }
+
+        self.remove(&key);
+
+        let tex = TexturePosition::new(self.inner.get_dim(), pos);
+        self.dynamic().rects.insert(key.clone(), pos);
+        self.map.insert(key, tex);
+        self.generation += 1;
+
+        Ok(tex)
+    }
+
+    /// remove an image
+    ///
+    /// runtime inserts and removes only manage the
+    /// first page, so only the area of an image on
+    /// the first page can be reused by later inserts,
+    /// images on other pages stay in their textures
+    pub fn remove(&mut self, key: &K) -> Option<TexturePosition> {
+        let tex = self.map.remove(key)?;
+        self.rotated.remove(key);
+        self.generation += 1;
+        if self.page_of.remove(key).is_some() {
+            return Some(tex);
+        }
+
+        let dynamic = self.dynamic();
+        match dynamic.rects.remove(key) {
+            Some(rect) if rect.width != 0 && rect.height != 0 => dynamic.packer.remove(rect),
+            _ => {}
+        }
+        Some(tex)
+    }
+
+    /// fill the padding around mipmapped
+    /// images with their edge pixels
+    fn extruded(&self, image: &RgbaImage, pos: PositionedRect) -> (PositionedRect, RgbaImage) {
+        if self.inner.get_mip_level_count() <= 1 {
+            return (pos, image.clone());
+        }
+
+        let dim = self.inner.get_dim();
+        let pad = self.padding as u32;
+        let (x, y) = (pos.x.saturating_sub(pad), pos.y.saturating_sub(pad));
+        let mut area = RgbaImage::new(
+            (pos.x + pos.width + pad).min(dim.width) - x,
+            (pos.y + pos.height + pad).min(dim.height) - y,
+        );
+        imageops::replace(&mut area, image, (pos.x - x) as i64, (pos.y - y) as i64);
+
+        let local = PositionedRect::new(pos.x - x, pos.y - y, pos.width, pos.height);
+        let filled = extrude(&mut area, local, self.padding);
+        let image = imageops::crop_imm(&area, filled.x, filled.y, filled.width, filled.height);
+        (
+            PositionedRect::new(x + filled.x, y + filled.y, filled.width, filled.height),
+            image.to_image(),
+        )
+    }
+
+    fn dynamic(&mut self) -> &mut DynamicState<K> {
+        let (inner, map, page_of) = (&self.inner, &self.map, &self.page_of);
+        let padding = self.padding;
+        self.dynamic.get_or_insert_with(|| {
+            // reconstruct the packer from the existing images
+            let dim = inner.get_dim();
+            let mut packer = MaxRectsPacker::new(dim).with_padding(padding);
+            let rects: HashMap<K, PositionedRect> = map
+                .iter()
+                .filter(|(key, _)| !page_of.contains_key(key))
+                .map(|(key, tex)| (key.clone(), tex.to_rect(dim)))
+                .collect();
+            for rect in rects
+                .values()
+                .filter(|rect| rect.width != 0 && rect.height != 0)
+            {
+                packer.reserve(*rect);
+            }
+            DynamicState { packer, rects }
+        })
+    }
+
+    /// copy the old contents to a larger
+    /// texture and remap the texture positions
+    fn grow(&mut self, target: &Target, dim: Rect) {
+        let old = self.inner.get_dim();
+        tracing::debug!("Growing texture atlas {old:?} -> {dim:?}");
+
+        let levels = self.inner.get_mip_level_count();
+        let texture =
+            Texture::<USAGE>::new_rgba_mipmapped(target, dim, levels, self.inner.label.as_deref());
+        if old.width != 0 && old.height != 0 {
+            self.inner.texture.copy_to(target, &texture, levels);
+        }
+        self.inner.texture = texture;
+
+        let dynamic = self.dynamic();
+        dynamic
+            .packer
+            .alloc_more(Rect::new(dim.width - old.width, dim.height - old.height));
+        let rects: Vec<(K, PositionedRect)> = dynamic
+            .rects
+            .iter()
+            .map(|(key, rect)| (key.clone(), *rect))
+            .collect();
+        for (key, rect) in rects {
+            self.map.insert(key, TexturePosition::new(dim, rect));
+        }
+        self.generation += 1;
+    }
 }
 
 impl<K> Deref for TextureAtlasMap<K>
@@ -205,3 +515,65 @@ impl<K> Ord for SortBySize<K> {
         self.size().cmp(&other.size())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::TextureAtlasMapBuilder;
+    use crate::Engine;
+    use image::RgbaImage;
+    use wgpu::RequestAdapterOptions;
+
+    #[test]
+    pub fn test_pages() {
+        let atlas = TextureAtlasMapBuilder::new()
+            .with_limit(64)
+            .with(0, RgbaImage::new(40, 40))
+            .with(1, RgbaImage::new(40, 40))
+            .with(2, RgbaImage::new(10, 10))
+            .build_serializeable();
+
+        let pages: Vec<_> = atlas.pages().map(|page| page.get_dim()).collect();
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|dim| dim.width <= 64 && dim.height <= 64));
+    }
+
+    #[test]
+    pub fn test_insert() {
+        let rt = crate::init_tokio();
+        rt.block_on(async {
+            let engine = Engine::new();
+            let adapter = engine
+                .get_instance()
+                .request_adapter(&RequestAdapterOptions::default())
+                .await;
+            if adapter.is_none() {
+                tracing::warn!("No GPU, skipping the texture atlas insert test");
+                return;
+            }
+
+            let target = engine.new_target_headless().await;
+            let mut atlas = TextureAtlasMapBuilder::new()
+                .with_limit(64)
+                .with_padding(1)
+                .with(0, RgbaImage::new(16, 16))
+                .build(&target);
+            let generation = atlas.generation();
+
+            // the builder padding is used at runtime
+            let pos = atlas.insert(&target, 1, &RgbaImage::new(8, 8)).unwrap();
+            let dim = atlas.get_dim();
+            assert_eq!(dim.width, 32);
+            assert_eq!(pos.to_rect(dim).x, 17);
+            assert!(atlas.generation() > generation);
+
+            // too large for the limit, the old image is kept
+            let generation = atlas.generation();
+            assert!(atlas.insert(&target, 1, &RgbaImage::new(100, 100)).is_err());
+            assert_eq!(atlas.get(&1), Some(pos));
+            assert_eq!(atlas.generation(), generation);
+
+            atlas.remove(&1);
+            assert!(atlas.generation() > generation);
+        });
+    }
+}
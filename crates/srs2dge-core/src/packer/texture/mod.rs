@@ -1,22 +1,32 @@
 use self::serde::SerializeableTextureAtlas;
 use super::{
-    packer2d::Packer,
+    algorithm::{DynPacker, PackerAlgorithm, PackerStats, RectPacker},
     rect::{PositionedRect, Rect},
 };
-use crate::{target::Target, texture::Texture};
+use crate::{
+    target::Target,
+    texture::{
+        mipmap::{atlas_mip_level_count, extrude},
+        Texture,
+    },
+};
 use image::RgbaImage;
-use std::{borrow::Borrow, ops::Deref};
+use std::{borrow::Borrow, mem, ops::Deref};
 use wgpu::TextureUsages;
 
 //
 
 pub use self::serde::*;
 pub use map::*;
+#[cfg(feature = "sheet")]
+pub use sheet::*;
 
 //
 
 mod map;
 mod serde;
+#[cfg(feature = "sheet")]
+mod sheet;
 
 //
 
@@ -31,7 +41,13 @@ const USAGE: u32 = TextureUsages::TEXTURE_BINDING.bits()
 pub struct TextureAtlasBuilder {
     /// the inner packer to pack
     /// texture ([`Rect`]:s) with
-    packer: Packer,
+    packer: DynPacker,
+
+    /// full pages of [`Self::push_paged`]
+    pages: Vec<DynPacker>,
+
+    /// allow rotating images by 90°
+    rotation: bool,
 
     /// texture side length limit
     limit: u16,
@@ -39,6 +55,9 @@ pub struct TextureAtlasBuilder {
     /// a padding for each texture
     padding: u8,
 
+    /// generate padding-aware mip levels
+    mipmaps: bool,
+
     /// optional label used for debugging
     label: Option<String>,
 }
@@ -56,13 +75,14 @@ pub struct TextureAtlas {
 
 impl Default for TextureAtlasBuilder {
     fn default() -> Self {
-        let mut packer = Packer::default();
         let padding = 2;
-        packer.padding = padding;
         Self {
-            packer,
+            packer: DynPacker::new(PackerAlgorithm::default(), Rect::default(), padding, false),
+            pages: vec![],
+            rotation: false,
             limit: u16::MAX,
             padding,
+            mipmaps: false,
             label: None,
         }
     }
@@ -89,7 +109,43 @@ impl TextureAtlasBuilder {
     /// a padding for each texture
     pub fn with_padding(mut self, padding: u8) -> Self {
         self.padding = padding;
-        self.packer.padding = padding;
+        self.packer = self.new_packer(self.packer.algorithm());
+        self
+    }
+
+    /// the packing heuristic
+    ///
+    /// resets the packer, so this has
+    /// to be set before pushing images
+    pub fn with_algorithm(mut self, algorithm: PackerAlgorithm) -> Self {
+        self.packer = self.new_packer(algorithm);
+        self
+    }
+
+    /// allow rotating images by 90° (clockwise)
+    ///
+    /// rotated images are pushed with their width
+    /// and height swapped and [`Self::build`]
+    /// rotates them, see
+    /// [`crate::prelude::PagedTexturePosition::uv`]
+    ///
+    /// resets the packer, so this has
+    /// to be set before pushing images
+    pub fn with_rotation(mut self, rotation: bool) -> Self {
+        self.rotation = rotation;
+        self.packer = self.new_packer(self.packer.algorithm());
+        self
+    }
+
+    /// generate mip levels for the atlas
+    ///
+    /// the padding around each image is filled with
+    /// its edge pixels and the number of mip levels
+    /// is limited by the padding so that images
+    /// don't bleed into each other, see
+    /// [`atlas_mip_level_count`]
+    pub fn with_mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
         self
     }
 
@@ -107,24 +163,84 @@ impl TextureAtlasBuilder {
         self.packer.push_until(rect, self.limit)
     }
 
+    /// push a new image (its size) to this texture
+    /// atlas builder and start a new page if the
+    /// current page reached the limit
+    ///
+    /// returns the page index and the position
+    /// in that page, `None` only if the image
+    /// is larger than the limit
+    pub fn push_paged(&mut self, rect: Rect) -> Option<(usize, PositionedRect)> {
+        if let Some(pos) = self.push(rect) {
+            return Some((self.pages.len(), pos));
+        }
+
+        let page = self.new_packer_sized(self.packer.algorithm(), Rect::default());
+        self.pages.push(mem::replace(&mut self.packer, page));
+        match self.push(rect) {
+            Some(pos) => Some((self.pages.len(), pos)),
+            None => {
+                // doesn't fit even on an empty page
+                self.packer = self.pages.pop().unwrap();
+                None
+            }
+        }
+    }
+
+    /// number of pages, always at least one
+    pub fn page_count(&self) -> usize {
+        self.pages.len() + 1
+    }
+
+    /// occupancy statistics of the
+    /// images on the current page
+    pub fn stats(&self) -> PackerStats {
+        self.packer.stats()
+    }
+
     /// build the texture atlas headlessly
     pub fn build_serializeable<I, R>(self, iter: I) -> SerializeableTextureAtlas
     where
         R: Borrow<RgbaImage>,
         I: IntoIterator<Item = (R, PositionedRect)>,
     {
-        let dim = self.packer.area();
-        let label = self.label;
-
         // combine all images into one
+        let dim = self.packer.area();
         let mut combined = RgbaImage::new(dim.width, dim.height);
         for (image, pos) in iter {
-            for (xo, yo, pixel) in image.borrow().enumerate_pixels() {
-                combined.put_pixel(pos.x + xo, pos.y + yo, *pixel);
-            }
+            self.combine(&mut combined, image.borrow(), pos);
+        }
+
+        let levels = self.mip_levels(dim);
+        SerializeableTextureAtlas::new(combined, self.label).with_mip_levels(levels)
+    }
+
+    /// build all pages of the texture atlas headlessly
+    ///
+    /// the images are paired with their
+    /// page index from [`Self::push_paged`]
+    pub fn build_pages_serializeable<I, R>(self, iter: I) -> Vec<SerializeableTextureAtlas>
+    where
+        R: Borrow<RgbaImage>,
+        I: IntoIterator<Item = (R, usize, PositionedRect)>,
+    {
+        let mut pages: Vec<RgbaImage> = self
+            .pages
+            .iter()
+            .chain(Some(&self.packer))
+            .map(|packer| RgbaImage::new(packer.area().width, packer.area().height))
+            .collect();
+        for (image, page, pos) in iter {
+            self.combine(&mut pages[page], image.borrow(), pos);
         }
 
-        SerializeableTextureAtlas::new(combined, label)
+        pages
+            .into_iter()
+            .map(|page| {
+                let levels = self.mip_levels(page.dimensions().into());
+                SerializeableTextureAtlas::new(page, self.label.clone()).with_mip_levels(levels)
+            })
+            .collect()
     }
 
     /// build the texture atlas and upload
@@ -136,6 +252,37 @@ impl TextureAtlasBuilder {
     {
         self.build_serializeable(iter).upload(target)
     }
+
+    fn new_packer(&self, algorithm: PackerAlgorithm) -> DynPacker {
+        self.new_packer_sized(algorithm, self.packer.area())
+    }
+
+    fn new_packer_sized(&self, algorithm: PackerAlgorithm, area: Rect) -> DynPacker {
+        DynPacker::new(algorithm, area, self.padding, self.rotation)
+    }
+
+    fn mip_levels(&self, dim: Rect) -> u32 {
+        if self.mipmaps {
+            atlas_mip_level_count(dim, self.padding)
+        } else {
+            1
+        }
+    }
+
+    fn combine(&self, combined: &mut RgbaImage, image: &RgbaImage, pos: PositionedRect) {
+        let rotated = pos.width != image.width() || pos.height != image.height();
+        for (xo, yo, pixel) in image.enumerate_pixels() {
+            if rotated {
+                combined.put_pixel(pos.x + image.height() - 1 - yo, pos.y + xo, *pixel);
+            } else {
+                combined.put_pixel(pos.x + xo, pos.y + yo, *pixel);
+            }
+        }
+
+        if self.mipmaps {
+            extrude(combined, pos, self.padding);
+        }
+    }
 }
 
 impl TextureAtlas {
@@ -151,3 +298,31 @@ impl Deref for TextureAtlas {
         &self.texture
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::TextureAtlasBuilder;
+    use crate::prelude::{PagedTexturePosition, PositionedRect, Rect, TexturePosition};
+    use glam::Vec2;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    pub fn test_rotation() {
+        let mut builder = TextureAtlasBuilder::new()
+            .with_padding(0)
+            .with_rotation(true);
+        builder.push(Rect::new(4, 4)).unwrap();
+
+        let image = RgbaImage::from_fn(4, 2, |x, y| Rgba([x as u8, y as u8, 0, 255]));
+        let pos = PositionedRect::new(0, 0, 2, 4);
+        let (atlas, _) = builder.build_serializeable([(&image, pos)]).split();
+
+        let tex = PagedTexturePosition::new(0, TexturePosition::new(Rect::new(4, 4), pos))
+            .with_rotated(true);
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let uv = Vec2::new((x as f32 + 0.5) / 4.0, (y as f32 + 0.5) / 2.0);
+            let uv = tex.uv(uv) * 4.0;
+            assert_eq!(atlas.get_pixel(uv.x as u32, uv.y as u32), pixel);
+        }
+    }
+}
@@ -6,7 +6,10 @@ use crate::{
 };
 use image::RgbaImage;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
 
 //
 
@@ -18,13 +21,35 @@ pub struct SerializeableTextureAtlas {
     inner: SerializeableTexture,
 }
 
-#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SerializeableTextureAtlasMap<K>
 where
     K: Eq + Hash + Clone,
 {
     inner: SerializeableTextureAtlas,
     map: HashMap<K, TexturePosition>,
+
+    /// pages after the first one
+    #[serde(default)]
+    pages: Vec<SerializeableTextureAtlas>,
+
+    /// page indices of images that
+    /// are not on the first page
+    #[serde(default)]
+    page_of: HashMap<K, u32>,
+
+    /// images stored rotated 90° clockwise
+    #[serde(default)]
+    rotated: HashSet<K>,
+
+    /// padding of images inserted at runtime
+    #[serde(default = "default_padding")]
+    padding: u8,
+
+    /// side length limit when
+    /// growing at runtime
+    #[serde(default = "default_limit")]
+    limit: u16,
 }
 
 //
@@ -36,6 +61,14 @@ impl SerializeableTextureAtlas {
         }
     }
 
+    /// number of mip levels generated on upload
+    ///
+    /// see [`crate::texture::mipmap::atlas_mip_level_count`]
+    pub fn with_mip_levels(mut self, mip_levels: u32) -> Self {
+        self.inner = self.inner.with_mip_levels(mip_levels);
+        self
+    }
+
     pub fn split(self) -> (RgbaImage, Option<String>) {
         self.inner.split()
     }
@@ -55,6 +88,15 @@ impl SerializeableTextureAtlas {
     }
 }
 
+impl<K> Default for SerializeableTextureAtlasMap<K>
+where
+    K: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), None)
+    }
+}
+
 impl<K> SerializeableTextureAtlasMap<K>
 where
     K: Eq + Hash + Clone,
@@ -63,9 +105,63 @@ where
         Self {
             inner: SerializeableTextureAtlas::new(image, label),
             map,
+            pages: vec![],
+            page_of: Default::default(),
+            rotated: Default::default(),
+            padding: default_padding(),
+            limit: default_limit(),
+        }
+    }
+
+    /// a texture atlas map with multiple pages
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pages` is empty
+    pub fn new_paged(
+        pages: Vec<SerializeableTextureAtlas>,
+        map: HashMap<K, TexturePosition>,
+        page_of: HashMap<K, u32>,
+    ) -> Self {
+        let mut pages = pages.into_iter();
+        let inner = pages.next().expect("Texture atlas has no pages");
+        Self {
+            inner,
+            map,
+            pages: pages.collect(),
+            page_of,
+            rotated: Default::default(),
+            padding: default_padding(),
+            limit: default_limit(),
         }
     }
 
+    /// keys of images stored rotated 90° clockwise,
+    /// see [`TextureAtlasMap::get_paged`]
+    pub fn with_rotated(mut self, rotated: HashSet<K>) -> Self {
+        self.rotated = rotated;
+        self
+    }
+
+    /// padding of images inserted at runtime,
+    /// see [`TextureAtlasMap::insert`]
+    pub fn with_padding(mut self, padding: u8) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// side length limit when growing
+    /// at runtime, see [`TextureAtlasMap::insert`]
+    pub fn with_limit(mut self, limit: u16) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// the images of all pages
+    pub fn pages(&self) -> impl Iterator<Item = &SerializeableTextureAtlas> {
+        Some(&self.inner).into_iter().chain(self.pages.iter())
+    }
+
     pub async fn download(from: &TextureAtlasMap<K>, target: &Target) -> Self {
         from.download(target).await
     }
@@ -73,7 +169,17 @@ where
     pub fn upload(&self, target: &Target) -> TextureAtlasMap<K> {
         let inner = self.inner.upload(target);
         let map = self.map.clone();
-        TextureAtlasMap { inner, map }
+        TextureAtlasMap {
+            inner,
+            map,
+            pages: self.pages.iter().map(|page| page.upload(target)).collect(),
+            page_of: self.page_of.clone(),
+            rotated: self.rotated.clone(),
+            dynamic: None,
+            padding: self.padding,
+            limit: self.limit,
+            generation: 0,
+        }
     }
 }
 
@@ -95,10 +201,31 @@ where
     pub async fn download(&self, target: &Target) -> SerializeableTextureAtlasMap<K> {
         let inner = self.inner.download(target).await;
         let map = self.map.clone();
-        SerializeableTextureAtlasMap { inner, map }
+        let mut pages = Vec::with_capacity(self.pages.len());
+        for page in self.pages.iter() {
+            pages.push(page.download(target).await);
+        }
+        SerializeableTextureAtlasMap {
+            inner,
+            map,
+            pages,
+            page_of: self.page_of.clone(),
+            rotated: self.rotated.clone(),
+            padding: self.padding,
+            limit: self.limit,
+        }
     }
 
     pub fn upload(from: &SerializeableTextureAtlasMap<K>, target: &Target) -> Self {
         from.upload(target)
     }
 }
+
+// same as `TextureAtlasMapBuilder`
+fn default_padding() -> u8 {
+    2
+}
+
+fn default_limit() -> u16 {
+    u16::MAX
+}
@@ -0,0 +1,660 @@
+//! Aseprite and TexturePacker sprite sheet importers
+//!
+//! Both tools export a sheet image and a JSON
+//! file that describes the frames in it, either
+//! as a map (`JSON (Hash)`) or as a list
+//! (`JSON (Array)`) of frames.
+//!
+//! Requires the `sheet` feature.
+
+use super::{SerializeableTextureAtlasMap, TextureAtlasMapBuilder};
+use crate::packer::rect::{PositionedRect, Rect};
+use glam::Vec2;
+use image::{imageops, load_from_memory, ImageError, RgbaImage};
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{collections::BTreeMap, fmt};
+
+//
+
+/// A builder for [`SpriteSheet`]s
+///
+/// Frames of all added sheets are packed into
+/// a single texture atlas map, use
+/// `SpriteSheetBuilder::from(TextureAtlasMapBuilder)`
+/// to configure the atlas.
+#[derive(Debug, Clone, Default)]
+pub struct SpriteSheetBuilder {
+    atlas: TextureAtlasMapBuilder<String>,
+    frames: Vec<SpriteSheetFrame>,
+    tags: Vec<FrameTag>,
+
+    /// keep the transparent
+    /// borders trimmed off
+    trimmed: bool,
+}
+
+/// Imported sprite sheet frames and their animations
+///
+/// The atlas keeps its QOI compressed
+/// representation when serialized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpriteSheet {
+    /// the frames by their names
+    pub atlas: SerializeableTextureAtlasMap<String>,
+
+    /// frames in the order they were exported in
+    pub frames: Vec<SpriteSheetFrame>,
+
+    pub tags: Vec<FrameTag>,
+}
+
+/// Metadata of a single [`SpriteSheet`] frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpriteSheetFrame {
+    pub name: String,
+
+    /// the size of the frame before trimming
+    pub source_size: Rect,
+
+    /// the part of the frame that is in the atlas
+    ///
+    /// this is the whole frame unless the
+    /// sheet was imported with
+    /// [`SpriteSheetBuilder::with_trimmed`]
+    pub trim: PositionedRect,
+
+    /// the pivot point relative to the top left
+    /// corner of the whole frame, `0.0..=1.0`
+    pub pivot: Vec2,
+
+    /// seconds
+    pub duration: Option<f32>,
+}
+
+/// A named sequence of frames
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameTag {
+    pub name: String,
+
+    /// frame names in the exported order,
+    /// `direction` is not applied yet
+    pub frames: Vec<String>,
+
+    #[serde(default)]
+    pub direction: TagDirection,
+
+    /// how many times the animation is played,
+    /// `None` means forever
+    #[serde(default)]
+    pub repeat: Option<u32>,
+}
+
+/// The playback direction of a [`FrameTag`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TagDirection {
+    #[default]
+    #[serde(alias = "forward")]
+    Forward,
+
+    #[serde(alias = "reverse")]
+    Reverse,
+
+    #[serde(alias = "pingpong")]
+    PingPong,
+
+    #[serde(alias = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+#[derive(Debug)]
+pub enum SpriteSheetError {
+    /// invalid JSON or not a sprite sheet
+    Json(serde_json::Error),
+
+    /// the sheet image could not be decoded
+    Image(ImageError),
+
+    /// the frame is (partially)
+    /// outside of the sheet image
+    OutOfBounds(String),
+
+    /// another frame already has this name
+    DuplicateFrame(String),
+
+    /// the tag refers to frames
+    /// that are not in the sheet
+    InvalidTag(String),
+}
+
+//
+
+#[derive(Debug, Deserialize)]
+struct JsonSheet {
+    #[serde(deserialize_with = "frames")]
+    frames: Vec<(String, JsonFrame)>,
+
+    #[serde(default)]
+    meta: JsonMeta,
+
+    /// TexturePacker (PixiJS) animations
+    #[serde(default)]
+    animations: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonFrame {
+    /// `w` and `h` are the size before
+    /// rotating, so a rotated frame is
+    /// `h` pixels wide in the sheet
+    frame: JsonRect,
+
+    /// rotated 90° clockwise in the sheet
+    #[serde(default)]
+    rotated: bool,
+
+    #[serde(default)]
+    trimmed: bool,
+
+    sprite_source_size: Option<JsonRect>,
+
+    source_size: Option<JsonSize>,
+
+    /// milliseconds
+    duration: Option<f32>,
+
+    pivot: Option<JsonPoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonNamedFrame {
+    filename: String,
+
+    #[serde(flatten)]
+    frame: JsonFrame,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonMeta {
+    #[serde(default)]
+    frame_tags: Vec<JsonTag>,
+
+    #[serde(default)]
+    slices: Vec<JsonSlice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTag {
+    name: String,
+    from: usize,
+    to: usize,
+
+    #[serde(default)]
+    direction: TagDirection,
+
+    /// newer Aseprite versions write it as a string
+    repeat: Option<JsonRepeat>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonRepeat {
+    Number(u32),
+    String(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSlice {
+    keys: Vec<JsonSliceKey>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSliceKey {
+    /// the first frame this key applies to
+    frame: usize,
+
+    bounds: JsonRect,
+
+    /// relative to `bounds`
+    pivot: Option<JsonPoint>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct JsonRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct JsonSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+//
+
+impl SpriteSheetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// keep the transparent borders of trimmed
+    /// frames trimmed off instead of restoring them
+    ///
+    /// saves atlas space, but the sprites have to be
+    /// offset by [`SpriteSheetFrame::trim`]
+    ///
+    /// applies to sheets added after this
+    pub fn with_trimmed(mut self, trimmed: bool) -> Self {
+        self.trimmed = trimmed;
+        self
+    }
+
+    /// add the frames and tags of an Aseprite
+    /// or TexturePacker JSON sheet
+    ///
+    /// `image` is the sheet image the JSON refers to
+    pub fn with_json(mut self, json: &str, image: &RgbaImage) -> Result<Self, SpriteSheetError> {
+        self.insert_json(json, image)?;
+        Ok(self)
+    }
+
+    /// add the frames and tags of an Aseprite
+    /// or TexturePacker JSON sheet
+    ///
+    /// `image_bytes` is the encoded sheet image
+    pub fn with_json_bytes(self, json: &str, image_bytes: &[u8]) -> Result<Self, SpriteSheetError> {
+        let image = load_from_memory(image_bytes)?.to_rgba8();
+        self.with_json(json, &image)
+    }
+
+    /// add the frames and tags of an Aseprite
+    /// or TexturePacker JSON sheet
+    ///
+    /// Aseprite slice pivots are applied
+    /// to the frames by their index in the
+    /// sheet, so the sheet should contain
+    /// the frames of a single sprite
+    ///
+    /// nothing is added if the sheet is invalid
+    pub fn insert_json(&mut self, json: &str, image: &RgbaImage) -> Result<(), SpriteSheetError> {
+        let JsonSheet {
+            frames,
+            meta,
+            animations,
+        } = serde_json::from_str(json)?;
+
+        // collect everything first, so that a
+        // failure leaves the builder untouched
+        let mut sprites = vec![];
+        let mut sheet_frames: Vec<SpriteSheetFrame> = vec![];
+        let mut tags = vec![];
+        for (index, (name, frame)) in frames.into_iter().enumerate() {
+            if self
+                .frames
+                .iter()
+                .chain(&sheet_frames)
+                .any(|other| other.name == name)
+            {
+                return Err(SpriteSheetError::DuplicateFrame(name));
+            }
+
+            let sprite = extract(image, &frame)
+                .ok_or_else(|| SpriteSheetError::OutOfBounds(name.clone()))?;
+
+            let JsonRect { w, h, .. } = frame.frame;
+            let source_size = frame
+                .source_size
+                .map_or(Rect::new(w, h), |JsonSize { w, h }| Rect::new(w, h));
+            let whole = PositionedRect::new(0, 0, source_size.width, source_size.height);
+            let trim = frame
+                .sprite_source_size
+                .filter(|_| frame.trimmed)
+                .map_or(whole, |JsonRect { x, y, w, h }| {
+                    PositionedRect::new(x, y, w, h)
+                });
+
+            let (sprite, trim) = if self.trimmed || trim == whole {
+                (sprite, trim)
+            } else {
+                (untrim(&sprite, trim, source_size), whole)
+            };
+
+            let pivot = frame
+                .pivot
+                .map(|JsonPoint { x, y }| Vec2::new(x, y))
+                .or_else(|| slice_pivot(&meta.slices, index, source_size))
+                .unwrap_or(Vec2::splat(0.5));
+
+            sprites.push((name.clone(), sprite));
+            sheet_frames.push(SpriteSheetFrame {
+                name,
+                source_size,
+                trim,
+                pivot,
+                duration: frame.duration.map(|ms| ms / 1000.0),
+            });
+        }
+
+        for tag in meta.frame_tags {
+            if tag.from > tag.to || tag.to >= sheet_frames.len() {
+                return Err(SpriteSheetError::InvalidTag(tag.name));
+            }
+
+            tags.push(FrameTag {
+                frames: sheet_frames[tag.from..=tag.to]
+                    .iter()
+                    .map(|frame| frame.name.clone())
+                    .collect(),
+                direction: tag.direction,
+                repeat: tag.repeat.and_then(|repeat| match repeat {
+                    JsonRepeat::Number(n) => Some(n),
+                    JsonRepeat::String(s) => s.parse().ok(),
+                }),
+                name: tag.name,
+            });
+        }
+
+        for (name, frames) in animations {
+            if !frames
+                .iter()
+                .all(|frame| sheet_frames.iter().any(|other| &other.name == frame))
+            {
+                return Err(SpriteSheetError::InvalidTag(name));
+            }
+
+            tags.push(FrameTag {
+                name,
+                frames,
+                direction: TagDirection::Forward,
+                repeat: None,
+            });
+        }
+
+        tracing::debug!("Imported {} frames from a sprite sheet", sheet_frames.len());
+
+        for (name, sprite) in sprites {
+            self.atlas.insert(name, sprite);
+        }
+        self.frames.extend(sheet_frames);
+        self.tags.extend(tags);
+
+        Ok(())
+    }
+
+    /// pack the frames headlessly
+    pub fn build_serializeable(self) -> SpriteSheet {
+        SpriteSheet {
+            atlas: self.atlas.build_serializeable(),
+            frames: self.frames,
+            tags: self.tags,
+        }
+    }
+}
+
+impl From<TextureAtlasMapBuilder<String>> for SpriteSheetBuilder {
+    fn from(atlas: TextureAtlasMapBuilder<String>) -> Self {
+        Self {
+            atlas,
+            ..Default::default()
+        }
+    }
+}
+
+impl SpriteSheet {
+    pub fn builder() -> SpriteSheetBuilder {
+        SpriteSheetBuilder::new()
+    }
+
+    pub fn frame(&self, name: &str) -> Option<&SpriteSheetFrame> {
+        self.frames.iter().find(|frame| frame.name == name)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&FrameTag> {
+        self.tags.iter().find(|tag| tag.name == name)
+    }
+}
+
+impl From<serde_json::Error> for SpriteSheetError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<ImageError> for SpriteSheetError {
+    fn from(err: ImageError) -> Self {
+        Self::Image(err)
+    }
+}
+
+// cut a frame out of the sheet and undo the rotation
+fn extract(image: &RgbaImage, frame: &JsonFrame) -> Option<RgbaImage> {
+    let JsonRect { x, y, w, h } = frame.frame;
+    let (w, h) = if frame.rotated { (h, w) } else { (w, h) };
+    if x.checked_add(w)? > image.width() || y.checked_add(h)? > image.height() {
+        return None;
+    }
+
+    let sprite = imageops::crop_imm(image, x, y, w, h).to_image();
+    Some(if frame.rotated {
+        imageops::rotate270(&sprite)
+    } else {
+        sprite
+    })
+}
+
+// restore the transparent borders of a trimmed frame
+fn untrim(sprite: &RgbaImage, trim: PositionedRect, source_size: Rect) -> RgbaImage {
+    let mut image = RgbaImage::new(source_size.width, source_size.height);
+    imageops::replace(&mut image, sprite, trim.x as i64, trim.y as i64);
+    image
+}
+
+// the pivot of the first slice with a pivot
+// in its latest key at or before `index`
+fn slice_pivot(slices: &[JsonSlice], index: usize, source_size: Rect) -> Option<Vec2> {
+    slices.iter().find_map(|slice| {
+        let key = slice
+            .keys
+            .iter()
+            .filter(|key| key.frame <= index)
+            .max_by_key(|key| key.frame)?;
+        let pivot = key.pivot?;
+        Some(Vec2::new(
+            (key.bounds.x as f32 + pivot.x) / source_size.width.max(1) as f32,
+            (key.bounds.y as f32 + pivot.y) / source_size.height.max(1) as f32,
+        ))
+    })
+}
+
+fn frames<'de, D>(deserializer: D) -> Result<Vec<(String, JsonFrame)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // a map of frames would lose the
+    // frame order if collected into a HashMap
+    struct FramesVisitor;
+
+    impl<'de> Visitor<'de> for FramesVisitor {
+        type Value = Vec<(String, JsonFrame)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map or a list of frames")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut frames = vec![];
+            while let Some(JsonNamedFrame { filename, frame }) = seq.next_element()? {
+                frames.push((filename, frame));
+            }
+            Ok(frames)
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut frames = vec![];
+            while let Some(entry) = map.next_entry()? {
+                frames.push(entry);
+            }
+            Ok(frames)
+        }
+    }
+
+    deserializer.deserialize_any(FramesVisitor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SpriteSheetBuilder, SpriteSheetError, TagDirection};
+    use crate::packer::rect::{PositionedRect, Rect};
+    use glam::Vec2;
+    use image::{Rgba, RgbaImage};
+
+    const ASEPRITE: &str = r##"{
+        "frames": [
+            {
+                "filename": "walk 0.aseprite",
+                "frame": { "x": 0, "y": 0, "w": 2, "h": 2 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 2, "h": 2 },
+                "sourceSize": { "w": 2, "h": 2 },
+                "duration": 100
+            },
+            {
+                "filename": "walk 1.aseprite",
+                "frame": { "x": 2, "y": 0, "w": 2, "h": 2 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 2, "h": 2 },
+                "sourceSize": { "w": 2, "h": 2 },
+                "duration": 150
+            }
+        ],
+        "meta": {
+            "app": "http://www.aseprite.org/",
+            "frameTags": [
+                { "name": "walk", "from": 0, "to": 1, "direction": "pingpong", "repeat": "2" }
+            ],
+            "slices": [
+                {
+                    "name": "feet",
+                    "color": "#0000ffff",
+                    "keys": [
+                        { "frame": 0, "bounds": { "x": 0, "y": 0, "w": 2, "h": 2 }, "pivot": { "x": 1, "y": 2 } }
+                    ]
+                }
+            ]
+        }
+    }"##;
+
+    // `a` is rotated and trimmed, the
+    // sheet has its 2x1 pixels as a column
+    const TEXTURE_PACKER: &str = r##"{
+        "frames": {
+            "a": {
+                "frame": { "x": 0, "y": 2, "w": 2, "h": 1 },
+                "rotated": true,
+                "trimmed": true,
+                "spriteSourceSize": { "x": 1, "y": 1, "w": 2, "h": 1 },
+                "sourceSize": { "w": 3, "h": 3 },
+                "pivot": { "x": 0.5, "y": 1.0 }
+            }
+        },
+        "animations": { "idle": ["a"] },
+        "meta": { "app": "https://www.codeandweb.com/texturepacker" }
+    }"##;
+
+    fn image() -> RgbaImage {
+        let mut image = RgbaImage::new(4, 4);
+        image.put_pixel(0, 2, Rgba([255, 0, 0, 255]));
+        image.put_pixel(0, 3, Rgba([0, 255, 0, 255]));
+        image
+    }
+
+    #[test]
+    pub fn test_import() {
+        let sheet = SpriteSheetBuilder::new()
+            .with_json(ASEPRITE, &image())
+            .unwrap()
+            .with_json(TEXTURE_PACKER, &image())
+            .unwrap()
+            .build_serializeable();
+
+        let names: Vec<_> = sheet.frames.iter().map(|frame| &frame.name[..]).collect();
+        assert_eq!(names, ["walk 0.aseprite", "walk 1.aseprite", "a"]);
+
+        let walk = sheet.tag("walk").unwrap();
+        assert_eq!(walk.frames, ["walk 0.aseprite", "walk 1.aseprite"]);
+        assert_eq!(walk.direction, TagDirection::PingPong);
+        assert_eq!(walk.repeat, Some(2));
+        assert_eq!(sheet.tag("idle").unwrap().frames, ["a"]);
+
+        let frame = sheet.frame("walk 1.aseprite").unwrap();
+        assert_eq!(frame.duration, Some(0.15));
+        assert_eq!(frame.pivot, Vec2::new(0.5, 1.0));
+
+        let frame = sheet.frame("a").unwrap();
+        assert_eq!(frame.source_size, Rect::new(3, 3));
+        assert_eq!(frame.trim, PositionedRect::new(0, 0, 3, 3));
+        assert_eq!(frame.duration, None);
+    }
+
+    #[test]
+    pub fn test_rotated_trimmed() {
+        let json: super::JsonSheet = serde_json::from_str(TEXTURE_PACKER).unwrap();
+        let (_, frame) = &json.frames[0];
+
+        let sprite = super::extract(&image(), frame).unwrap();
+        assert_eq!(sprite.dimensions(), (2, 1));
+        assert_eq!(sprite.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(sprite.get_pixel(1, 0).0, [0, 255, 0, 255]);
+
+        let full = super::untrim(&sprite, PositionedRect::new(1, 1, 2, 1), Rect::new(3, 3));
+        assert_eq!(full.get_pixel(1, 1).0, [255, 0, 0, 255]);
+        assert_eq!(full.get_pixel(2, 1).0, [0, 255, 0, 255]);
+        assert_eq!(full.get_pixel(0, 0).0, [0, 0, 0, 0]);
+
+        let duplicate = SpriteSheetBuilder::new()
+            .with_json(TEXTURE_PACKER, &image())
+            .unwrap()
+            .with_json(TEXTURE_PACKER, &image());
+        assert!(matches!(
+            duplicate,
+            Err(SpriteSheetError::DuplicateFrame(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_invalid_sheet() {
+        let invalid = ASEPRITE.replace(r#""to": 1"#, r#""to": 5"#);
+        let mut builder = SpriteSheetBuilder::new();
+        assert!(matches!(
+            builder.insert_json(&invalid, &image()),
+            Err(SpriteSheetError::InvalidTag(_))
+        ));
+
+        // nothing from the invalid sheet was added
+        assert_eq!(builder.atlas.stats().unwrap().rects, 0);
+        assert!(builder.frames.is_empty() && builder.tags.is_empty());
+        builder.insert_json(ASEPRITE, &image()).unwrap();
+        assert_eq!(builder.frames.len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 pub use crate::{
-    batch::prelude::*, buffer::prelude::*, color::*, frame::prelude::*, packer::prelude::*,
-    shader::prelude::*, target::prelude::*, texture::prelude::*, util::*, *,
+    batch::prelude::*, buffer::prelude::*, camera::*, color::*, frame::prelude::*, graph::*,
+    packer::prelude::*, shader::prelude::*, target::prelude::*, texture::prelude::*,
+    tilemap::prelude::*, util::*, *,
 };
 
 pub use winit::{
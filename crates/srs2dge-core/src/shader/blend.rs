@@ -0,0 +1,73 @@
+use wgpu::{BlendComponent, BlendFactor, BlendOperation, BlendState};
+
+//
+
+/// Color blending presets for
+/// [`super::builder::ShaderBuilder::with_blend`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BlendMode {
+    /// no blending, overwrite the old color
+    Replace,
+
+    /// regular transparency
+    #[default]
+    Alpha,
+
+    /// transparency for colors that
+    /// are already multiplied by alpha
+    PremultipliedAlpha,
+
+    /// add the colors together,
+    /// particles and glow
+    Additive,
+
+    /// multiply the colors together,
+    /// lighting and shadows
+    Multiply,
+
+    /// any other blend state
+    Custom(BlendState),
+}
+
+//
+
+impl BlendMode {
+    pub const fn state(self) -> BlendState {
+        match self {
+            BlendMode::Replace => BlendState::REPLACE,
+            BlendMode::Alpha => BlendState::ALPHA_BLENDING,
+            BlendMode::PremultipliedAlpha => BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+            },
+            BlendMode::Multiply => BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Dst,
+                    dst_factor: BlendFactor::Zero,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::DstAlpha,
+                    dst_factor: BlendFactor::Zero,
+                    operation: BlendOperation::Add,
+                },
+            },
+            BlendMode::Custom(state) => state,
+        }
+    }
+}
+
+impl From<BlendState> for BlendMode {
+    fn from(state: BlendState) -> Self {
+        Self::Custom(state)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{layout::AutoLayout, module::ShaderModule, Shader};
+use super::{blend::BlendMode, layout::AutoLayout, module::ShaderModule, ComputeShader, Shader};
 use crate::{
     buffer::{
         index::{DefaultIndex, Index},
@@ -6,12 +6,14 @@ use crate::{
     },
     label,
     target::Target,
+    texture::DEPTH_STENCIL_FORMAT,
 };
 use std::marker::PhantomData;
 use wgpu::{
-    BlendState, ColorTargetState, ColorWrites, FragmentState, FrontFace, MultisampleState,
-    PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology,
-    RenderPipelineDescriptor, TextureFormat, VertexState,
+    ColorTargetState, ColorWrites, CompareFunction, ComputePipelineDescriptor, DepthBiasState,
+    DepthStencilState, FragmentState, FrontFace, MultisampleState, PipelineLayoutDescriptor,
+    PolygonMode, PrimitiveState, PrimitiveTopology, RenderPipelineDescriptor, StencilState,
+    TextureFormat, VertexState,
 };
 
 //
@@ -29,11 +31,21 @@ pub struct ShaderBuilder<
     format: Option<TextureFormat>,
     layout: Option<PipelineLayoutDescriptor<'s>>,
     topology: PrimitiveTopology,
+    depth: Option<(bool, CompareFunction)>,
+    stencil: Option<StencilState>,
+    blend: BlendMode,
+    sample_count: u32,
     label: Option<&'s str>,
 
     _p: PhantomData<(V, I)>,
 }
 
+pub struct ComputeShaderBuilder<'s, const CS: bool = false> {
+    pub(crate) comp: Option<(&'s ShaderModule<'s>, &'s str)>,
+    layout: Option<PipelineLayoutDescriptor<'s>>,
+    label: Option<&'s str>,
+}
+
 //
 
 impl<'s, V, I, const VS: bool, const FS: bool, const FMT: bool> Default
@@ -46,6 +58,10 @@ impl<'s, V, I, const VS: bool, const FS: bool, const FMT: bool> Default
             format: None,
             layout: None,
             topology: PrimitiveTopology::TriangleStrip,
+            depth: None,
+            stencil: None,
+            blend: BlendMode::default(),
+            sample_count: 1,
             label: label!(),
 
             _p: PhantomData::default(),
@@ -69,6 +85,10 @@ impl<'s, V, I, const VS: bool, const FS: bool, const FMT: bool>
             format: self.format,
             layout: self.layout,
             topology: self.topology,
+            depth: self.depth,
+            stencil: self.stencil,
+            blend: self.blend,
+            sample_count: self.sample_count,
             label: self.label,
 
             _p: PhantomData::default(),
@@ -117,6 +137,45 @@ impl<'s, V, I, const VS: bool, const FS: bool, const FMT: bool>
         self
     }
 
+    /// enable depth testing
+    ///
+    /// the shader can then only be used in render
+    /// passes with a depth/stencil attachment
+    pub fn with_depth(mut self, write: bool, compare: CompareFunction) -> Self {
+        self.depth = Some((write, compare));
+        self
+    }
+
+    /// enable stencil testing
+    ///
+    /// the shader can then only be used in render
+    /// passes with a depth/stencil attachment
+    ///
+    /// the reference value is set with
+    /// [`crate::frame::render_pass::RenderPass::set_stencil_reference`]
+    pub fn with_stencil(mut self, stencil: StencilState) -> Self {
+        self.stencil = Some(stencil);
+        self
+    }
+
+    /// color blending, [`BlendMode::Alpha`] by default
+    ///
+    /// accepts [`BlendMode`] presets or a custom [`wgpu::BlendState`]
+    pub fn with_blend(mut self, blend: impl Into<BlendMode>) -> Self {
+        self.blend = blend.into();
+        self
+    }
+
+    /// MSAA sample count, `1` by default
+    ///
+    /// the shader can then only be used in render
+    /// passes with the same sample count, see
+    /// [`Target::set_multisample`]
+    pub fn with_multisample(mut self, count: u32) -> Self {
+        self.sample_count = count;
+        self
+    }
+
     pub fn with_label<'n: 's>(mut self, label: Option<&'n str>) -> Self {
         self.label = label;
         self
@@ -160,6 +219,21 @@ where
             None
         };
 
+        let depth_stencil = if self.depth.is_some() || self.stencil.is_some() {
+            let (depth_write_enabled, depth_compare) =
+                self.depth.unwrap_or((false, CompareFunction::Always));
+
+            Some(DepthStencilState {
+                format: DEPTH_STENCIL_FORMAT,
+                depth_write_enabled,
+                depth_compare,
+                stencil: self.stencil.unwrap_or_default(),
+                bias: DepthBiasState::default(),
+            })
+        } else {
+            None
+        };
+
         let pipeline = target
             .device
             .create_render_pipeline(&RenderPipelineDescriptor {
@@ -179,14 +253,17 @@ where
                     polygon_mode: PolygonMode::Fill,
                     conservative: false,
                 },
-                depth_stencil: None,
-                multisample: MultisampleState::default(),
+                depth_stencil: depth_stencil.clone(),
+                multisample: MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
                 fragment: Some(FragmentState {
                     module: &frag_mod.inner,
                     entry_point: frag_entry,
                     targets: &[Some(ColorTargetState {
                         format,
-                        blend: Some(BlendState::ALPHA_BLENDING),
+                        blend: Some(self.blend.state()),
                         write_mask: ColorWrites::ALL,
                     })],
                 }),
@@ -196,8 +273,86 @@ where
         Shader {
             pipeline,
             format,
+            depth_stencil: depth_stencil.map(|state| state.format),
+            sample_count: self.sample_count,
 
             _p: PhantomData::default(),
         }
     }
 }
+
+impl<'s, const CS: bool> Default for ComputeShaderBuilder<'s, CS> {
+    fn default() -> Self {
+        Self {
+            comp: None,
+            layout: None,
+            label: label!(),
+        }
+    }
+}
+
+impl<'s, const CS: bool> ComputeShaderBuilder<'s, CS> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn pass<const CSN: bool>(self) -> ComputeShaderBuilder<'s, CSN> {
+        ComputeShaderBuilder {
+            comp: self.comp,
+            layout: self.layout,
+            label: self.label,
+        }
+    }
+
+    pub fn with_compute<'n: 's>(
+        self,
+        module: &'n ShaderModule,
+        entry: &'n str,
+    ) -> ComputeShaderBuilder<'s, true> {
+        ComputeShaderBuilder {
+            comp: Some((module, entry)),
+            ..self.pass()
+        }
+    }
+
+    pub fn with_label<'n: 's>(mut self, label: Option<&'n str>) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn with_baked_layout<'l: 's>(
+        self,
+        layout: PipelineLayoutDescriptor<'l>,
+    ) -> ComputeShaderBuilder<'s, CS> {
+        ComputeShaderBuilder {
+            layout: Some(layout),
+            ..self.pass()
+        }
+    }
+}
+
+impl<'s> ComputeShaderBuilder<'s, true> {
+    pub fn build(self, target: &Target) -> ComputeShader {
+        let (comp_mod, comp_entry) = self.comp.unwrap();
+
+        let layout = match self.layout {
+            Some(l) => target.device.create_pipeline_layout(&l),
+            None => {
+                let a = AutoLayout::new_compute(target, (comp_mod, comp_entry));
+                let a = a.get();
+                target.device.create_pipeline_layout(&a.get())
+            }
+        };
+
+        let pipeline = target
+            .device
+            .create_compute_pipeline(&ComputePipelineDescriptor {
+                label: self.label,
+                layout: Some(&layout),
+                module: &comp_mod.inner,
+                entry_point: comp_entry,
+            });
+
+        ComputeShader { pipeline }
+    }
+}
@@ -1,23 +1,24 @@
 use crate::{label, prelude::ShaderModule, target::Target};
 use naga::{
     valid::{Capabilities, ValidationFlags, Validator},
-    AddressSpace, ImageClass, ImageDimension, Module, ScalarKind, TypeInner,
+    AddressSpace, ArraySize, ImageClass, ImageDimension, Module, ScalarKind, StorageAccess,
+    StorageFormat, TypeInner,
 };
 use std::{collections::BTreeMap, num::NonZeroU64};
 use wgpu::{
     BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
     BufferBindingType, PipelineLayoutDescriptor, SamplerBindingType, ShaderSource, ShaderStages,
-    TextureSampleType, TextureViewDimension,
+    StorageTextureAccess, TextureFormat, TextureSampleType, TextureViewDimension,
 };
 
 //
 
 pub struct AutoLayout {
-    group: BindGroupLayout,
+    groups: Vec<BindGroupLayout>,
 }
 
 pub struct AutoLayoutGetter<'a> {
-    groups: [&'a BindGroupLayout; 1],
+    groups: Vec<&'a BindGroupLayout>,
 }
 
 //
@@ -42,30 +43,53 @@ impl AutoLayout {
         let vs = Self::module(vs, vs_main, ShaderStages::VERTEX, &mut validator);
         let fs = Self::module(fs, fs_main, ShaderStages::FRAGMENT, &mut validator);
 
-        let entries: Vec<BindGroupLayoutEntry> = Self::merge(vs, fs).into_values().collect();
+        Self::from_entries(target, Self::merge(vs, fs))
+    }
 
-        let group = target
-            .device
-            .create_bind_group_layout(&BindGroupLayoutDescriptor {
-                label: label!(),
-                entries: &entries,
-            });
+    pub fn new_compute(target: &Target, (cs, cs_main): (&ShaderModule, &str)) -> Self {
+        let mut validator = Validator::new(ValidationFlags::all(), Capabilities::all());
+        let cs = Self::module(cs, cs_main, ShaderStages::COMPUTE, &mut validator);
 
-        Self { group }
+        Self::from_entries(target, Self::merge(cs, vec![]))
     }
 
     pub fn get(&self) -> AutoLayoutGetter {
         AutoLayoutGetter {
-            groups: [&self.group],
+            groups: self.groups.iter().collect(),
         }
     }
 
+    // one layout for every group up to the
+    // last used one, unused groups are empty
+    fn from_entries(target: &Target, entries: BTreeMap<(u32, u32), BindGroupLayoutEntry>) -> Self {
+        let count = entries.keys().last().map_or(1, |(group, _)| group + 1);
+
+        let groups = (0..count)
+            .map(|group| {
+                let entries: Vec<BindGroupLayoutEntry> = entries
+                    .iter()
+                    .filter(|((g, _), _)| *g == group)
+                    .map(|(_, entry)| *entry)
+                    .collect();
+
+                target
+                    .device
+                    .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                        label: label!(),
+                        entries: &entries,
+                    })
+            })
+            .collect();
+
+        Self { groups }
+    }
+
     fn module(
         module: &ShaderModule,
         entry: &str,
         visibility: ShaderStages,
         validator: &mut Validator,
-    ) -> Vec<BindGroupLayoutEntry> {
+    ) -> Vec<(u32, BindGroupLayoutEntry)> {
         let module = parse(&module.source);
 
         let i = module
@@ -92,7 +116,7 @@ impl AutoLayout {
                 let size = layouter[ty];
                 let ty = module.types.get_handle(ty).unwrap();
 
-                match (&ty.inner, space) {
+                let entry = match (&ty.inner, space) {
                     (TypeInner::Sampler { .. }, _) => Some(BindGroupLayoutEntry {
                         binding: bind.binding,
                         visibility,
@@ -106,40 +130,52 @@ impl AutoLayout {
                             class,
                         },
                         _,
-                    ) => Some(BindGroupLayoutEntry {
-                        binding: bind.binding,
-                        visibility,
-                        ty: BindingType::Texture {
-                            sample_type: match class {
-                                ImageClass::Sampled {
-                                    kind: ScalarKind::Float,
-                                    ..
-                                } => TextureSampleType::Float { filterable: false },
-                                ImageClass::Sampled {
-                                    kind: ScalarKind::Sint,
-                                    ..
-                                } => TextureSampleType::Sint,
-                                ImageClass::Sampled {
-                                    kind: ScalarKind::Uint,
-                                    ..
-                                } => TextureSampleType::Uint,
-                                ImageClass::Depth { .. } => TextureSampleType::Depth,
-                                ImageClass::Storage { .. } => todo!(),
-                                _ => todo!(),
+                    ) => {
+                        let view_dimension = match (dim, arrayed) {
+                            (ImageDimension::D1, false) => TextureViewDimension::D1,
+                            (ImageDimension::D2, false) => TextureViewDimension::D2,
+                            (ImageDimension::D2, true) => TextureViewDimension::D2Array,
+                            (ImageDimension::D3, false) => TextureViewDimension::D3,
+                            (ImageDimension::Cube, false) => TextureViewDimension::Cube,
+                            (ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
+                            _ => unimplemented!(),
+                        };
+
+                        let ty = match class {
+                            ImageClass::Storage { format, access } => BindingType::StorageTexture {
+                                access: storage_texture_access(*access),
+                                format: storage_format(*format),
+                                view_dimension,
                             },
-                            view_dimension: match (dim, arrayed) {
-                                (ImageDimension::D1, false) => TextureViewDimension::D1,
-                                (ImageDimension::D2, false) => TextureViewDimension::D2,
-                                (ImageDimension::D2, true) => TextureViewDimension::D2Array,
-                                (ImageDimension::D3, false) => TextureViewDimension::D3,
-                                (ImageDimension::Cube, false) => TextureViewDimension::Cube,
-                                (ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
-                                _ => unimplemented!(),
+                            _ => BindingType::Texture {
+                                sample_type: match class {
+                                    ImageClass::Sampled {
+                                        kind: ScalarKind::Float,
+                                        ..
+                                    } => TextureSampleType::Float { filterable: false },
+                                    ImageClass::Sampled {
+                                        kind: ScalarKind::Sint,
+                                        ..
+                                    } => TextureSampleType::Sint,
+                                    ImageClass::Sampled {
+                                        kind: ScalarKind::Uint,
+                                        ..
+                                    } => TextureSampleType::Uint,
+                                    ImageClass::Depth { .. } => TextureSampleType::Depth,
+                                    _ => todo!(),
+                                },
+                                view_dimension,
+                                multisampled: false,
                             },
-                            multisampled: false,
-                        },
-                        count: None,
-                    }),
+                        };
+
+                        Some(BindGroupLayoutEntry {
+                            binding: bind.binding,
+                            visibility,
+                            ty,
+                            count: None,
+                        })
+                    }
                     (_, AddressSpace::Uniform) => Some(BindGroupLayoutEntry {
                         binding: bind.binding,
                         visibility,
@@ -150,25 +186,47 @@ impl AutoLayout {
                         },
                         count: None,
                     }),
+                    (inner, AddressSpace::Storage { access }) => Some(BindGroupLayoutEntry {
+                        binding: bind.binding,
+                        visibility,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Storage {
+                                read_only: !access.contains(StorageAccess::STORE),
+                            },
+                            has_dynamic_offset: false,
+                            // the size of runtime sized arrays
+                            // is validated when binding instead
+                            min_binding_size: if is_runtime_sized(&module, inner) {
+                                None
+                            } else {
+                                NonZeroU64::new(size.size as _)
+                            },
+                        },
+                        count: None,
+                    }),
                     other => unimplemented!("Unimplemented: {other:?}"),
-                }
+                };
+                entry.map(|entry| (bind.group, entry))
             })
             .collect()
     }
 
+    // entries by their group and binding
     fn merge(
-        vs: Vec<BindGroupLayoutEntry>,
-        fs: Vec<BindGroupLayoutEntry>,
-    ) -> BTreeMap<u32, BindGroupLayoutEntry> {
-        let mut first: BTreeMap<u32, BindGroupLayoutEntry> =
-            vs.into_iter().map(|entry| (entry.binding, entry)).collect();
-
-        for mut entry in fs.into_iter() {
-            if let Some(existing_entry) = first.get(&entry.binding) {
+        vs: Vec<(u32, BindGroupLayoutEntry)>,
+        fs: Vec<(u32, BindGroupLayoutEntry)>,
+    ) -> BTreeMap<(u32, u32), BindGroupLayoutEntry> {
+        let mut first: BTreeMap<(u32, u32), BindGroupLayoutEntry> = vs
+            .into_iter()
+            .map(|(group, entry)| ((group, entry.binding), entry))
+            .collect();
+
+        for (group, mut entry) in fs.into_iter() {
+            if let Some(existing_entry) = first.get(&(group, entry.binding)) {
                 entry.visibility |= existing_entry.visibility;
-                first.insert(entry.binding, entry);
+                first.insert((group, entry.binding), entry);
             } else {
-                first.insert(entry.binding, entry);
+                first.insert((group, entry.binding), entry);
             }
         }
 
@@ -178,6 +236,67 @@ impl AutoLayout {
 
 //
 
+// a runtime sized array or a struct that ends in one
+fn is_runtime_sized(module: &Module, ty: &TypeInner) -> bool {
+    match ty {
+        TypeInner::Array {
+            size: ArraySize::Dynamic,
+            ..
+        } => true,
+        TypeInner::Struct { members, .. } => members.last().map_or(false, |last| {
+            is_runtime_sized(module, &module.types[last.ty].inner)
+        }),
+        _ => false,
+    }
+}
+
+fn storage_texture_access(access: StorageAccess) -> StorageTextureAccess {
+    if access.contains(StorageAccess::LOAD | StorageAccess::STORE) {
+        StorageTextureAccess::ReadWrite
+    } else if access.contains(StorageAccess::STORE) {
+        StorageTextureAccess::WriteOnly
+    } else {
+        StorageTextureAccess::ReadOnly
+    }
+}
+
+fn storage_format(format: StorageFormat) -> TextureFormat {
+    match format {
+        StorageFormat::R8Unorm => TextureFormat::R8Unorm,
+        StorageFormat::R8Snorm => TextureFormat::R8Snorm,
+        StorageFormat::R8Uint => TextureFormat::R8Uint,
+        StorageFormat::R8Sint => TextureFormat::R8Sint,
+        StorageFormat::R16Uint => TextureFormat::R16Uint,
+        StorageFormat::R16Sint => TextureFormat::R16Sint,
+        StorageFormat::R16Float => TextureFormat::R16Float,
+        StorageFormat::Rg8Unorm => TextureFormat::Rg8Unorm,
+        StorageFormat::Rg8Snorm => TextureFormat::Rg8Snorm,
+        StorageFormat::Rg8Uint => TextureFormat::Rg8Uint,
+        StorageFormat::Rg8Sint => TextureFormat::Rg8Sint,
+        StorageFormat::R32Uint => TextureFormat::R32Uint,
+        StorageFormat::R32Sint => TextureFormat::R32Sint,
+        StorageFormat::R32Float => TextureFormat::R32Float,
+        StorageFormat::Rg16Uint => TextureFormat::Rg16Uint,
+        StorageFormat::Rg16Sint => TextureFormat::Rg16Sint,
+        StorageFormat::Rg16Float => TextureFormat::Rg16Float,
+        StorageFormat::Rgba8Unorm => TextureFormat::Rgba8Unorm,
+        StorageFormat::Rgba8Snorm => TextureFormat::Rgba8Snorm,
+        StorageFormat::Rgba8Uint => TextureFormat::Rgba8Uint,
+        StorageFormat::Rgba8Sint => TextureFormat::Rgba8Sint,
+        StorageFormat::Rgb10a2Unorm => TextureFormat::Rgb10a2Unorm,
+        StorageFormat::Rg11b10Float => TextureFormat::Rg11b10Float,
+        StorageFormat::Rg32Uint => TextureFormat::Rg32Uint,
+        StorageFormat::Rg32Sint => TextureFormat::Rg32Sint,
+        StorageFormat::Rg32Float => TextureFormat::Rg32Float,
+        StorageFormat::Rgba16Uint => TextureFormat::Rgba16Uint,
+        StorageFormat::Rgba16Sint => TextureFormat::Rgba16Sint,
+        StorageFormat::Rgba16Float => TextureFormat::Rgba16Float,
+        StorageFormat::Rgba32Uint => TextureFormat::Rgba32Uint,
+        StorageFormat::Rgba32Sint => TextureFormat::Rgba32Sint,
+        StorageFormat::Rgba32Float => TextureFormat::Rgba32Float,
+    }
+}
+
 fn parse(source: &ShaderSource) -> Module {
     match source {
         #[cfg(feature = "spirv")]
@@ -214,3 +333,36 @@ fn parse(source: &ShaderSource) -> Module {
         _ => todo!(),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::is_runtime_sized;
+
+    #[test]
+    pub fn test_runtime_sized() {
+        let module = naga::front::wgsl::parse_str(
+            "struct Fixed { a: vec4<f32>, b: u32, };
+            struct Particles { count: u32, items: array<vec4<f32>>, };",
+        )
+        .unwrap();
+
+        let sized: Vec<_> = module
+            .types
+            .iter()
+            .filter(|(_, ty)| ty.name.is_some())
+            .map(|(_, ty)| {
+                (
+                    ty.name.clone().unwrap(),
+                    is_runtime_sized(&module, &ty.inner),
+                )
+            })
+            .collect();
+        assert_eq!(
+            sized,
+            [
+                ("Fixed".to_string(), false),
+                ("Particles".to_string(), true)
+            ]
+        );
+    }
+}
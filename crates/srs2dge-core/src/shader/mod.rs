@@ -1,10 +1,11 @@
-use self::builder::ShaderBuilder;
+use self::builder::{ComputeShaderBuilder, ShaderBuilder};
 use crate::buffer::{index::Index, vertex::Vertex};
 use std::marker::PhantomData;
-use wgpu::{BindGroup, BindGroupLayout, Device, RenderPipeline, TextureFormat};
+use wgpu::{BindGroup, BindGroupLayout, ComputePipeline, Device, RenderPipeline, TextureFormat};
 
 //
 
+pub mod blend;
 pub mod builder;
 pub mod layout;
 pub mod module;
@@ -20,10 +21,17 @@ where
 {
     pub(crate) pipeline: RenderPipeline,
     pub(crate) format: TextureFormat,
+    pub(crate) depth_stencil: Option<TextureFormat>,
+    pub(crate) sample_count: u32,
 
     _p: PhantomData<(V, I)>,
 }
 
+#[derive(Debug)]
+pub struct ComputeShader {
+    pub(crate) pipeline: ComputePipeline,
+}
+
 //
 
 pub trait Layout<'a> {
@@ -44,3 +52,24 @@ where
         ShaderBuilder::<'s, V, I>::new()
     }
 }
+
+impl ComputeShader {
+    pub fn builder<'s>() -> ComputeShaderBuilder<'s> {
+        ComputeShaderBuilder::<'s>::new()
+    }
+
+    /// the bind group layout of this shader
+    ///
+    /// useful with automatically generated
+    /// layouts for creating bind groups
+    pub fn bind_group_layout(&self) -> BindGroupLayout {
+        self.bind_group_layout_at(0)
+    }
+
+    /// the bind group layout of group `index`
+    ///
+    /// see [`Self::bind_group_layout`]
+    pub fn bind_group_layout_at(&self, index: u32) -> BindGroupLayout {
+        self.pipeline.get_bind_group_layout(index)
+    }
+}
@@ -1 +1 @@
-pub use super::{builder::*, layout::*, module::*, *};
+pub use super::{blend::*, builder::*, layout::*, module::*, *};
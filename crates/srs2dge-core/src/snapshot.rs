@@ -0,0 +1,329 @@
+//! Golden-image snapshot testing
+//!
+//! Renders a frame with a headless [`Target`]
+//! and compares it against a stored PNG
+//!
+//! Usage in pseudocode:
+//! ```ignore
+//! let mut target = Snapshot::target(&engine, Rect::new(64, 64)).await;
+//! Snapshot::new("tests/snapshots")
+//!     .render(&mut target, "quad", |target, frame| {
+//!         draw the scene into the primary render pass
+//!     })
+//!     .await
+//!     .unwrap();
+//! ```
+//!
+//! References are (re)generated instead of
+//! compared when the `SRS2DGE_BLESS` environment
+//! variable is set or with [`Snapshot::with_bless`]
+//!
+//! Requires the `snapshot` feature
+
+use crate::{
+    prelude::{Frame, Rect},
+    target::Target,
+    texture::serde::SerializeableTexture,
+    Engine,
+};
+use image::{ImageError, Rgba, RgbaImage};
+use std::{fs, io, path::PathBuf};
+use wgpu::TextureFormat;
+
+//
+
+/// Environment variable that turns on bless mode
+pub const BLESS_ENV: &str = "SRS2DGE_BLESS";
+
+//
+
+/// Golden-image comparison settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// directory where the reference PNGs are stored
+    dir: PathBuf,
+
+    /// max per channel difference
+    /// that is still considered equal
+    tolerance: u8,
+
+    /// write the references instead
+    /// of comparing against them
+    bless: bool,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// the reference image does not exist yet
+    Missing(PathBuf),
+
+    /// the rendered image and the reference
+    /// image have different dimensions
+    SizeMismatch {
+        expected: Rect,
+        got: Rect,
+    },
+
+    /// some pixels differ more than the tolerance
+    /// allows, the diff image was written to `diff`
+    Mismatch {
+        differing: usize,
+        diff: PathBuf,
+    },
+
+    Image(ImageError),
+    Io(io::Error),
+}
+
+//
+
+impl Snapshot {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tolerance: 2,
+            bless: std::env::var_os(BLESS_ENV).is_some(),
+        }
+    }
+
+    /// max per channel difference
+    /// that is still considered equal
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// write the references instead
+    /// of comparing against them
+    pub fn with_bless(mut self, bless: bool) -> Self {
+        self.bless = bless;
+        self
+    }
+
+    /// Create a headless render target
+    /// to render snapshots with
+    pub async fn target(engine: &Engine, dim: Rect) -> Target {
+        engine
+            .new_target_headless_with(dim, TextureFormat::Rgba8Unorm)
+            .await
+    }
+
+    /// path to the reference image `name`
+    pub fn reference_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.png"))
+    }
+
+    /// Render one frame with `draw` and
+    /// compare the result with the reference
+    /// image `name`
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `target` is not headless
+    pub async fn render<F>(
+        &self,
+        target: &mut Target,
+        name: &str,
+        draw: F,
+    ) -> Result<(), SnapshotError>
+    where
+        F: FnOnce(&mut Target, &mut Frame),
+    {
+        let mut frame = target.get_frame();
+        draw(target, &mut frame);
+        // submit
+        drop(frame);
+
+        let texture = target
+            .get_headless_texture()
+            .expect("Snapshots require a headless target");
+        let (image, _) = SerializeableTexture::download(texture, target, Some(name.to_string()))
+            .await
+            .split();
+
+        self.compare(name, &image)
+    }
+
+    /// Compare `image` with the reference image `name`
+    pub fn compare(&self, name: &str, image: &RgbaImage) -> Result<(), SnapshotError> {
+        let path = self.reference_path(name);
+
+        if self.bless {
+            fs::create_dir_all(&self.dir)?;
+            image.save(&path)?;
+            tracing::info!("Blessed snapshot {path:?}");
+            return Ok(());
+        }
+
+        if !path.exists() {
+            return Err(SnapshotError::Missing(path));
+        }
+
+        let expected = image::open(&path)?.into_rgba8();
+        if expected.dimensions() != image.dimensions() {
+            return Err(SnapshotError::SizeMismatch {
+                expected: expected.dimensions().into(),
+                got: image.dimensions().into(),
+            });
+        }
+
+        let (diff, differing) = diff_image(&expected, image, self.tolerance);
+        if differing == 0 {
+            return Ok(());
+        }
+
+        let diff_path = self.dir.join(format!("{name}.diff.png"));
+        diff.save(&diff_path)?;
+        image.save(self.dir.join(format!("{name}.actual.png")))?;
+
+        Err(SnapshotError::Mismatch {
+            differing,
+            diff: diff_path,
+        })
+    }
+}
+
+/// Generate an image that highlights the
+/// pixels that differ more than `tolerance`
+///
+/// Equal pixels are dimmed and differing pixels
+/// are red. Returns the image and the number of
+/// differing pixels.
+pub fn diff_image(expected: &RgbaImage, got: &RgbaImage, tolerance: u8) -> (RgbaImage, usize) {
+    let mut differing = 0;
+    let diff = RgbaImage::from_fn(expected.width(), expected.height(), |x, y| {
+        let a = expected.get_pixel(x, y);
+        let b = got.get_pixel(x, y);
+
+        if pixel_eq(a, b, tolerance) {
+            let luma = (a.0[0] as u32 + a.0[1] as u32 + a.0[2] as u32) / 3 / 4;
+            Rgba([luma as u8, luma as u8, luma as u8, 255])
+        } else {
+            differing += 1;
+            Rgba([255, 0, 0, 255])
+        }
+    });
+
+    (diff, differing)
+}
+
+fn pixel_eq(a: &Rgba<u8>, b: &Rgba<u8>, tolerance: u8) -> bool {
+    a.0.iter()
+        .zip(b.0.iter())
+        .all(|(a, b)| a.abs_diff(*b) <= tolerance)
+}
+
+//
+
+impl From<ImageError> for SnapshotError {
+    fn from(err: ImageError) -> Self {
+        Self::Image(err)
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Snapshot, SnapshotError};
+    use crate::{color::Color, packer::rect::Rect, Engine};
+    use image::{Rgba, RgbaImage};
+    use std::path::PathBuf;
+    use wgpu::RequestAdapterOptions;
+
+    fn dir(test: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join("srs2dge-test")
+            .join("snapshot")
+            .join(test)
+    }
+
+    fn checker(tint: u8) -> RgbaImage {
+        RgbaImage::from_fn(16, 16, |x, y| {
+            if (x / 4 + y / 4) % 2 == 0 {
+                Rgba([tint, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 255])
+            }
+        })
+    }
+
+    #[test]
+    pub fn test_bless_and_compare() {
+        let snapshot = Snapshot::new(dir("compare")).with_tolerance(4);
+        snapshot
+            .clone()
+            .with_bless(true)
+            .compare("checker", &checker(200))
+            .unwrap();
+
+        // within tolerance
+        snapshot
+            .clone()
+            .with_bless(false)
+            .compare("checker", &checker(203))
+            .unwrap();
+
+        // outside of tolerance
+        match snapshot.with_bless(false).compare("checker", &checker(100)) {
+            Err(SnapshotError::Mismatch { differing, diff }) => {
+                assert_eq!(differing, 16 * 16 / 2);
+                assert!(diff.exists());
+            }
+            other => panic!("expected a mismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    pub fn test_missing() {
+        let snapshot = Snapshot::new(dir("missing")).with_bless(false);
+        assert!(matches!(
+            snapshot.compare("does-not-exist", &checker(0)),
+            Err(SnapshotError::Missing(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_render() {
+        let rt = crate::init_tokio();
+        rt.block_on(async {
+            let engine = Engine::new();
+            let adapter = engine
+                .get_instance()
+                .request_adapter(&RequestAdapterOptions::default())
+                .await;
+            if adapter.is_none() {
+                tracing::warn!("No GPU, skipping the snapshot render test");
+                return;
+            }
+
+            let snapshot = Snapshot::new(dir("render")).with_bless(false);
+            std::fs::create_dir_all(dir("render")).unwrap();
+            RgbaImage::from_pixel(8, 4, Rgba([255, 0, 0, 255]))
+                .save(snapshot.reference_path("clear"))
+                .unwrap();
+
+            let mut target = Snapshot::target(&engine, Rect::new(8, 4)).await;
+            snapshot
+                .render(&mut target, "clear", |_, frame| {
+                    frame.set_clear_color(Color::RED);
+                    frame.primary_render_pass();
+                })
+                .await
+                .unwrap();
+
+            // a different clear color is detected
+            let result = snapshot
+                .render(&mut target, "clear", |_, frame| {
+                    frame.set_clear_color(Color::BLUE);
+                    frame.primary_render_pass();
+                })
+                .await;
+            assert!(matches!(result, Err(SnapshotError::Mismatch { .. })));
+        });
+    }
+}
@@ -0,0 +1,613 @@
+use self::{
+    belt::BeltPool,
+    catcher::Catcher,
+    poll::PollThread,
+    surface::{ISurface, Surface},
+};
+use crate::{
+    label,
+    prelude::{Frame, Rect},
+    texture::{
+        sampler::SamplerBuilder, DepthStencilTexture, MultisampleTexture, RenderTargetTexture,
+        DEPTH_STENCIL_FORMAT,
+    },
+    DeviceStorage,
+};
+use colorful::Colorful;
+use main_game_loop::event::Event;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use wgpu::{
+    util::power_preference_from_env, Adapter, Device, DeviceDescriptor, Features, Instance, Limits,
+    PowerPreference, Queue, RequestAdapterOptionsBase, TextureFormat, TextureFormatFeatureFlags,
+};
+use winit::{event::WindowEvent, window::Window};
+
+//
+
+pub mod prelude;
+pub mod surface;
+
+//
+
+/// The size of the offscreen render target
+/// used by [`Target::new_headless`]
+pub const DEFAULT_HEADLESS_DIM: Rect = Rect::new(512, 512);
+
+//
+
+mod belt;
+mod catcher;
+mod poll;
+
+//
+
+/// [`Target::set_multisample`] errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultisampleError {
+    /// only 1, 2, 4 and 8 samples are allowed
+    InvalidSampleCount(u32),
+
+    /// the format can't be multisampled
+    /// or resolved on this adapter
+    UnsupportedFormat(TextureFormat),
+}
+
+//
+
+/// This handles the gpu logical device instance
+pub struct Target {
+    adapter: Arc<Adapter>,
+    pub(crate) device: Arc<Device>,
+    pub(crate) queue: Arc<Queue>,
+
+    pub(crate) surface: Option<Surface>,
+    pub(crate) headless: Option<RenderTargetTexture>,
+    pub(crate) depth_stencil: Option<DepthStencilTexture>,
+    depth_stencil_enabled: bool,
+    pub(crate) multisample: Option<MultisampleTexture>,
+    sample_count: u32,
+    pub(crate) belts: Arc<BeltPool>,
+    pub(crate) samplers: Mutex<HashMap<SamplerBuilder, Arc<wgpu::Sampler>>>,
+    catcher: Catcher,
+
+    // tracing
+    frame_id: AtomicUsize,
+
+    // thread to poll the device
+    _poll: PollThread,
+}
+
+//
+
+impl Target {
+    /// Create a new render target that is bound to a window
+    pub async fn new(
+        instance: Arc<Instance>,
+        window: Arc<Window>,
+        device_storage: DeviceStorage,
+    ) -> Self {
+        // create a surface that is compatible with both the window and the instance
+        let surface = ISurface::new(window, instance.clone());
+
+        // create a device and a queue for it
+        let (adapter, device, queue) =
+            Self::new_with_opt(instance, Some(&surface), device_storage).await;
+
+        // complete the surface (ready for rendering)
+        let surface = Some(surface.complete(&adapter, device.clone()));
+
+        Self::new_finish(adapter, device, queue, surface)
+    }
+
+    /// Create a new render target that doesn't require a window
+    ///
+    /// Frames are drawn into an offscreen
+    /// [`DEFAULT_HEADLESS_DIM`] sized
+    /// [`TextureFormat::Rgba8Unorm`] texture
+    pub async fn new_headless(instance: Arc<Instance>, device_storage: DeviceStorage) -> Self {
+        Self::new_headless_with(
+            instance,
+            device_storage,
+            DEFAULT_HEADLESS_DIM,
+            TextureFormat::Rgba8Unorm,
+        )
+        .await
+    }
+
+    /// Create a new render target that doesn't require a window
+    ///
+    /// Frames are drawn into an offscreen
+    /// texture with the given size and format
+    pub async fn new_headless_with(
+        instance: Arc<Instance>,
+        device_storage: DeviceStorage,
+        dim: Rect,
+        format: TextureFormat,
+    ) -> Self {
+        let (adapter, device, queue) = Self::new_with_opt(instance, None, device_storage).await;
+
+        let mut target = Self::new_finish(adapter, device, queue, None);
+        target.headless = Some(RenderTargetTexture::new_format(
+            &target,
+            dim,
+            format,
+            Some("HeadlessRenderTarget"),
+        ));
+        target
+    }
+
+    /// check if objects created with `self` target
+    /// can be used with the `other` target
+    ///
+    /// this just checks if both [`Target`]s share their
+    /// logical devices
+    pub fn compatible_with(&self, other: &Target) -> bool {
+        Arc::ptr_eq(&self.device, &other.device) && Arc::ptr_eq(&self.queue, &other.queue)
+    }
+
+    /// reconfigures the swapchain if the window is
+    /// resized
+    ///
+    /// calling this in the event function is not
+    /// often needed, but it is recommended
+    ///
+    /// wayland requires calling this (idk why)
+    ///
+    /// [`Self::resized`] is an alternative to this
+    pub fn event(&mut self, event: &Event) {
+        let Some(window) = self.get_window() else {
+            return;
+        };
+
+        if let Event::WindowEvent {
+            window_id,
+            event: WindowEvent::Resized(_),
+        } = event
+        {
+            if *window_id == window.id() {
+                self.resized();
+            }
+        }
+    }
+
+    /// reconfigures the swapchain if the window is
+    /// resized
+    ///
+    /// calling this in the event function is not
+    /// often needed, but it is recommended
+    ///
+    /// wayland requires calling this (idk why)
+    ///
+    /// [`Self::event`] is an alternative to this
+    pub fn resized(&mut self) {
+        let Some(surface) = &mut self.surface else {
+            return;
+        };
+
+        surface.configure();
+    }
+
+    /// recreates the offscreen render target
+    /// with a new size
+    ///
+    /// does nothing with windowed targets
+    pub fn resize_headless(&mut self, dim: Rect) {
+        let Some(format) = self.headless.as_ref().map(|texture| texture.get_format()) else {
+            return;
+        };
+
+        self.headless = Some(RenderTargetTexture::new_format(
+            self,
+            dim,
+            format,
+            Some("HeadlessRenderTarget"),
+        ));
+    }
+
+    /// get the offscreen texture that headless
+    /// frames are drawn into
+    ///
+    /// the result can be read back with
+    /// [`crate::texture::Texture::read`] after
+    /// the [`Frame`] is dropped
+    ///
+    /// not possible with windowed targets
+    pub fn get_headless_texture(&self) -> Option<&RenderTargetTexture> {
+        self.headless.as_ref()
+    }
+
+    /// enable or disable the depth/stencil attachment
+    ///
+    /// the attachment is (re)created to match
+    /// the size of the swapchain or the offscreen
+    /// texture when a frame begins
+    ///
+    /// see [`Frame::primary_render_pass_with_depth_stencil`]
+    ///
+    /// with MSAA, the adapter has to support
+    /// multisampling the depth/stencil format,
+    /// see [`Self::set_multisample`]
+    pub fn set_depth_stencil(&mut self, on: bool) -> Result<(), MultisampleError> {
+        if on
+            && self.sample_count > 1
+            && !self.supports(DEPTH_STENCIL_FORMAT, TextureFormatFeatureFlags::MULTISAMPLE)
+        {
+            return Err(MultisampleError::UnsupportedFormat(DEPTH_STENCIL_FORMAT));
+        }
+
+        self.depth_stencil_enabled = on;
+        if !on {
+            self.depth_stencil = None;
+        }
+        Ok(())
+    }
+
+    /// get the depth/stencil attachment
+    ///
+    /// `None` before the first frame or if
+    /// depth/stencil is not enabled
+    pub fn get_depth_stencil(&self) -> Option<&DepthStencilTexture> {
+        self.depth_stencil.as_ref()
+    }
+
+    /// set the MSAA sample count of primary render passes
+    ///
+    /// `1` disables MSAA, which is the default
+    ///
+    /// primary passes draw into a multisampled
+    /// attachment, that is resolved into the
+    /// swapchain or the offscreen texture
+    ///
+    /// shaders used in primary passes need the same
+    /// sample count, see
+    /// [`crate::shader::builder::ShaderBuilder::with_multisample`],
+    /// so this should be set before creating them
+    ///
+    /// the sample count has to be 1, 2, 4 or 8 and
+    /// the adapter has to support multisampling and
+    /// resolving the target format (and the
+    /// depth/stencil format if it is enabled)
+    pub fn set_multisample(&mut self, sample_count: u32) -> Result<(), MultisampleError> {
+        if !matches!(sample_count, 1 | 2 | 4 | 8) {
+            return Err(MultisampleError::InvalidSampleCount(sample_count));
+        }
+
+        if sample_count > 1 {
+            let format = self.get_format();
+            if !self.supports(
+                format,
+                TextureFormatFeatureFlags::MULTISAMPLE
+                    | TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE,
+            ) {
+                return Err(MultisampleError::UnsupportedFormat(format));
+            }
+            if self.depth_stencil_enabled
+                && !self.supports(DEPTH_STENCIL_FORMAT, TextureFormatFeatureFlags::MULTISAMPLE)
+            {
+                return Err(MultisampleError::UnsupportedFormat(DEPTH_STENCIL_FORMAT));
+            }
+        }
+
+        self.sample_count = sample_count;
+        self.multisample = None;
+        // the depth/stencil attachment needs the same sample count
+        self.depth_stencil = None;
+        Ok(())
+    }
+
+    /// get the MSAA sample count of primary render passes
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// can textures of this format be
+    /// sampled with a filtering sampler
+    /// on this adapter
+    pub fn is_filterable(&self, format: TextureFormat) -> bool {
+        self.supports(format, TextureFormatFeatureFlags::FILTERABLE)
+    }
+
+    /// start rendering a new frame
+    ///
+    /// the first frame sets the window visible
+    ///
+    /// headless targets draw into their
+    /// offscreen texture instead,
+    /// see [`Self::get_headless_texture`]
+    #[must_use]
+    pub fn get_frame(&mut self) -> Frame {
+        let frame_id = self.frame_id.fetch_add(1, Ordering::Relaxed);
+
+        // first frame sets the window visible
+        if frame_id == 0 {
+            if let Some(window) = self.get_window() {
+                window.set_visible(true);
+            }
+        }
+
+        let mut frame = if let Some(surface) = self.surface.as_mut() {
+            Frame::new(
+                &self.device,
+                self.queue.clone(),
+                surface,
+                self.belts.clone(),
+                frame_id,
+            )
+        } else {
+            Frame::new_headless(
+                &self.device,
+                self.queue.clone(),
+                self.headless
+                    .as_ref()
+                    .expect("Headless target is missing its render target texture"),
+                self.belts.clone(),
+                frame_id,
+            )
+        };
+
+        // resize with the swapchain
+        // (minimized windows have no size)
+        let dim = Rect::from(frame.get_dim());
+        let has_size = dim.width != 0 && dim.height != 0;
+
+        if self.depth_stencil_enabled {
+            let outdated =
+                self.depth_stencil.as_ref().map(|texture| texture.get_dim()) != Some(dim);
+            if outdated && has_size {
+                self.depth_stencil = Some(DepthStencilTexture::new_multisampled(
+                    self,
+                    dim,
+                    DEPTH_STENCIL_FORMAT,
+                    self.sample_count,
+                    Some("DepthStencil"),
+                ));
+            }
+
+            if let Some(depth_stencil) = self.depth_stencil.as_ref() {
+                frame.set_depth_stencil(depth_stencil);
+            }
+        }
+
+        if self.sample_count > 1 {
+            let format = self.get_format();
+            let outdated = self
+                .multisample
+                .as_ref()
+                .map(|texture| (texture.get_dim(), texture.get_format()))
+                != Some((dim, format));
+            if outdated && has_size {
+                self.multisample = Some(MultisampleTexture::new_multisampled(
+                    self,
+                    dim,
+                    format,
+                    self.sample_count,
+                    Some("Multisample"),
+                ));
+            }
+
+            if let Some(multisample) = self.multisample.as_ref() {
+                frame.set_multisample(multisample);
+            }
+        }
+
+        frame
+    }
+
+    /// make the first frame NOT automatically set the window visible
+    pub fn no_auto_visible(&self) {
+        self.frame_id.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// finish the frame
+    #[deprecated]
+    pub fn finish_frame(&mut self, _: Frame) {}
+
+    /// set vertical sync preference
+    ///
+    /// does nothing with headless targets
+    pub fn set_vsync(&mut self, on: bool) {
+        if let Some(s) = self.surface.as_mut() {
+            s.set_vsync(on);
+        }
+    }
+
+    /// get the current vertical sync preference
+    ///
+    /// not possible with headless targets
+    pub fn get_vsync(&self) -> Option<bool> {
+        self.surface.as_ref().map(|s| s.get_vsync())
+    }
+
+    /// get the window bound to this render target
+    ///
+    /// not possible with headless targets
+    pub fn get_window(&self) -> Option<Arc<Window>> {
+        self.surface.as_ref().map(|surface| surface.get_window())
+    }
+
+    /// get the texture format this target prefers
+    ///
+    /// the offscreen texture format is returned with headless targets
+    pub fn get_format(&self) -> TextureFormat {
+        self.surface
+            .as_ref()
+            .map(|surface| surface.format())
+            .or_else(|| self.headless.as_ref().map(|texture| texture.get_format()))
+            .unwrap_or(TextureFormat::Rgba8Unorm)
+    }
+
+    /// get the logical device
+    pub fn get_device(&self) -> Arc<Device> {
+        self.device.clone()
+    }
+
+    /// run something while listening for wgpu errors
+    pub fn catch_error<T, F: FnOnce(&Self) -> T>(&self, f: F) -> Result<T, String> {
+        Catcher::catch_error(self, f)
+    }
+
+    /// run something and await on it while listening for wgpu errors
+    pub async fn catch_error_async<T, Fut, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&Self) -> Fut,
+        Fut: Future<Output = T>,
+    {
+        Catcher::catch_error_async(self, f).await
+    }
+
+    async fn new_with_opt(
+        instance: Arc<Instance>,
+        surface: Option<&wgpu::Surface>,
+        device_storage: DeviceStorage,
+    ) -> (Arc<Adapter>, Arc<Device>, Arc<Queue>) {
+        // 'borrow' a device and a queue if this surface is compatible with any previous ones
+        // or create new if there were none
+        if let Some(pre_existing) = Self::try_borrow_device(surface, device_storage.clone()) {
+            // borrow
+            pre_existing
+        } else {
+            // create
+            // get a GPU
+            let adapter = Self::make_adapter(surface, &instance).await;
+
+            // print out some info about the selected GPU
+            Self::debug_report(&adapter);
+
+            // create a logical device and a queue for it
+            let (device, queue) = Self::make_device(&adapter).await;
+
+            // push to the device storage
+            if let Ok(mut write) = device_storage.write() {
+                write.push((adapter.clone(), device.clone(), queue.clone()));
+            }
+
+            (adapter, device, queue)
+        }
+    }
+
+    fn supports(&self, format: TextureFormat, flags: TextureFormatFeatureFlags) -> bool {
+        self.adapter
+            .get_texture_format_features(format)
+            .flags
+            .contains(flags)
+    }
+
+    fn try_borrow_device(
+        compatible_surface: Option<&wgpu::Surface>,
+        device_storage: DeviceStorage,
+    ) -> Option<(Arc<Adapter>, Arc<Device>, Arc<Queue>)> {
+        device_storage
+            .read()
+            .ok()?
+            .iter()
+            .find(|(adapter, _, _)| {
+                if let Some(surface) = compatible_surface {
+                    adapter.is_surface_supported(surface)
+                } else {
+                    true
+                }
+            })
+            .cloned()
+    }
+
+    async fn make_adapter(
+        compatible_surface: Option<&wgpu::Surface>,
+        instance: &Instance,
+    ) -> Arc<Adapter> {
+        let options = RequestAdapterOptionsBase {
+            power_preference: power_preference_from_env()
+                .unwrap_or(PowerPreference::HighPerformance),
+            compatible_surface,
+            ..Default::default()
+        };
+        Arc::new(
+            instance
+                .request_adapter(&options)
+                .await
+                .expect("No suitable GPUs"),
+        )
+    }
+
+    fn debug_report(adapter: &Adapter) {
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            let gpu_info = adapter.get_info();
+            let api = format!("{:?}", gpu_info.backend).red();
+            let name = gpu_info.name.blue();
+            let ty = format!("{:?}", gpu_info.device_type).green();
+
+            tracing::debug!("GPU API: {api}");
+            tracing::debug!("GPU: {name} ({ty})");
+        }
+    }
+
+    async fn make_device(adapter: &Adapter) -> (Arc<Device>, Arc<Queue>) {
+        let (device, queue) = adapter
+            .request_device(
+                &DeviceDescriptor {
+                    label: label!(),
+                    // optional, `RenderPass` falls
+                    // back to separate draw calls
+                    features: adapter.features() & Features::MULTI_DRAW_INDIRECT,
+                    // webgl2 has no compute or storage
+                    // buffers, use whatever the adapter
+                    // supports everywhere else
+                    limits: if cfg!(target_arch = "wasm32") {
+                        Limits {
+                            // max_texture_dimension_2d: 16384,
+                            ..Limits::downlevel_webgl2_defaults()
+                        }
+                    } else {
+                        adapter.limits()
+                    },
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        (Arc::new(device), Arc::new(queue))
+    }
+
+    fn new_finish(
+        adapter: Arc<Adapter>,
+        device: Arc<Device>,
+        queue: Arc<Queue>,
+        surface: Option<Surface>,
+    ) -> Self {
+        // create a belt for fast data uploading
+        let belts = Arc::new(BeltPool::new());
+
+        // create a catcher to catch non fatal errors
+        // for example: shader compilation errors
+        let catcher = Catcher::new(&device);
+
+        // create a poll thread to allow wgpu wait operations to work
+        let _poll = PollThread::new(device.clone());
+
+        Self {
+            adapter,
+            device,
+            queue,
+
+            surface,
+            headless: None,
+            depth_stencil: None,
+            depth_stencil_enabled: false,
+            multisample: None,
+            sample_count: 1,
+            belts,
+            samplers: Default::default(),
+            catcher,
+
+            frame_id: AtomicUsize::new(0),
+
+            _poll,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use super::{Texture, DEFAULT_USAGE};
+use crate::{
+    prelude::{Rect, TexturePosition},
+    target::Target,
+};
+use glam::Vec2;
+use image::RgbaImage;
+use std::{num::NonZeroU32, ops::Deref};
+use wgpu::{
+    Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, TextureAspect, TextureFormat,
+    TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
+};
+
+//
+
+/// A stack of equally sized 2D textures
+/// that a shader can sample by layer index
+///
+/// Images smaller than the array are placed
+/// in the top left corner of their layer,
+/// use [`Texture2DArray::remap`] to correct
+/// their texture positions
+#[derive(Debug)]
+pub struct Texture2DArray<const USAGE: u32 = { DEFAULT_USAGE | TextureUsages::COPY_DST.bits() }> {
+    pub(crate) texture: wgpu::Texture,
+    format: TextureFormat,
+    view: TextureView,
+    dim: Rect,
+
+    // the size of the image in each layer
+    layer_dims: Vec<Rect>,
+}
+
+//
+
+impl<const USAGE: u32> Texture2DArray<USAGE> {
+    pub fn new(
+        target: &Target,
+        format: TextureFormat,
+        dim: Rect,
+        layers: u32,
+        label: Option<&str>,
+    ) -> Self {
+        let size = Extent3d {
+            width: dim.width,
+            height: dim.height,
+            depth_or_array_layers: layers,
+        };
+        let texture = Texture::<USAGE>::create_texture(target, format, size, 1, 1, None, label);
+        let view = texture.create_view(&TextureViewDescriptor {
+            label,
+            dimension: Some(TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+
+        Self {
+            texture,
+            format,
+            view,
+            dim,
+            layer_dims: vec![dim; layers as usize],
+        }
+    }
+
+    pub fn new_rgba(target: &Target, dim: Rect, layers: u32, label: Option<&str>) -> Self {
+        Self::new(target, TextureFormat::Rgba8Unorm, dim, layers, label)
+    }
+
+    /// one layer per image, the array is
+    /// as large as the largest image
+    pub fn new_rgba_with(
+        target: &Target,
+        images: &[RgbaImage],
+        label: Option<&str>,
+    ) -> Result<Self, &'static str> {
+        if images.is_empty() {
+            return Err("Texture array needs at least one layer");
+        }
+
+        let dim = images.iter().fold(Rect::new(1, 1), |dim, image| {
+            Rect::new(dim.width.max(image.width()), dim.height.max(image.height()))
+        });
+
+        let mut array = Self::new_rgba(target, dim, images.len() as _, label);
+        for (layer, image) in images.iter().enumerate() {
+            array.write_layer(target, layer as _, image)?;
+        }
+
+        Ok(array)
+    }
+
+    /// replace the image in `layer`
+    pub fn write_layer(
+        &mut self,
+        target: &Target,
+        layer: u32,
+        image: &RgbaImage,
+    ) -> Result<(), &'static str> {
+        if !matches!(
+            self.format,
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+        ) {
+            return Err("Image format doesn't match with the texture format");
+        }
+
+        let layer_dim = self
+            .layer_dims
+            .get_mut(layer as usize)
+            .ok_or("Layer out of the texture array's bounds")?;
+
+        if image.width() > self.dim.width || image.height() > self.dim.height {
+            return Err("Image is larger than the texture array");
+        }
+
+        // empty images have nothing to upload
+        let (Some(bytes_per_row), Some(rows_per_image)) = (
+            NonZeroU32::new(image.width() * 4),
+            NonZeroU32::new(image.height()),
+        ) else {
+            *layer_dim = Rect::from(image.dimensions());
+            return Ok(());
+        };
+
+        target.queue.write_texture(
+            ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: layer,
+                },
+                aspect: TextureAspect::All,
+            },
+            image.as_raw(),
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(rows_per_image),
+            },
+            Extent3d {
+                width: image.width(),
+                height: image.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+        *layer_dim = Rect::from(image.dimensions());
+
+        Ok(())
+    }
+
+    /// convert a texture position relative to
+    /// the image in `layer` to a texture position
+    /// relative to the whole layer
+    pub fn remap(&self, layer: u32, tex: TexturePosition) -> TexturePosition {
+        let Some(layer_dim) = self.layer_dims.get(layer as usize) else {
+            return tex;
+        };
+
+        let scale = Vec2::new(
+            layer_dim.width as f32 / self.dim.width as f32,
+            layer_dim.height as f32 / self.dim.height as f32,
+        );
+        TexturePosition {
+            top_left: tex.top_left * scale,
+            bottom_right: tex.bottom_right * scale,
+        }
+    }
+
+    pub fn get_dim(&self) -> Rect {
+        self.dim
+    }
+
+    pub fn get_layers(&self) -> u32 {
+        self.layer_dims.len() as _
+    }
+
+    pub fn get_format(&self) -> TextureFormat {
+        self.format
+    }
+}
+
+impl<const USAGE: u32> Deref for Texture2DArray<USAGE> {
+    type Target = TextureView;
+
+    fn deref(&self) -> &Self::Target {
+        &self.view
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Texture2DArray;
+    use crate::{
+        prelude::{Rect, TexturePosition},
+        Engine,
+    };
+    use glam::Vec2;
+    use image::RgbaImage;
+    use wgpu::RequestAdapterOptions;
+
+    #[test]
+    pub fn test_remap() {
+        crate::init_tokio().block_on(async {
+            let engine = Engine::new();
+            let adapter = engine
+                .get_instance()
+                .request_adapter(&RequestAdapterOptions::default())
+                .await;
+            if adapter.is_none() {
+                tracing::warn!("No GPU, skipping the texture array remap test");
+                return;
+            }
+
+            let target = engine.new_target_headless().await;
+            let images = [RgbaImage::new(16, 8), RgbaImage::new(4, 8)];
+            let array: Texture2DArray =
+                Texture2DArray::new_rgba_with(&target, &images, None).unwrap();
+            assert_eq!(array.get_dim(), Rect::new(16, 8));
+            assert_eq!(array.get_layers(), 2);
+
+            let tex = TexturePosition::default();
+            assert_eq!(array.remap(0, tex), tex);
+            let remapped = array.remap(1, tex);
+            assert_eq!(remapped.top_left, Vec2::ZERO);
+            assert_eq!(remapped.bottom_right, Vec2::new(0.25, 1.0));
+
+            // out of bounds layers are not remapped
+            assert_eq!(array.remap(2, tex), tex);
+        });
+    }
+}
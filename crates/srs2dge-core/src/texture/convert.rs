@@ -0,0 +1,222 @@
+//! Conversions between texture formats
+//! and [`DynamicImage`] variants
+//!
+//! | texture format                             | image variant                 |
+//! |--------------------------------------------|-------------------------------|
+//! | `Rgba8Unorm(Srgb)`, `Bgra8Unorm(Srgb)`     | [`DynamicImage::ImageRgba8`]  |
+//! | `R8Unorm`                                  | [`DynamicImage::ImageLuma8`]  |
+//! | `Rg8Unorm`                                 | [`DynamicImage::ImageLumaA8`] |
+//! | `Rgba16Float`, `Rgba32Float`               | [`DynamicImage::ImageRgba32F`] |
+//! | `R16Float`, `R32Float`                     | [`DynamicImage::ImageRgba32F`] |
+//!
+//! Single channel float textures are read like
+//! shaders sample them: `(r, 0, 0, 1)`, and
+//! only the red channel is written.
+
+use half::f16;
+use image::{DynamicImage, GrayAlphaImage, GrayImage, Rgba32FImage, RgbaImage};
+use std::borrow::Cow;
+use wgpu::TextureFormat;
+
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertError {
+    /// the image variant doesn't
+    /// match the texture format
+    InvalidFormat,
+
+    /// the texture format can't be
+    /// converted to an image
+    UnsupportedFormat(TextureFormat),
+
+    /// the texture data doesn't
+    /// match its dimensions
+    InvalidDim,
+}
+
+//
+
+impl From<ConvertError> for &'static str {
+    fn from(err: ConvertError) -> Self {
+        match err {
+            ConvertError::InvalidFormat => "Image format doesn't match with the texture format",
+            ConvertError::UnsupportedFormat(_) => "Texture format can't be converted to an image",
+            ConvertError::InvalidDim => "Texture data doesn't match its dimensions",
+        }
+    }
+}
+
+//
+
+/// can textures of this format be written and read
+pub fn is_supported(format: TextureFormat) -> bool {
+    matches!(
+        format,
+        TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb
+            | TextureFormat::R8Unorm
+            | TextureFormat::Rg8Unorm
+            | TextureFormat::Rgba16Float
+            | TextureFormat::Rgba32Float
+            | TextureFormat::R16Float
+            | TextureFormat::R32Float
+    )
+}
+
+/// bytes per pixel of a supported format
+pub fn pixel_size(format: TextureFormat) -> Result<u32, ConvertError> {
+    if !is_supported(format) {
+        return Err(ConvertError::UnsupportedFormat(format));
+    }
+    Ok(format.describe().block_size as u32)
+}
+
+/// the raw texture data of `image`
+pub fn to_bytes(
+    format: TextureFormat,
+    image: &DynamicImage,
+) -> Result<Cow<'_, [u8]>, ConvertError> {
+    let rgba8 = || image.as_rgba8().ok_or(ConvertError::InvalidFormat);
+    let rgba32f = || image.as_rgba32f().ok_or(ConvertError::InvalidFormat);
+
+    Ok(match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            Cow::Borrowed(rgba8()?.as_raw())
+        }
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            Cow::Owned(swizzle(rgba8()?.as_raw()))
+        }
+        TextureFormat::R8Unorm => Cow::Borrowed(
+            image
+                .as_luma8()
+                .ok_or(ConvertError::InvalidFormat)?
+                .as_raw(),
+        ),
+        TextureFormat::Rg8Unorm => Cow::Borrowed(
+            image
+                .as_luma_alpha8()
+                .ok_or(ConvertError::InvalidFormat)?
+                .as_raw(),
+        ),
+        TextureFormat::Rgba32Float => Cow::Borrowed(bytemuck::cast_slice(rgba32f()?.as_raw())),
+        TextureFormat::Rgba16Float => Cow::Owned(
+            rgba32f()?
+                .as_raw()
+                .iter()
+                .flat_map(|v| f16::from_f32(*v).to_le_bytes())
+                .collect(),
+        ),
+        TextureFormat::R32Float => Cow::Owned(
+            rgba32f()?
+                .pixels()
+                .flat_map(|p| p.0[0].to_le_bytes())
+                .collect(),
+        ),
+        TextureFormat::R16Float => Cow::Owned(
+            rgba32f()?
+                .pixels()
+                .flat_map(|p| f16::from_f32(p.0[0]).to_le_bytes())
+                .collect(),
+        ),
+        _ => return Err(ConvertError::UnsupportedFormat(format)),
+    })
+}
+
+/// an image from the raw texture data
+pub fn from_bytes(
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    bytes: Vec<u8>,
+) -> Result<DynamicImage, ConvertError> {
+    let f32s = |bytes: &[u8]| -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    };
+    let f16s = |bytes: &[u8]| -> Vec<f32> {
+        bytes
+            .chunks_exact(2)
+            .map(|b| f16::from_le_bytes([b[0], b[1]]).to_f32())
+            .collect()
+    };
+    let red = |values: Vec<f32>| -> Vec<f32> {
+        values
+            .into_iter()
+            .flat_map(|r| [r, 0.0, 0.0, 1.0])
+            .collect()
+    };
+
+    let image: Option<DynamicImage> = match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+            RgbaImage::from_raw(width, height, bytes).map(Into::into)
+        }
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            RgbaImage::from_raw(width, height, swizzle(&bytes)).map(Into::into)
+        }
+        TextureFormat::R8Unorm => GrayImage::from_raw(width, height, bytes).map(Into::into),
+        TextureFormat::Rg8Unorm => GrayAlphaImage::from_raw(width, height, bytes).map(Into::into),
+        TextureFormat::Rgba32Float => {
+            Rgba32FImage::from_raw(width, height, f32s(&bytes)).map(Into::into)
+        }
+        TextureFormat::Rgba16Float => {
+            Rgba32FImage::from_raw(width, height, f16s(&bytes)).map(Into::into)
+        }
+        TextureFormat::R32Float => {
+            Rgba32FImage::from_raw(width, height, red(f32s(&bytes))).map(Into::into)
+        }
+        TextureFormat::R16Float => {
+            Rgba32FImage::from_raw(width, height, red(f16s(&bytes))).map(Into::into)
+        }
+        _ => return Err(ConvertError::UnsupportedFormat(format)),
+    };
+    image.ok_or(ConvertError::InvalidDim)
+}
+
+// rgba <-> bgra
+fn swizzle(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .chunks_exact(4)
+        .flat_map(|p| [p[2], p[1], p[0], p[3]])
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_bytes, pixel_size, to_bytes, ConvertError};
+    use image::{DynamicImage, Rgba, Rgba32FImage};
+    use wgpu::TextureFormat;
+
+    #[test]
+    pub fn test_float_round_trip() {
+        let image: DynamicImage =
+            Rgba32FImage::from_fn(3, 2, |x, y| Rgba([x as f32, y as f32, 0.5, 1.0])).into();
+
+        for format in [TextureFormat::Rgba16Float, TextureFormat::Rgba32Float] {
+            let bytes = to_bytes(format, &image).unwrap();
+            assert_eq!(bytes.len() as u32, 3 * 2 * pixel_size(format).unwrap());
+            let read = from_bytes(format, 3, 2, bytes.into_owned()).unwrap();
+            assert_eq!(read, image, "{format:?}");
+        }
+
+        let bytes = to_bytes(TextureFormat::R32Float, &image).unwrap();
+        let read = from_bytes(TextureFormat::R32Float, 3, 2, bytes.into_owned()).unwrap();
+        assert_eq!(
+            read.as_rgba32f().unwrap().get_pixel(2, 1).0,
+            [2.0, 0.0, 0.0, 1.0]
+        );
+
+        assert_eq!(
+            to_bytes(TextureFormat::Rgba8Unorm, &image).unwrap_err(),
+            ConvertError::InvalidFormat
+        );
+        assert_eq!(
+            pixel_size(TextureFormat::Depth32Float).unwrap_err(),
+            ConvertError::UnsupportedFormat(TextureFormat::Depth32Float)
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! CPU mipmap generation
+//!
+//! Each mip level halves the previous one with
+//! an alpha weighted box filter, so fully
+//! transparent pixels don't darken the edges.
+
+use crate::prelude::{PositionedRect, Rect};
+use image::{Rgba, RgbaImage};
+
+//
+
+/// number of mip levels in a full mip chain
+pub fn full_mip_level_count(dim: Rect) -> u32 {
+    u32::BITS - dim.width.max(dim.height).max(1).leading_zeros()
+}
+
+/// number of mip levels that don't make
+/// sprites of a texture atlas bleed into
+/// each other
+///
+/// the padding between sprites is filled with
+/// their edge pixels (see [`extrude`]), so each
+/// sprite has at least `padding / 2` pixels
+/// of its own color around it and a single
+/// texel of a mip level may cover at most
+/// that many padding pixels plus one
+pub fn atlas_mip_level_count(dim: Rect, padding: u8) -> u32 {
+    let covered = padding as u32 / 2 + 1;
+    let levels = u32::BITS - covered.leading_zeros();
+    levels.min(full_mip_level_count(dim))
+}
+
+/// the size of a single mip level
+pub fn mip_level_dim(dim: Rect, level: u32) -> Rect {
+    Rect::new((dim.width >> level).max(1), (dim.height >> level).max(1))
+}
+
+/// generate the mip levels `1..levels` of `image`
+///
+/// `levels` is clamped to the full mip chain
+pub fn generate_mipmaps(image: &RgbaImage, levels: u32) -> Vec<RgbaImage> {
+    let dim = Rect::from(image.dimensions());
+    let levels = levels.min(full_mip_level_count(dim));
+
+    let mut mips: Vec<RgbaImage> = Vec::with_capacity(levels.saturating_sub(1) as usize);
+    for level in 1..levels {
+        let prev = mips.last().unwrap_or(image);
+        let next = downsample(prev, (0, 0), mip_level_dim(dim, level), (true, true));
+        mips.push(next);
+    }
+    mips
+}
+
+/// regenerate the mip levels `1..levels`
+/// of an area (`spot`) of a texture of
+/// size `dim` from the new contents of that area
+///
+/// returns the affected area in each mip level
+/// and its contents
+///
+/// only texels that are fully covered by `spot`
+/// are regenerated, the texels at the edges that
+/// also cover pixels outside of `spot` keep their
+/// old contents, so the neighbours of `spot` are
+/// never overwritten and all texels are updated
+/// if `spot` is aligned to `2^(levels-1)` pixels
+pub fn spot_mipmaps(
+    image: &RgbaImage,
+    spot: PositionedRect,
+    dim: Rect,
+    levels: u32,
+) -> Vec<(PositionedRect, RgbaImage)> {
+    let levels = levels.min(full_mip_level_count(dim));
+
+    let mut mips: Vec<(PositionedRect, RgbaImage)> =
+        Vec::with_capacity(levels.saturating_sub(1) as usize);
+    for level in 1..levels {
+        let (prev_spot, prev) = mips
+            .last()
+            .map(|(spot, image)| (*spot, image))
+            .unwrap_or((spot, image));
+
+        // the last texel of a level also covers
+        // the last pixel of an odd sized previous level
+        let prev_dim = mip_level_dim(dim, level - 1);
+        let level_dim = mip_level_dim(dim, level);
+        let covered = |start: u32, len: u32, prev_len: u32, level_len: u32| {
+            let end = start + len;
+            let first = (start + 1) / 2;
+            let last = if end == prev_len { level_len } else { end / 2 };
+            (first, last.saturating_sub(first), end == prev_len)
+        };
+        let (x, width, rest_x) = covered(
+            prev_spot.x,
+            prev_spot.width,
+            prev_dim.width,
+            level_dim.width,
+        );
+        let (y, height, rest_y) = covered(
+            prev_spot.y,
+            prev_spot.height,
+            prev_dim.height,
+            level_dim.height,
+        );
+        if width == 0 || height == 0 {
+            break;
+        }
+
+        let skip = (prev_spot.x & 1, prev_spot.y & 1);
+        let next = downsample(prev, skip, Rect::new(width, height), (rest_x, rest_y));
+        mips.push((PositionedRect::new(x, y, width, height), next));
+    }
+    mips
+}
+
+/// fill the `padding` around `rect` with
+/// the edge pixels of `rect`
+///
+/// the padding is split between the sides:
+/// `padding - padding / 2` pixels to the left
+/// and top and `padding / 2` to the right and
+/// bottom, so that two neighboring rects packed
+/// with this padding don't overwrite each other
+///
+/// returns the filled area including `rect`
+pub fn extrude(image: &mut RgbaImage, rect: PositionedRect, padding: u8) -> PositionedRect {
+    if rect.width == 0 || rect.height == 0 {
+        return rect;
+    }
+
+    let (before, after) = ((padding - padding / 2) as u32, (padding / 2) as u32);
+    let x0 = rect.x.saturating_sub(before);
+    let y0 = rect.y.saturating_sub(before);
+    let x1 = (rect.x + rect.width + after).min(image.width());
+    let y1 = (rect.y + rect.height + after).min(image.height());
+
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let sx = x.clamp(rect.x, rect.x + rect.width - 1);
+            let sy = y.clamp(rect.y, rect.y + rect.height - 1);
+            if (sx, sy) != (x, y) {
+                let pixel = *image.get_pixel(sx, sy);
+                image.put_pixel(x, y, pixel);
+            }
+        }
+    }
+
+    PositionedRect::new(x0, y0, x1 - x0, y1 - y0)
+}
+
+// each texel is the 2x2 pixels after the first
+// `skip` pixels of `image`, the last row and
+// column cover the rest of `image` if `rest`
+fn downsample(image: &RgbaImage, skip: (u32, u32), dim: Rect, rest: (bool, bool)) -> RgbaImage {
+    let (w, h) = image.dimensions();
+    let range = |i: u32, skip: u32, last: u32, len: u32, rest: bool| {
+        let start = 2 * i + skip;
+        let end = if i == last && rest {
+            len
+        } else {
+            (start + 2).min(len)
+        };
+        start..end
+    };
+
+    RgbaImage::from_fn(dim.width, dim.height, |i, j| {
+        let mut color = [0u32; 3];
+        let mut plain = [0u32; 3];
+        let mut alpha = 0u32;
+        let mut count = 0u32;
+        for y in range(j, skip.1, dim.height - 1, h, rest.1) {
+            for x in range(i, skip.0, dim.width - 1, w, rest.0) {
+                let Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+                for (c, v) in [r, g, b].into_iter().enumerate() {
+                    color[c] += v as u32 * a as u32;
+                    plain[c] += v as u32;
+                }
+                alpha += a as u32;
+                count += 1;
+            }
+        }
+
+        let count = count.max(1);
+        let [r, g, b] = if alpha == 0 {
+            plain.map(|c| ((c + count / 2) / count) as u8)
+        } else {
+            color.map(|c| ((c + alpha / 2) / alpha) as u8)
+        };
+        Rgba([r, g, b, ((alpha + count / 2) / count) as u8])
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{atlas_mip_level_count, extrude, generate_mipmaps, spot_mipmaps};
+    use crate::prelude::{PositionedRect, Rect};
+    use image::{imageops, Rgba, RgbaImage};
+    use rand::Rng;
+
+    #[test]
+    pub fn test_mip_chain() {
+        let mips = generate_mipmaps(&RgbaImage::new(13, 4), u32::MAX);
+        let dims: Vec<_> = mips.iter().map(|mip| mip.dimensions()).collect();
+        assert_eq!(dims, [(6, 2), (3, 1), (1, 1)]);
+
+        // transparent pixels don't darken the color
+        let mut image = RgbaImage::new(2, 2);
+        image.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+        let mips = generate_mipmaps(&image, 2);
+        assert_eq!(*mips[0].get_pixel(0, 0), Rgba([255, 255, 255, 64]));
+
+        assert_eq!(atlas_mip_level_count(Rect::new(256, 256), 0), 1);
+        assert_eq!(atlas_mip_level_count(Rect::new(256, 256), 2), 2);
+        assert_eq!(atlas_mip_level_count(Rect::new(256, 256), 6), 3);
+    }
+
+    #[test]
+    pub fn test_spot_mipmaps() {
+        let mut rng = rand::thread_rng();
+        let image = RgbaImage::from_fn(32, 32, |_, _| Rgba(rng.gen()));
+        let full = generate_mipmaps(&image, 3);
+
+        let spot_mips = |spot: PositionedRect| {
+            let sub =
+                imageops::crop_imm(&image, spot.x, spot.y, spot.width, spot.height).to_image();
+            let mips = spot_mipmaps(&sub, spot, Rect::new(32, 32), 3);
+            for ((area, mip), full) in mips.iter().zip(full.iter()) {
+                let expected =
+                    imageops::crop_imm(full, area.x, area.y, area.width, area.height).to_image();
+                assert_eq!(*mip, expected);
+            }
+            mips.into_iter().map(|(area, _)| area).collect::<Vec<_>>()
+        };
+
+        // aligned
+        assert_eq!(
+            spot_mips(PositionedRect::new(8, 4, 16, 12)),
+            [
+                PositionedRect::new(4, 2, 8, 6),
+                PositionedRect::new(2, 1, 4, 3)
+            ]
+        );
+
+        // unaligned, the texels that also cover
+        // the neighbours are left as is
+        assert_eq!(
+            spot_mips(PositionedRect::new(5, 3, 10, 9)),
+            [
+                PositionedRect::new(3, 2, 4, 4),
+                PositionedRect::new(2, 1, 1, 2)
+            ]
+        );
+
+        // the edges of the texture
+        assert_eq!(
+            spot_mips(PositionedRect::new(27, 0, 5, 32)),
+            [
+                PositionedRect::new(14, 0, 2, 16),
+                PositionedRect::new(7, 0, 1, 8)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_extrude() {
+        let mut image = RgbaImage::new(8, 8);
+        image.put_pixel(3, 3, Rgba([1, 2, 3, 4]));
+        let area = extrude(&mut image, PositionedRect::new(3, 3, 1, 1), 4);
+        assert_eq!(area, PositionedRect::new(1, 1, 5, 5));
+        assert!(
+            image
+                .enumerate_pixels()
+                .all(|(x, y, p)| (p.0 == [1, 2, 3, 4])
+                    == ((1..6).contains(&x) && (1..6).contains(&y)))
+        );
+    }
+}
@@ -1,10 +1,14 @@
+use self::{
+    convert::ConvertError,
+    mipmap::{full_mip_level_count, generate_mipmaps, mip_level_dim, spot_mipmaps},
+};
 use crate::{
     label,
     prelude::{PositionedRect, Rect},
     target::Target,
 };
 use image::{DynamicImage, GrayImage, RgbaImage};
-use std::{mem, num::NonZeroU32, ops::Deref};
+use std::{num::NonZeroU32, ops::Deref};
 use tokio::sync::oneshot::channel;
 use wgpu::{
     util::DeviceExt, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d,
@@ -14,13 +18,34 @@ use wgpu::{
 
 //
 
+pub mod array;
+pub mod convert;
+pub mod mipmap;
 pub mod pos;
 pub mod prelude;
+pub mod sampler;
 pub mod serde;
 
 //
 
-const DEFAULT_USAGE: u32 = TextureUsages::TEXTURE_BINDING.bits();
+pub(crate) const DEFAULT_USAGE: u32 = TextureUsages::TEXTURE_BINDING.bits();
+
+/// usage of textures that compute (or fragment)
+/// shaders write to and that can also be sampled
+/// and read back
+pub const STORAGE_USAGE: u32 = TextureUsages::STORAGE_BINDING.bits()
+    | TextureUsages::TEXTURE_BINDING.bits()
+    | TextureUsages::COPY_SRC.bits()
+    | TextureUsages::COPY_DST.bits();
+
+/// usage of textures that shaders
+/// only read as storage textures
+pub const READ_ONLY_STORAGE_USAGE: u32 =
+    TextureUsages::STORAGE_BINDING.bits() | TextureUsages::COPY_DST.bits();
+
+/// the format of [`DepthStencilTexture`]s
+/// and shaders with depth or stencil
+pub const DEPTH_STENCIL_FORMAT: TextureFormat = TextureFormat::Depth24PlusStencil8;
 
 //
 
@@ -29,14 +54,20 @@ pub const fn has_render_attachment(usage: u32) -> bool {
     // usage & TextureUsages::RENDER_ATTACHMENT.bits() != 0
 }
 
+pub const fn has_storage_binding(usage: u32) -> bool {
+    TextureUsages::from_bits_truncate(usage).contains(TextureUsages::STORAGE_BINDING)
+}
+
 //
 
 #[derive(Debug)]
 pub struct Texture<const USAGE: u32 = DEFAULT_USAGE> {
-    texture: wgpu::Texture,
+    pub(crate) texture: wgpu::Texture,
     format: TextureFormat,
     view: TextureView,
     dim: Rect,
+    sample_count: u32,
+    mip_level_count: u32,
 }
 
 //
@@ -49,15 +80,24 @@ pub type RenderTargetTexture = Texture<
     },
 >;
 
+pub type DepthStencilTexture =
+    Texture<{ TextureUsages::TEXTURE_BINDING.bits() | TextureUsages::RENDER_ATTACHMENT.bits() }>;
+
+pub type MultisampleTexture = Texture<{ TextureUsages::RENDER_ATTACHMENT.bits() }>;
+
+pub type StorageTexture = Texture<STORAGE_USAGE>;
+
+pub type ReadOnlyStorageTexture = Texture<READ_ONLY_STORAGE_USAGE>;
+
 //
 
 impl<const USAGE: u32> Texture<USAGE> {
     pub fn new(target: &Target, format: TextureFormat, dim: Rect, label: Option<&str>) -> Self {
-        Self::new_inner(target, format, dim, None, label)
+        Self::new_inner(target, format, dim, 1, 1, None, label)
     }
 
     pub fn new_rgba(target: &Target, dim: Rect, label: Option<&str>) -> Self {
-        Self::new_inner(target, TextureFormat::Rgba8Unorm, dim, None, label)
+        Self::new_inner(target, TextureFormat::Rgba8Unorm, dim, 1, 1, None, label)
     }
 
     pub fn new_rgba_with(target: &Target, data: &RgbaImage, label: Option<&str>) -> Self {
@@ -65,13 +105,67 @@ impl<const USAGE: u32> Texture<USAGE> {
             target,
             TextureFormat::Rgba8Unorm,
             Rect::from(data.dimensions()),
+            1,
+            1,
             Some(data.as_raw()),
             label,
         )
     }
 
+    /// an rgba texture with `levels` mip levels
+    /// generated on the CPU from `data`
+    ///
+    /// `None` allocates the full mip chain
+    pub fn new_rgba_with_mipmaps(
+        target: &Target,
+        data: &RgbaImage,
+        levels: Option<u32>,
+        label: Option<&str>,
+    ) -> Self {
+        let dim = Rect::from(data.dimensions());
+        let levels = levels
+            .unwrap_or(u32::MAX)
+            .clamp(1, full_mip_level_count(dim));
+
+        let mut bytes = data.as_raw().clone();
+        for mip in generate_mipmaps(data, levels) {
+            bytes.extend_from_slice(mip.as_raw());
+        }
+
+        Self::new_inner(
+            target,
+            TextureFormat::Rgba8Unorm,
+            dim,
+            1,
+            levels,
+            Some(&bytes),
+            label,
+        )
+    }
+
+    /// an empty rgba texture with `levels` mip levels
+    ///
+    /// [`Self::write`] fills in the mip levels
+    pub fn new_rgba_mipmapped(
+        target: &Target,
+        dim: Rect,
+        levels: u32,
+        label: Option<&str>,
+    ) -> Self {
+        let levels = levels.clamp(1, full_mip_level_count(dim));
+        Self::new_inner(
+            target,
+            TextureFormat::Rgba8Unorm,
+            dim,
+            1,
+            levels,
+            None,
+            label,
+        )
+    }
+
     pub fn new_grey(target: &Target, dim: Rect, label: Option<&str>) -> Self {
-        Self::new_inner(target, TextureFormat::R8Unorm, dim, None, label)
+        Self::new_inner(target, TextureFormat::R8Unorm, dim, 1, 1, None, label)
     }
 
     pub fn new_grey_with(target: &Target, data: &GrayImage, label: Option<&str>) -> Self {
@@ -79,6 +173,8 @@ impl<const USAGE: u32> Texture<USAGE> {
             target,
             TextureFormat::R8Unorm,
             Rect::from(data.dimensions()),
+            1,
+            1,
             Some(data.as_raw()),
             label,
         )
@@ -90,17 +186,47 @@ impl<const USAGE: u32> Texture<USAGE> {
         format: TextureFormat,
         label: Option<&str>,
     ) -> Self {
-        Self::new_inner(target, format, dim, None, label)
+        Self::new_inner(target, format, dim, 1, 1, None, label)
+    }
+
+    /// a texture with multiple samples per pixel
+    ///
+    /// used as MSAA render attachments
+    pub fn new_multisampled(
+        target: &Target,
+        dim: Rect,
+        format: TextureFormat,
+        sample_count: u32,
+        label: Option<&str>,
+    ) -> Self {
+        Self::new_inner(target, format, dim, sample_count, 1, None, label)
+    }
+
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
     }
 
     pub fn get_dim(&self) -> Rect {
         self.dim
     }
 
+    pub fn get_mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
     pub fn get_format(&self) -> TextureFormat {
         self.format
     }
 
+    /// write `image` to `spot` in the first mip level
+    ///
+    /// the image variant has to match the texture
+    /// format, see [`convert`]
+    ///
+    /// the affected areas of the other mip
+    /// levels are regenerated on the CPU,
+    /// see [`mipmap::spot_mipmaps`] for
+    /// the texels at unaligned edges
     pub fn write(
         &self,
         target: &Target,
@@ -115,40 +241,38 @@ impl<const USAGE: u32> Texture<USAGE> {
             return Err("Spot out of the texture's bounds");
         }
 
-        const INVALID_FORMAT: &str = "Image format doesn't match with the texture format";
-        let image = match self.format {
-            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
-                image.as_rgba8().ok_or(INVALID_FORMAT)?.as_raw()
-            }
-            TextureFormat::R8Unorm => image.as_luma8().ok_or(INVALID_FORMAT)?.as_raw(),
-            _ => unimplemented!(),
+        // checked before anything is written
+        let mip_image = if self.mip_level_count > 1 {
+            Some(
+                image
+                    .as_rgba8()
+                    .ok_or("Only rgba textures can have mipmaps")?,
+            )
+        } else {
+            None
         };
 
-        target.queue.write_texture(
-            ImageCopyTexture {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: Origin3d {
-                    x: spot.x,
-                    y: spot.y,
-                    z: 0,
-                },
-                aspect: TextureAspect::All,
-            },
-            image,
-            ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(NonZeroU32::new(spot.width).unwrap()),
-                rows_per_image: Some(NonZeroU32::new(spot.height).unwrap()),
-            },
-            spot.rect().into(),
-        );
+        let pixel_size = convert::pixel_size(self.format)?;
+        let bytes = convert::to_bytes(self.format, &image)?;
+        self.write_level(target, 0, spot, &bytes, pixel_size);
+
+        if let Some(image) = mip_image {
+            for (level, (spot, mip)) in
+                (1..).zip(spot_mipmaps(image, spot, self.dim, self.mip_level_count))
+            {
+                self.write_level(target, level, spot, mip.as_raw(), pixel_size);
+            }
+        }
 
         Ok(())
     }
 
-    pub async fn read(&self, target: &Target) -> DynamicImage {
-        let dim = BufferDimensions::new(self.dim.width as _, self.dim.height as _, self.format);
+    /// read the first mip level back from the GPU
+    ///
+    /// the image variant depends on the
+    /// texture format, see [`convert`]
+    pub async fn read(&self, target: &Target) -> Result<DynamicImage, ConvertError> {
+        let dim = BufferDimensions::new(self.dim.width as _, self.dim.height as _, self.format)?;
 
         // cache these buffers
         let read_buffer = target.device.create_buffer(&BufferDescriptor {
@@ -193,51 +317,123 @@ impl<const USAGE: u32> Texture<USAGE> {
             .copied()
             .collect();
 
-        match self.format {
-            TextureFormat::Rgba8Unorm
-            | TextureFormat::Rgba8UnormSrgb
-            | TextureFormat::Bgra8Unorm
-            | TextureFormat::Bgra8UnormSrgb => {
-                RgbaImage::from_raw(dim.width as _, dim.height as _, bytes)
-                    .unwrap()
-                    .into()
-            }
-            TextureFormat::R8Unorm => GrayImage::from_raw(dim.width as _, dim.height as _, bytes)
-                .unwrap()
-                .into(),
-            _ => unimplemented!(),
+        convert::from_bytes(self.format, dim.width as _, dim.height as _, bytes)
+    }
+
+    /// copy the first `levels` mip levels of
+    /// `self` to the top left corner of `to`
+    ///
+    /// used to grow textures
+    pub(crate) fn copy_to<const TO: u32>(&self, target: &Target, to: &Texture<TO>, levels: u32) {
+        let mut encoder = target
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor { label: label!() });
+        for level in 0..levels {
+            let dim = mip_level_dim(self.dim, level);
+            let copy = |texture| ImageCopyTexture {
+                texture,
+                mip_level: level,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            };
+            encoder.copy_texture_to_texture(
+                copy(&self.texture),
+                copy(&to.texture),
+                Extent3d {
+                    width: dim.width,
+                    height: dim.height,
+                    depth_or_array_layers: 1,
+                },
+            );
         }
+        target.queue.submit([encoder.finish()]);
+    }
+
+    fn write_level(
+        &self,
+        target: &Target,
+        level: u32,
+        spot: PositionedRect,
+        bytes: &[u8],
+        pixel_size: u32,
+    ) {
+        target.queue.write_texture(
+            ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: level,
+                origin: Origin3d {
+                    x: spot.x,
+                    y: spot.y,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            bytes,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(NonZeroU32::new(spot.width * pixel_size).unwrap()),
+                rows_per_image: Some(NonZeroU32::new(spot.height).unwrap()),
+            },
+            spot.rect().into(),
+        );
     }
 
     fn new_inner(
         target: &Target,
         format: TextureFormat,
         dim: Rect,
+        sample_count: u32,
+        mip_level_count: u32,
         data: Option<&[u8]>,
         label: Option<&str>,
     ) -> Self {
+        let texture = Self::create_texture(
+            target,
+            format,
+            dim.into(),
+            sample_count,
+            mip_level_count,
+            data,
+            label,
+        );
+        let view = texture.create_view(&Default::default());
+
+        Self {
+            texture,
+            format,
+            view,
+            dim,
+            sample_count,
+            mip_level_count,
+        }
+    }
+
+    /// a 2D texture with `size.depth_or_array_layers`
+    /// layers, `data` has every mip level
+    /// of every layer if given
+    pub(crate) fn create_texture(
+        target: &Target,
+        format: TextureFormat,
+        size: Extent3d,
+        sample_count: u32,
+        mip_level_count: u32,
+        data: Option<&[u8]>,
+        label: Option<&str>,
+    ) -> wgpu::Texture {
         let desc = TextureDescriptor {
             label,
-            size: dim.into(),
-            mip_level_count: 1,
-            sample_count: 1,
+            size,
+            mip_level_count,
+            sample_count,
             dimension: TextureDimension::D2,
             format,
             usage: TextureUsages::from_bits_truncate(USAGE),
         };
-        let texture = match data {
+        match data {
             None => target.device.create_texture(&desc),
             Some(data) => target
                 .device
                 .create_texture_with_data(&target.queue, &desc, data),
-        };
-        let view = texture.create_view(&Default::default());
-
-        Self {
-            texture,
-            format,
-            view,
-            dim,
         }
     }
 }
@@ -260,25 +456,18 @@ struct BufferDimensions {
 }
 
 impl BufferDimensions {
-    fn new(width: usize, height: usize, format: TextureFormat) -> Self {
-        let pixel_size = match format {
-            TextureFormat::Rgba8Unorm
-            | TextureFormat::Rgba8UnormSrgb
-            | TextureFormat::Bgra8Unorm
-            | TextureFormat::Bgra8UnormSrgb => mem::size_of::<f32>(),
-            TextureFormat::R8Unorm => mem::size_of::<u8>(),
-            _ => unimplemented!(),
-        };
+    fn new(width: usize, height: usize, format: TextureFormat) -> Result<Self, ConvertError> {
+        let pixel_size = convert::pixel_size(format)? as usize;
 
         let unpadded_bytes_per_row = width * pixel_size;
         let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
         let padded_bytes_per_row_padding = (align - unpadded_bytes_per_row % align) % align;
         let padded_bytes_per_row = unpadded_bytes_per_row + padded_bytes_per_row_padding;
-        Self {
+        Ok(Self {
             width,
             height,
             unpadded_bytes_per_row,
             padded_bytes_per_row,
-        }
+        })
     }
 }
@@ -1,15 +1,30 @@
 use crate::prelude::{PositionedRect, Rect};
+use bytemuck::{Pod, Zeroable};
 use glam::{Vec2, Vec4};
 use serde::{Deserialize, Serialize};
 
 //
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Zeroable, Pod)]
+#[repr(C)]
 pub struct TexturePosition {
     pub top_left: Vec2,
     pub bottom_right: Vec2,
 }
 
+/// A [`TexturePosition`] in one page
+/// of a multi page texture atlas
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PagedTexturePosition {
+    pub page: u32,
+    pub pos: TexturePosition,
+
+    /// the image is stored rotated 90°
+    /// clockwise, see [`Self::uv`]
+    #[serde(default)]
+    pub rotated: bool,
+}
+
 //
 
 impl TexturePosition {
@@ -24,6 +39,16 @@ impl TexturePosition {
         }
     }
 
+    /// the pixel area in a texture of size `area`
+    pub fn to_rect(self, area: Rect) -> PositionedRect {
+        let (w, h) = (area.width as f32, area.height as f32);
+        let x = (self.top_left.x * w).round() as u32;
+        let y = (self.top_left.y * h).round() as u32;
+        let right = (self.bottom_right.x * w).round() as u32;
+        let bottom = (self.bottom_right.y * h).round() as u32;
+        PositionedRect::new(x, y, right.saturating_sub(x), bottom.saturating_sub(y))
+    }
+
     pub const fn to_vec4(self) -> Vec4 {
         Vec4::from_array([
             self.top_left.x,
@@ -42,3 +67,36 @@ impl Default for TexturePosition {
         }
     }
 }
+
+impl PagedTexturePosition {
+    pub const fn new(page: u32, pos: TexturePosition) -> Self {
+        Self {
+            page,
+            pos,
+            rotated: false,
+        }
+    }
+
+    pub fn with_rotated(mut self, rotated: bool) -> Self {
+        self.rotated = rotated;
+        self
+    }
+
+    /// map a texture coordinate (`0.0..=1.0`)
+    /// of the original image to the page
+    pub fn uv(self, uv: Vec2) -> Vec2 {
+        let uv = if self.rotated {
+            Vec2::new(1.0 - uv.y, uv.x)
+        } else {
+            uv
+        };
+        self.pos.top_left + (self.pos.bottom_right - self.pos.top_left) * uv
+    }
+}
+
+/// a position in the first page
+impl From<TexturePosition> for PagedTexturePosition {
+    fn from(pos: TexturePosition) -> Self {
+        Self::new(0, pos)
+    }
+}
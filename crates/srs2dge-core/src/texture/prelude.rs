@@ -1 +1 @@
-pub use super::{pos::*, *};
+pub use super::{array::*, pos::*, sampler::*, *};
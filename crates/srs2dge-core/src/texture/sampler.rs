@@ -0,0 +1,289 @@
+use crate::{label, target::Target};
+use std::{
+    hash::{Hash, Hasher},
+    num::NonZeroU8,
+    ops::Deref,
+    sync::Arc,
+};
+use wgpu::{AddressMode, FilterMode, SamplerBorderColor, SamplerDescriptor};
+
+//
+
+/// A builder for [`Sampler`]s
+///
+/// Defaults to nearest filtering,
+/// [`AddressMode::ClampToEdge`] and
+/// no anisotropic filtering.
+#[derive(Debug, Clone, Copy)]
+pub struct SamplerBuilder {
+    mag_filter: FilterMode,
+    min_filter: FilterMode,
+    mip_filter: FilterMode,
+    address_mode: [AddressMode; 3],
+    border_color: Option<SamplerBorderColor>,
+    anisotropy: u8,
+    lod_min_clamp: f32,
+    lod_max_clamp: f32,
+}
+
+/// [`SamplerBuilder::build`] errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerError {
+    /// only 1, 2, 4, 8 and 16
+    /// samples are allowed
+    InvalidAnisotropy(u8),
+
+    /// anisotropic filtering
+    /// requires linear filters
+    NonLinearAnisotropy,
+}
+
+/// A texture sampler
+///
+/// Samplers are cached per configuration
+/// in the [`Target`], so building the same
+/// configuration again is cheap.
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    inner: Arc<wgpu::Sampler>,
+    desc: SamplerBuilder,
+}
+
+//
+
+impl Default for SamplerBuilder {
+    fn default() -> Self {
+        Self {
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            mip_filter: FilterMode::Nearest,
+            address_mode: [AddressMode::ClampToEdge; 3],
+            border_color: None,
+            anisotropy: 1,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: f32::MAX,
+        }
+    }
+}
+
+impl SamplerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// magnification, minification
+    /// and mipmap filter
+    pub fn with_filter(self, filter: FilterMode) -> Self {
+        self.with_mag_filter(filter)
+            .with_min_filter(filter)
+            .with_mip_filter(filter)
+    }
+
+    pub fn with_mag_filter(mut self, filter: FilterMode) -> Self {
+        self.mag_filter = filter;
+        self
+    }
+
+    pub fn with_min_filter(mut self, filter: FilterMode) -> Self {
+        self.min_filter = filter;
+        self
+    }
+
+    /// filter between mip levels
+    pub fn with_mip_filter(mut self, filter: FilterMode) -> Self {
+        self.mip_filter = filter;
+        self
+    }
+
+    /// address mode of all axes
+    pub fn with_address_mode(mut self, mode: AddressMode) -> Self {
+        self.address_mode = [mode; 3];
+        self
+    }
+
+    /// address mode of the x axis
+    pub fn with_address_mode_u(mut self, mode: AddressMode) -> Self {
+        self.address_mode[0] = mode;
+        self
+    }
+
+    /// address mode of the y axis
+    pub fn with_address_mode_v(mut self, mode: AddressMode) -> Self {
+        self.address_mode[1] = mode;
+        self
+    }
+
+    /// address mode of the z axis
+    pub fn with_address_mode_w(mut self, mode: AddressMode) -> Self {
+        self.address_mode[2] = mode;
+        self
+    }
+
+    /// color of [`AddressMode::ClampToBorder`]
+    ///
+    /// requires [`wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER`]
+    pub fn with_border_color(mut self, color: Option<SamplerBorderColor>) -> Self {
+        self.border_color = color;
+        self
+    }
+
+    /// max anisotropic filtering samples,
+    /// valid values are 1, 2, 4, 8 and 16
+    ///
+    /// anything above 1 requires
+    /// all filters to be linear,
+    /// checked by [`Self::build`]
+    pub fn with_anisotropy(mut self, anisotropy: u8) -> Self {
+        self.anisotropy = anisotropy;
+        self
+    }
+
+    /// the range of mip levels to sample from
+    pub fn with_lod_clamp(mut self, min: f32, max: f32) -> Self {
+        self.lod_min_clamp = min;
+        self.lod_max_clamp = max;
+        self
+    }
+
+    /// does this sampler blend between texels
+    pub fn is_filtering(&self) -> bool {
+        [self.mag_filter, self.min_filter, self.mip_filter].contains(&FilterMode::Linear)
+    }
+
+    /// check the anisotropy and filters
+    pub fn validate(&self) -> Result<(), SamplerError> {
+        if !matches!(self.anisotropy, 1 | 2 | 4 | 8 | 16) {
+            return Err(SamplerError::InvalidAnisotropy(self.anisotropy));
+        }
+
+        if self.anisotropy > 1
+            && [self.mag_filter, self.min_filter, self.mip_filter].contains(&FilterMode::Nearest)
+        {
+            return Err(SamplerError::NonLinearAnisotropy);
+        }
+
+        Ok(())
+    }
+
+    /// get the sampler from the cache of
+    /// `target` or create a new one
+    pub fn build(self, target: &Target) -> Result<Sampler, SamplerError> {
+        self.validate()?;
+
+        let mut samplers = target.samplers.lock().unwrap();
+        let inner = samplers
+            .entry(self)
+            .or_insert_with(|| {
+                tracing::debug!("Creating a new sampler {self:?}");
+                Arc::new(target.device.create_sampler(&SamplerDescriptor {
+                    label: label!(),
+                    address_mode_u: self.address_mode[0],
+                    address_mode_v: self.address_mode[1],
+                    address_mode_w: self.address_mode[2],
+                    mag_filter: self.mag_filter,
+                    min_filter: self.min_filter,
+                    mipmap_filter: self.mip_filter,
+                    lod_min_clamp: self.lod_min_clamp,
+                    lod_max_clamp: self.lod_max_clamp,
+                    compare: None,
+                    anisotropy_clamp: NonZeroU8::new(self.anisotropy).filter(|a| a.get() > 1),
+                    border_color: self.border_color,
+                }))
+            })
+            .clone();
+
+        Ok(Sampler { inner, desc: self })
+    }
+
+    // floats compared by their bits
+    // to make this usable as a key
+    fn key(&self) -> impl Hash + Eq {
+        (
+            self.mag_filter,
+            self.min_filter,
+            self.mip_filter,
+            self.address_mode,
+            self.border_color,
+            self.anisotropy,
+            self.lod_min_clamp.to_bits(),
+            self.lod_max_clamp.to_bits(),
+        )
+    }
+}
+
+impl PartialEq for SamplerBuilder {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SamplerBuilder {}
+
+impl Hash for SamplerBuilder {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl Sampler {
+    pub fn builder() -> SamplerBuilder {
+        SamplerBuilder::new()
+    }
+
+    /// nearest filtering, for pixel art
+    pub fn nearest(target: &Target) -> Self {
+        SamplerBuilder::new()
+            .build(target)
+            .expect("Nearest sampler is valid")
+    }
+
+    /// linear filtering, for smooth textures
+    pub fn linear(target: &Target) -> Self {
+        SamplerBuilder::new()
+            .with_filter(FilterMode::Linear)
+            .build(target)
+            .expect("Linear sampler is valid")
+    }
+
+    /// the configuration this sampler was built with
+    pub fn get_desc(&self) -> SamplerBuilder {
+        self.desc
+    }
+}
+
+impl Deref for Sampler {
+    type Target = wgpu::Sampler;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SamplerBuilder, SamplerError};
+    use wgpu::FilterMode;
+
+    #[test]
+    pub fn test_validate() {
+        let linear = SamplerBuilder::new().with_filter(FilterMode::Linear);
+        for anisotropy in [1, 2, 4, 8, 16] {
+            assert_eq!(linear.with_anisotropy(anisotropy).validate(), Ok(()));
+        }
+        for anisotropy in [0, 3, 5, 32] {
+            assert_eq!(
+                linear.with_anisotropy(anisotropy).validate(),
+                Err(SamplerError::InvalidAnisotropy(anisotropy))
+            );
+        }
+
+        assert_eq!(
+            linear
+                .with_mip_filter(FilterMode::Nearest)
+                .with_anisotropy(4)
+                .validate(),
+            Err(SamplerError::NonLinearAnisotropy)
+        );
+        assert_eq!(SamplerBuilder::new().validate(), Ok(()));
+    }
+}
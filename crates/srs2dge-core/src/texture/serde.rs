@@ -19,6 +19,12 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub struct SerializeableTexture {
     pub image: RgbaQoiImage,
     pub label: Option<String>,
+
+    /// number of mip levels generated
+    /// on upload, `0` and `1` are both
+    /// just the image itself
+    #[serde(default)]
+    pub mip_levels: u32,
 }
 
 /// A helper struct to serialize an rgba image
@@ -37,9 +43,16 @@ impl SerializeableTexture {
         Self {
             image: RgbaQoiImage { image },
             label,
+            mip_levels: 1,
         }
     }
 
+    /// number of mip levels generated on upload
+    pub fn with_mip_levels(mut self, mip_levels: u32) -> Self {
+        self.mip_levels = mip_levels;
+        self
+    }
+
     /// Split this texture to its image and label parts.
     pub fn split(self) -> (RgbaImage, Option<String>) {
         (self.image.image, self.label)
@@ -58,7 +71,16 @@ impl SerializeableTexture {
     /// Upload this texture from RAM to VRAM
     /// (CPU to GPU) handled by [`Texture`].
     pub fn upload<const USAGE: u32>(&self, target: &Target) -> Texture<USAGE> {
-        Texture::new_rgba_with(target, &self.image.image, self.label.as_deref())
+        if self.mip_levels > 1 {
+            Texture::new_rgba_with_mipmaps(
+                target,
+                &self.image.image,
+                Some(self.mip_levels),
+                self.label.as_deref(),
+            )
+        } else {
+            Texture::new_rgba_with(target, &self.image.image, self.label.as_deref())
+        }
     }
 
     pub fn get_dim(&self) -> Rect {
@@ -69,11 +91,25 @@ impl SerializeableTexture {
 impl<const USAGE: u32> Texture<USAGE> {
     /// Download this texture from VRAM to RAM
     /// (GPU to CPU) handled by [`Self`].
+    ///
+    /// the image is converted to rgba8
+    ///
+    /// # Panics
+    ///
+    /// if the texture format can't be read,
+    /// see [`crate::texture::convert`]
     pub async fn download(&self, target: &Target, label: Option<String>) -> SerializeableTexture {
-        let image = RgbaQoiImage {
-            image: self.read(target).await.into_rgba8(),
-        };
-        SerializeableTexture { image, label }
+        let image = self
+            .read(target)
+            .await
+            .expect("Texture format can't be downloaded");
+        SerializeableTexture {
+            image: RgbaQoiImage {
+                image: image.into_rgba8(),
+            },
+            label,
+            mip_levels: self.get_mip_level_count(),
+        }
     }
 
     /// Upload this texture from RAM to VRAM
@@ -0,0 +1,407 @@
+//! Layered and chunked tile grids
+//!
+//! Tiles refer to [`crate::packer::texture::TextureAtlasMap`]
+//! keys and are rendered one chunk at a time by
+//! [`TileMapRenderer`], which rebuilds only the
+//! chunks that changed.
+
+use crate::color::Color;
+use glam::{IVec2, Vec2};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashMap, hash::Hash};
+
+//
+
+pub use renderer::*;
+
+//
+
+pub mod prelude;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+
+mod renderer;
+
+//
+
+/// side length of a chunk in tiles
+pub const CHUNK_SIZE: i32 = 16;
+
+/// A grid of tile layers
+///
+/// The tile `(x, y)` covers the area from
+/// `(x, y) * tile_size` to `(x + 1, y + 1) * tile_size`
+/// (plus the offset of its layer).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileMap<K>
+where
+    K: Eq + Hash + Clone,
+{
+    tile_size: Vec2,
+
+    /// keys used by the tiles, [`Tile::index`]
+    /// is an index into this
+    palette: Vec<K>,
+    #[serde(skip)]
+    palette_map: HashMap<K, u32>,
+
+    layers: Vec<TileLayer>,
+
+    // incremented every time a chunk changes
+    version: u64,
+}
+
+/// A single layer of a [`TileMap`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileLayer {
+    pub name: String,
+
+    /// invisible layers are not rendered
+    pub visible: bool,
+
+    /// tint and opacity of all tiles
+    pub color: Color,
+
+    /// world space offset of all tiles
+    pub offset: Vec2,
+
+    /// custom properties
+    #[serde(default)]
+    pub properties: Properties,
+
+    #[serde(
+        serialize_with = "serialize_chunks",
+        deserialize_with = "deserialize_chunks"
+    )]
+    chunks: HashMap<IVec2, Chunk>,
+}
+
+/// A tile in a [`TileLayer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Tile {
+    /// index into the palette of the [`TileMap`],
+    /// see [`TileMap::tile`]
+    pub index: u32,
+
+    pub flip_x: bool,
+    pub flip_y: bool,
+
+    /// swap the x and y axes of the
+    /// texture, applied before the flips
+    ///
+    /// combined with the flips, this
+    /// rotates the tile by 90°
+    pub flip_diagonal: bool,
+}
+
+/// A custom property value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Color(Color),
+
+    /// a path to a file
+    File(String),
+
+    /// an id of an object
+    Object(u32),
+
+    /// nested properties
+    Class(Properties),
+}
+
+/// Custom properties by name
+pub type Properties = HashMap<String, Property>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Chunk {
+    tiles: Vec<Option<Tile>>,
+    count: usize,
+    version: u64,
+}
+
+//
+
+impl<K> TileMap<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(tile_size: Vec2) -> Self {
+        Self {
+            tile_size,
+            palette: vec![],
+            palette_map: Default::default(),
+            layers: vec![],
+            version: 0,
+        }
+    }
+
+    pub fn get_tile_size(&self) -> Vec2 {
+        self.tile_size
+    }
+
+    /// add a new layer on top of the other layers
+    ///
+    /// returns the index of the layer
+    pub fn push_layer(&mut self, name: impl Into<String>) -> usize {
+        self.layers.push(TileLayer::new(name));
+        self.layers.len() - 1
+    }
+
+    pub fn layer(&self, layer: usize) -> Option<&TileLayer> {
+        self.layers.get(layer)
+    }
+
+    pub fn layer_mut(&mut self, layer: usize) -> Option<&mut TileLayer> {
+        self.layers.get_mut(layer)
+    }
+
+    /// find a layer by its name
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    /// a tile that shows `key`
+    ///
+    /// adds `key` to the palette if it isn't there yet
+    pub fn tile(&mut self, key: K) -> Tile {
+        if self.palette_map.len() != self.palette.len() {
+            self.rebuild_palette_map();
+        }
+
+        let palette = &mut self.palette;
+        let index = *self.palette_map.entry(key).or_insert_with_key(|key| {
+            palette.push(key.clone());
+            palette.len() as u32 - 1
+        });
+        Tile {
+            index,
+            ..Default::default()
+        }
+    }
+
+    /// the key that a tile shows
+    pub fn key(&self, tile: Tile) -> Option<&K> {
+        self.palette.get(tile.index as usize)
+    }
+
+    pub fn palette(&self) -> &[K] {
+        &self.palette
+    }
+
+    /// set the key at a tile position
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `layer` is out of bounds
+    pub fn set(&mut self, layer: usize, pos: IVec2, key: Option<K>) {
+        let tile = key.map(|key| self.tile(key));
+        self.set_tile(layer, pos, tile);
+    }
+
+    /// the key at a tile position
+    pub fn get(&self, layer: usize, pos: IVec2) -> Option<&K> {
+        self.key(self.get_tile(layer, pos)?)
+    }
+
+    /// set or clear a tile
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `layer` is out of bounds
+    /// or if the tile isn't from this map's palette
+    pub fn set_tile(&mut self, layer: usize, pos: IVec2, tile: Option<Tile>) {
+        if let Some(tile) = tile {
+            assert!(
+                (tile.index as usize) < self.palette.len(),
+                "Tile is not from this tile map"
+            );
+        }
+
+        let (chunk_pos, index) = Self::split(pos);
+        let chunks = &mut self.layers[layer].chunks;
+        let chunk = match (chunks.get_mut(&chunk_pos), tile) {
+            (Some(chunk), _) => chunk,
+            (None, None) => return,
+            (None, Some(_)) => chunks.entry(chunk_pos).or_insert_with(Chunk::new),
+        };
+
+        let old = &mut chunk.tiles[index];
+        if *old == tile {
+            return;
+        }
+        match (old.is_some(), tile.is_some()) {
+            (false, true) => chunk.count += 1,
+            (true, false) => chunk.count -= 1,
+            _ => {}
+        }
+        *old = tile;
+
+        self.version += 1;
+        chunk.version = self.version;
+        if chunk.count == 0 {
+            chunks.remove(&chunk_pos);
+        }
+    }
+
+    pub fn get_tile(&self, layer: usize, pos: IVec2) -> Option<Tile> {
+        let (chunk_pos, index) = Self::split(pos);
+        self.layers.get(layer)?.chunks.get(&chunk_pos)?.tiles[index]
+    }
+
+    /// remove all tiles of a layer
+    pub fn clear_layer(&mut self, layer: usize) {
+        if let Some(layer) = self.layers.get_mut(layer) {
+            layer.chunks.clear();
+        }
+    }
+
+    /// the tile at a world position
+    pub fn tile_at(&self, layer: usize, world: Vec2) -> IVec2 {
+        let offset = self.layers.get(layer).map_or(Vec2::ZERO, |l| l.offset);
+        ((world - offset) / self.tile_size).floor().as_ivec2()
+    }
+
+    /// world position of the bottom left corner of a tile
+    pub fn to_world(&self, layer: usize, pos: IVec2) -> Vec2 {
+        let offset = self.layers.get(layer).map_or(Vec2::ZERO, |l| l.offset);
+        offset + pos.as_vec2() * self.tile_size
+    }
+
+    // chunk position and the tile index in it
+    fn split(pos: IVec2) -> (IVec2, usize) {
+        let chunk = IVec2::new(pos.x.div_euclid(CHUNK_SIZE), pos.y.div_euclid(CHUNK_SIZE));
+        let local = pos - chunk * CHUNK_SIZE;
+        (chunk, (local.y * CHUNK_SIZE + local.x) as usize)
+    }
+
+    // the map is not serialized
+    fn rebuild_palette_map(&mut self) {
+        self.palette_map = self
+            .palette
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.clone(), i as u32))
+            .collect();
+    }
+}
+
+impl TileLayer {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            visible: true,
+            color: Color::WHITE,
+            offset: Vec2::ZERO,
+            properties: Default::default(),
+            chunks: Default::default(),
+        }
+    }
+
+    /// positions of all non-empty chunks
+    pub fn chunks(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// all tiles and their positions
+    pub fn tiles(&self) -> impl Iterator<Item = (IVec2, Tile)> + '_ {
+        self.chunks
+            .iter()
+            .flat_map(|(&chunk_pos, chunk)| chunk.tiles_at(chunk_pos))
+    }
+}
+
+impl Chunk {
+    fn new() -> Self {
+        Self {
+            tiles: vec![None; (CHUNK_SIZE * CHUNK_SIZE) as usize],
+            count: 0,
+            version: 0,
+        }
+    }
+
+    fn tiles_at(&self, chunk_pos: IVec2) -> impl Iterator<Item = (IVec2, Tile)> + '_ {
+        self.tiles.iter().enumerate().filter_map(move |(i, tile)| {
+            let local = IVec2::new(i as i32 % CHUNK_SIZE, i as i32 / CHUNK_SIZE);
+            Some((chunk_pos * CHUNK_SIZE + local, (*tile)?))
+        })
+    }
+}
+
+// JSON map keys have to be strings,
+// so the chunks are stored as a list
+fn serialize_chunks<S>(chunks: &HashMap<IVec2, Chunk>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(chunks)
+}
+
+fn deserialize_chunks<'de, D>(deserializer: D) -> Result<HashMap<IVec2, Chunk>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<(IVec2, Chunk)>::deserialize(deserializer)?
+        .into_iter()
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TileMap, CHUNK_SIZE};
+    use glam::{IVec2, Vec2};
+
+    #[test]
+    pub fn test_tile_map() {
+        let mut map = TileMap::new(Vec2::new(16.0, 8.0));
+        let layer = map.push_layer("ground");
+
+        map.set(layer, IVec2::new(-1, 3), Some("grass"));
+        map.set(layer, IVec2::new(CHUNK_SIZE, 0), Some("dirt"));
+        map.set(layer, IVec2::new(0, 0), Some("grass"));
+        assert_eq!(map.palette(), ["grass", "dirt"]);
+        assert_eq!(map.get(layer, IVec2::new(-1, 3)), Some(&"grass"));
+        assert_eq!(map.get(layer, IVec2::new(-1, 2)), None);
+
+        let mut chunks: Vec<_> = map.layers()[layer].chunks().collect();
+        chunks.sort_by_key(|c| (c.x, c.y));
+        assert_eq!(
+            chunks,
+            [IVec2::new(-1, 0), IVec2::new(0, 0), IVec2::new(1, 0)]
+        );
+
+        // empty chunks are removed
+        map.set(layer, IVec2::new(CHUNK_SIZE, 0), None);
+        assert_eq!(map.layers()[layer].chunks().count(), 2);
+        assert_eq!(map.layers()[layer].tiles().count(), 2);
+
+        assert_eq!(map.tile_at(layer, Vec2::new(-0.5, 8.5)), IVec2::new(-1, 1));
+        assert_eq!(
+            map.to_world(layer, IVec2::new(2, -1)),
+            Vec2::new(32.0, -8.0)
+        );
+    }
+
+    #[test]
+    pub fn test_serde() {
+        let mut map = TileMap::new(Vec2::new(16.0, 16.0));
+        let layer = map.push_layer("ground");
+        map.set(layer, IVec2::new(-1, 3), Some("grass".to_string()));
+        map.set(layer, IVec2::new(CHUNK_SIZE, 0), Some("dirt".to_string()));
+
+        let json = serde_json::to_string(&map).unwrap();
+        let mut loaded: TileMap<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.palette(), map.palette());
+        assert_eq!(loaded.layers(), map.layers());
+
+        loaded.set(layer, IVec2::new(0, 0), Some("dirt".to_string()));
+        assert_eq!(loaded.palette().len(), 2);
+    }
+}
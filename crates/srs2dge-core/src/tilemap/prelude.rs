@@ -0,0 +1,3 @@
+#[cfg(feature = "tiled")]
+pub use super::tiled::*;
+pub use super::*;
@@ -0,0 +1,267 @@
+use super::{Chunk, Tile, TileMap, CHUNK_SIZE};
+use crate::{
+    color::Color,
+    packer::texture::TextureAtlasMap,
+    prelude::{DefaultVertex, Frame, IndexBuffer, PagedTexturePosition, Target, VertexBuffer},
+};
+use glam::{IVec2, Vec2};
+use std::{collections::HashMap, hash::Hash};
+
+//
+
+/// Renders [`TileMap`]s chunk by chunk
+///
+/// Each chunk has its own cached vertex and index
+/// buffers that are rebuilt only if a tile in it,
+/// the layer's color or offset or the texture
+/// position of a palette key changes. Chunks
+/// outside of the view are skipped and not
+/// rebuilt until visible.
+#[derive(Debug, Default)]
+pub struct TileMapRenderer {
+    chunks: HashMap<(usize, IVec2), ChunkMesh>,
+
+    // the palette the chunks were built with,
+    // `None` for keys missing from the atlas
+    palette: Vec<Option<PagedTexturePosition>>,
+}
+
+/// A draw call of a visible chunk
+///
+/// Bind the page texture from
+/// [`TextureAtlasMap::page`] before drawing.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkDraw<'a> {
+    pub layer: usize,
+    pub page: u32,
+    pub vbo: &'a VertexBuffer,
+    pub ibo: &'a IndexBuffer,
+    pub indices: u32,
+}
+
+#[derive(Debug)]
+struct ChunkMesh {
+    // state the mesh was built from
+    version: u64,
+    color: Color,
+    offset: Vec2,
+    tile_size: Vec2,
+
+    pages: Vec<PageMesh>,
+}
+
+#[derive(Debug)]
+struct PageMesh {
+    page: u32,
+    vbo: VertexBuffer,
+    ibo: IndexBuffer,
+    indices: u32,
+}
+
+// vertices and indices of one atlas page
+type PageData = (u32, Vec<DefaultVertex>, Vec<u32>);
+
+//
+
+impl TileMapRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// drop all cached chunk meshes
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// rebuild the changed visible chunks
+    /// and return their draw calls in the
+    /// order of the layers
+    ///
+    /// `view` is the visible world space area
+    /// as its min and max corners, `None`
+    /// draws all chunks
+    pub fn generate<K>(
+        &mut self,
+        target: &mut Target,
+        frame: &mut Frame,
+        map: &TileMap<K>,
+        atlas: &TextureAtlasMap<K>,
+        view: Option<(Vec2, Vec2)>,
+    ) -> Vec<ChunkDraw<'_>>
+    where
+        K: Eq + Hash + Clone,
+    {
+        // drop chunks that were removed
+        self.chunks.retain(|(layer, chunk_pos), _| {
+            map.layers
+                .get(*layer)
+                .map_or(false, |layer| layer.chunks.contains_key(chunk_pos))
+        });
+
+        // keys that were inserted, removed or moved
+        // since the last call (or a different atlas)
+        // invalidate all chunks, new palette entries
+        // are only used by chunks that changed anyway
+        let palette: Vec<_> = map.palette.iter().map(|key| atlas.get_paged(key)).collect();
+        if palette.len() < self.palette.len()
+            || self.palette.iter().zip(palette.iter()).any(|(a, b)| a != b)
+        {
+            self.chunks.clear();
+        }
+        self.palette = palette;
+        let palette = &self.palette;
+
+        let tile_size = map.tile_size;
+        let chunk_size = tile_size * CHUNK_SIZE as f32;
+        let mut visible = vec![];
+        for (index, layer) in map.layers.iter().enumerate() {
+            if !layer.visible {
+                continue;
+            }
+
+            let mut chunks: Vec<_> = layer
+                .chunks
+                .iter()
+                .filter(|(chunk_pos, _)| {
+                    let min = layer.offset + chunk_pos.as_vec2() * chunk_size;
+                    let max = min + chunk_size;
+                    view.map_or(true, |(view_min, view_max)| {
+                        min.cmplt(view_max).all() && max.cmpgt(view_min).all()
+                    })
+                })
+                .collect();
+            chunks.sort_unstable_by_key(|(chunk_pos, _)| (chunk_pos.y, chunk_pos.x));
+
+            for (&chunk_pos, chunk) in chunks {
+                let key = (index, chunk_pos);
+                let outdated = self.chunks.get(&key).map_or(true, |mesh| {
+                    mesh.version != chunk.version
+                        || mesh.color != layer.color
+                        || mesh.offset != layer.offset
+                        || mesh.tile_size != tile_size
+                });
+
+                if outdated {
+                    let origin = layer.offset + chunk_pos.as_vec2() * chunk_size;
+                    let data = chunk_data(chunk, origin, tile_size, layer.color, palette);
+                    let old = self.chunks.remove(&key).map(|mesh| mesh.pages);
+                    let pages = upload(target, frame, old.unwrap_or_default(), data);
+                    self.chunks.insert(
+                        key,
+                        ChunkMesh {
+                            version: chunk.version,
+                            color: layer.color,
+                            offset: layer.offset,
+                            tile_size,
+                            pages,
+                        },
+                    );
+                }
+
+                visible.push(key);
+            }
+        }
+
+        visible
+            .into_iter()
+            .flat_map(|key| {
+                self.chunks[&key].pages.iter().map(move |page| ChunkDraw {
+                    layer: key.0,
+                    page: page.page,
+                    vbo: &page.vbo,
+                    ibo: &page.ibo,
+                    indices: page.indices,
+                })
+            })
+            .collect()
+    }
+}
+
+// reuse the old buffers if they are large enough
+fn upload(
+    target: &mut Target,
+    frame: &mut Frame,
+    mut old: Vec<PageMesh>,
+    data: Vec<PageData>,
+) -> Vec<PageMesh> {
+    data.into_iter()
+        .map(|(page, vertices, indices)| {
+            let reused = old
+                .iter()
+                .position(|mesh| {
+                    mesh.vbo.capacity() >= vertices.len() && mesh.ibo.capacity() >= indices.len()
+                })
+                .map(|i| old.swap_remove(i));
+
+            let (vbo, ibo) = match reused {
+                Some(PageMesh { vbo, ibo, .. }) => {
+                    vbo.upload(target, frame, &vertices);
+                    ibo.upload(target, frame, &indices);
+                    (vbo, ibo)
+                }
+                None => (
+                    VertexBuffer::new_with(target, &vertices),
+                    IndexBuffer::new_with(target, &indices),
+                ),
+            };
+
+            PageMesh {
+                page,
+                vbo,
+                ibo,
+                indices: indices.len() as u32,
+            }
+        })
+        .collect()
+}
+
+// quads of all tiles in a chunk grouped by atlas page,
+// tiles whose keys are missing from the atlas are skipped
+fn chunk_data(
+    chunk: &Chunk,
+    origin: Vec2,
+    tile_size: Vec2,
+    color: Color,
+    palette: &[Option<PagedTexturePosition>],
+) -> Vec<PageData> {
+    let mut pages: Vec<PageData> = vec![];
+    for (pos, tile) in chunk.tiles_at(IVec2::ZERO) {
+        let Some(&Some(tex)) = palette.get(tile.index as usize) else {
+            continue;
+        };
+
+        let i = match pages.iter().position(|(p, _, _)| *p == tex.page) {
+            Some(i) => i,
+            None => {
+                pages.push((tex.page, vec![], vec![]));
+                pages.len() - 1
+            }
+        };
+        let (_, vertices, indices) = &mut pages[i];
+
+        let offset = vertices.len() as u32;
+        let pos = origin + pos.as_vec2() * tile_size;
+        for corner in [Vec2::ZERO, Vec2::Y, Vec2::X, Vec2::ONE] {
+            let uv = tex.uv(tile_uv(tile, corner));
+            vertices.push(DefaultVertex::new(pos + corner * tile_size, color, uv));
+        }
+        indices.extend([offset, offset + 1, offset + 2, offset + 3, !0]);
+    }
+    pages
+}
+
+// texture coordinates (`0.0..=1.0`) of a corner
+// of a tile, the y axis of `corner` points up
+fn tile_uv(tile: Tile, corner: Vec2) -> Vec2 {
+    let mut uv = Vec2::new(corner.x, 1.0 - corner.y);
+    if tile.flip_y {
+        uv.y = 1.0 - uv.y;
+    }
+    if tile.flip_x {
+        uv.x = 1.0 - uv.x;
+    }
+    if tile.flip_diagonal {
+        uv = Vec2::new(uv.y, uv.x);
+    }
+    uv
+}
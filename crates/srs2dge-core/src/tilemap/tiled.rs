@@ -0,0 +1,1130 @@
+//! [Tiled](https://www.mapeditor.org/) map loader
+//!
+//! Requires the `tiled` feature.
+//!
+//! Supports the JSON (`.tmj`, `.json`) and
+//! the XML (`.tmx`) formats of orthogonal maps
+//! with tile layers, object layers, group layers
+//! and custom properties.
+//!
+//! Tiled rows grow downwards, so the tile row `r`
+//! is placed at `y = -r - 1` and object positions
+//! are flipped to `(x, -y)`. The map's top left
+//! corner stays at the origin and one world unit
+//! is one pixel.
+
+use super::{Properties, Property, Tile, TileMap};
+use crate::{
+    color::Color,
+    packer::rect::{PositionedRect, Rect},
+};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use glam::{IVec2, Vec2};
+use image::{imageops, RgbaImage};
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, io::Read};
+
+//
+
+/// A loaded Tiled map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TiledMap {
+    /// the tile layers, the keys are
+    /// global tile ids (see [`Tileset::slice`])
+    pub tile_map: TileMap<u32>,
+
+    pub object_layers: Vec<ObjectLayer>,
+
+    pub tilesets: Vec<Tileset>,
+
+    /// custom properties of the map
+    pub properties: Properties,
+}
+
+/// A layer of [`Object`]s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub visible: bool,
+
+    /// tint and opacity
+    pub color: Color,
+    pub offset: Vec2,
+
+    pub objects: Vec<Object>,
+    pub properties: Properties,
+
+    /// the number of tile layers
+    /// below this layer
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Object {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+
+    /// the top left corner, or the bottom
+    /// left corner of tile objects
+    pub pos: Vec2,
+    pub size: Vec2,
+
+    /// clockwise in degrees
+    pub rotation: f32,
+    pub visible: bool,
+
+    pub shape: ObjectShape,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ObjectShape {
+    Rect,
+    Ellipse,
+    Point,
+
+    /// points relative to the position
+    Polygon(Vec<Vec2>),
+
+    /// points relative to the position
+    Polyline(Vec<Vec2>),
+
+    /// a tile drawn at the object
+    Tile {
+        gid: u32,
+        flip_x: bool,
+        flip_y: bool,
+    },
+}
+
+/// The tiles of a map
+///
+/// External tilesets only have `first_gid` and
+/// `source`, load them with [`Tileset::from_json`]
+/// or [`Tileset::from_tsx`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Tileset {
+    pub name: String,
+
+    /// the global id of the first tile
+    pub first_gid: u32,
+
+    /// path of an external tileset
+    pub source: Option<String>,
+
+    pub tile_size: Rect,
+    pub columns: u32,
+    pub tile_count: u32,
+    pub margin: u32,
+    pub spacing: u32,
+
+    /// the image of all tiles, `None` if
+    /// each tile has its own image
+    pub image: Option<String>,
+
+    /// tiles with an image, a class
+    /// or properties by their local ids
+    pub tiles: HashMap<u32, TilesetTile>,
+
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TilesetTile {
+    pub image: Option<String>,
+    pub class: String,
+    pub properties: Properties,
+}
+
+#[derive(Debug)]
+pub enum TiledError {
+    /// invalid JSON or not a Tiled map
+    Json(serde_json::Error),
+
+    /// invalid XML
+    Xml(roxmltree::Error),
+
+    /// the tile layer data could not be decoded
+    Data(String),
+
+    /// the map uses something that
+    /// is not supported yet
+    Unsupported(String),
+}
+
+//
+
+const FLIP_X: u32 = 0x8000_0000;
+const FLIP_Y: u32 = 0x4000_0000;
+const FLIP_DIAGONAL: u32 = 0x2000_0000;
+const GID_MASK: u32 = 0x0fff_ffff;
+
+/// TMX attributes that are numbers in the JSON format
+const NUMBERS: &[&str] = &[
+    "width",
+    "height",
+    "tilewidth",
+    "tileheight",
+    "x",
+    "y",
+    "offsetx",
+    "offsety",
+    "opacity",
+    "rotation",
+    "gid",
+    "id",
+    "firstgid",
+    "columns",
+    "tilecount",
+    "margin",
+    "spacing",
+    "imagewidth",
+    "imageheight",
+];
+
+/// TMX attributes that are booleans in the JSON format
+const BOOLS: &[&str] = &["visible", "infinite"];
+
+#[derive(Debug, Deserialize)]
+struct JsonMap {
+    tilewidth: u32,
+    tileheight: u32,
+
+    #[serde(default)]
+    orientation: Option<String>,
+
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum JsonLayer {
+    #[serde(rename = "tilelayer")]
+    Tile(JsonTileLayer),
+
+    #[serde(rename = "objectgroup")]
+    Object(JsonObjectLayer),
+
+    #[serde(rename = "group")]
+    Group(JsonGroupLayer),
+
+    #[serde(rename = "imagelayer")]
+    Image,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonLayerCommon {
+    #[serde(default)]
+    name: String,
+
+    #[serde(default = "one")]
+    opacity: f32,
+
+    #[serde(default = "yes")]
+    visible: bool,
+
+    #[serde(default)]
+    offsetx: f32,
+
+    #[serde(default)]
+    offsety: f32,
+
+    #[serde(default)]
+    tintcolor: Option<String>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTileLayer {
+    #[serde(flatten)]
+    common: JsonLayerCommon,
+
+    #[serde(default)]
+    data: Option<JsonData>,
+
+    #[serde(default)]
+    chunks: Vec<JsonChunk>,
+
+    #[serde(default)]
+    compression: Option<String>,
+
+    #[serde(default)]
+    width: u32,
+
+    #[serde(default)]
+    x: i32,
+
+    #[serde(default)]
+    y: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonChunk {
+    data: JsonData,
+    x: i32,
+    y: i32,
+    width: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Array(Vec<u32>),
+    Base64(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonObjectLayer {
+    #[serde(flatten)]
+    common: JsonLayerCommon,
+
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonGroupLayer {
+    #[serde(flatten)]
+    common: JsonLayerCommon,
+
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    id: u32,
+
+    #[serde(default)]
+    name: String,
+
+    /// called `class` in Tiled 1.9
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+
+    #[serde(default)]
+    x: f32,
+
+    #[serde(default)]
+    y: f32,
+
+    #[serde(default)]
+    width: f32,
+
+    #[serde(default)]
+    height: f32,
+
+    #[serde(default)]
+    rotation: f32,
+
+    #[serde(default = "yes")]
+    visible: bool,
+
+    #[serde(default)]
+    gid: Option<u32>,
+
+    #[serde(default)]
+    point: bool,
+
+    #[serde(default)]
+    ellipse: bool,
+
+    #[serde(default)]
+    polygon: Option<Vec<JsonPoint>>,
+
+    #[serde(default)]
+    polyline: Option<Vec<JsonPoint>>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    firstgid: u32,
+
+    #[serde(default)]
+    source: Option<String>,
+
+    #[serde(default)]
+    name: String,
+
+    #[serde(default)]
+    tilewidth: u32,
+
+    #[serde(default)]
+    tileheight: u32,
+
+    #[serde(default)]
+    columns: u32,
+
+    #[serde(default)]
+    tilecount: u32,
+
+    #[serde(default)]
+    margin: u32,
+
+    #[serde(default)]
+    spacing: u32,
+
+    #[serde(default)]
+    image: Option<String>,
+
+    #[serde(default)]
+    tiles: Vec<JsonTilesetTile>,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonTilesetTile {
+    id: u32,
+
+    #[serde(default)]
+    image: Option<String>,
+
+    /// called `class` in Tiled 1.9
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonProperty {
+    name: String,
+
+    #[serde(default, rename = "type")]
+    ty: String,
+
+    #[serde(default)]
+    value: Value,
+}
+
+// layer state inherited from group layers
+#[derive(Debug, Clone, Copy)]
+struct Parent {
+    visible: bool,
+    color: Color,
+    offset: Vec2,
+}
+
+//
+
+impl TiledMap {
+    /// load a map from the JSON format
+    pub fn from_json(json: &str) -> Result<Self, TiledError> {
+        Self::convert(serde_json::from_str(json)?)
+    }
+
+    /// load a map from the XML format
+    pub fn from_tmx(tmx: &str) -> Result<Self, TiledError> {
+        let document = Document::parse(tmx)?;
+        let map = tmx_map(document.root_element())?;
+        Self::convert(serde_json::from_value(map)?)
+    }
+
+    /// the tileset that has the tile `gid`
+    pub fn tileset(&self, gid: u32) -> Option<&Tileset> {
+        self.tilesets
+            .iter()
+            .filter(|tileset| tileset.first_gid <= gid)
+            .max_by_key(|tileset| tileset.first_gid)
+    }
+
+    fn convert(map: JsonMap) -> Result<Self, TiledError> {
+        if let Some(orientation) = map.orientation.filter(|o| o != "orthogonal") {
+            return Err(TiledError::Unsupported(format!(
+                "{orientation} orientation"
+            )));
+        }
+
+        let mut tiled = Self {
+            tile_map: TileMap::new(Vec2::new(map.tilewidth as f32, map.tileheight as f32)),
+            object_layers: vec![],
+            tilesets: map.tilesets.into_iter().map(Tileset::convert).collect(),
+            properties: properties(map.properties),
+        };
+
+        let root = Parent {
+            visible: true,
+            color: Color::WHITE,
+            offset: Vec2::ZERO,
+        };
+        for layer in map.layers {
+            tiled.push_layer(layer, root)?;
+        }
+
+        Ok(tiled)
+    }
+
+    fn push_layer(&mut self, layer: JsonLayer, parent: Parent) -> Result<(), TiledError> {
+        match layer {
+            JsonLayer::Tile(layer) => {
+                let (name, parent, props) = parent.inherit(layer.common);
+                let index = self.tile_map.push_layer(name);
+                let tile_layer = self.tile_map.layer_mut(index).unwrap();
+                tile_layer.visible = parent.visible;
+                tile_layer.color = parent.color;
+                tile_layer.offset = parent.offset;
+                tile_layer.properties = props;
+
+                let compression = layer.compression.as_deref();
+                let mut chunks = vec![];
+                if let Some(data) = layer.data {
+                    chunks.push((layer.x, layer.y, layer.width, decode(data, compression)?));
+                }
+                for chunk in layer.chunks {
+                    let data = decode(chunk.data, compression)?;
+                    chunks.push((chunk.x, chunk.y, chunk.width, data));
+                }
+
+                for (x, y, width, data) in chunks {
+                    for (i, gid) in data.into_iter().enumerate() {
+                        if gid & GID_MASK == 0 {
+                            continue;
+                        }
+
+                        let col = x + (i as u32 % width.max(1)) as i32;
+                        let row = y + (i as u32 / width.max(1)) as i32;
+                        let tile = Tile {
+                            flip_x: gid & FLIP_X != 0,
+                            flip_y: gid & FLIP_Y != 0,
+                            flip_diagonal: gid & FLIP_DIAGONAL != 0,
+                            ..self.tile_map.tile(gid & GID_MASK)
+                        };
+                        self.tile_map
+                            .set_tile(index, IVec2::new(col, -row - 1), Some(tile));
+                    }
+                }
+            }
+            JsonLayer::Object(layer) => {
+                let (name, parent, props) = parent.inherit(layer.common);
+                self.object_layers.push(ObjectLayer {
+                    name,
+                    visible: parent.visible,
+                    color: parent.color,
+                    offset: parent.offset,
+                    objects: layer.objects.into_iter().map(Object::convert).collect(),
+                    properties: props,
+                    depth: self.tile_map.layers().len(),
+                });
+            }
+            JsonLayer::Group(group) => {
+                let (_, parent, _) = parent.inherit(group.common);
+                for layer in group.layers {
+                    self.push_layer(layer, parent)?;
+                }
+            }
+            JsonLayer::Image => {
+                tracing::warn!("Tiled image layers are not supported");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Object {
+    fn convert(object: JsonObject) -> Self {
+        let points = |points: Vec<JsonPoint>| {
+            points
+                .into_iter()
+                .map(|JsonPoint { x, y }| Vec2::new(x, -y))
+                .collect()
+        };
+
+        let shape = if let Some(gid) = object.gid {
+            ObjectShape::Tile {
+                gid: gid & GID_MASK,
+                flip_x: gid & FLIP_X != 0,
+                flip_y: gid & FLIP_Y != 0,
+            }
+        } else if let Some(polygon) = object.polygon {
+            ObjectShape::Polygon(points(polygon))
+        } else if let Some(polyline) = object.polyline {
+            ObjectShape::Polyline(points(polyline))
+        } else if object.point {
+            ObjectShape::Point
+        } else if object.ellipse {
+            ObjectShape::Ellipse
+        } else {
+            ObjectShape::Rect
+        };
+
+        Self {
+            id: object.id,
+            name: object.name,
+            class: object.class,
+            pos: Vec2::new(object.x, -object.y),
+            size: Vec2::new(object.width, object.height),
+            rotation: object.rotation,
+            visible: object.visible,
+            shape,
+            properties: properties(object.properties),
+        }
+    }
+}
+
+impl Tileset {
+    /// load an external tileset from the JSON format
+    pub fn from_json(json: &str, first_gid: u32) -> Result<Self, TiledError> {
+        let tileset: JsonTileset = serde_json::from_str(json)?;
+        Ok(Self {
+            first_gid,
+            ..Self::convert(tileset)
+        })
+    }
+
+    /// load an external tileset from the XML format
+    pub fn from_tsx(tsx: &str, first_gid: u32) -> Result<Self, TiledError> {
+        let document = Document::parse(tsx)?;
+        let tileset: JsonTileset = serde_json::from_value(tmx_tileset(document.root_element()))?;
+        Ok(Self {
+            first_gid,
+            ..Self::convert(tileset)
+        })
+    }
+
+    /// the area of a tile in the tileset image
+    pub fn tile_rect(&self, id: u32) -> PositionedRect {
+        let columns = self.columns.max(1);
+        let (col, row) = (id % columns, id / columns);
+        PositionedRect::new(
+            self.margin + col * (self.tile_size.width + self.spacing),
+            self.margin + row * (self.tile_size.height + self.spacing),
+            self.tile_size.width,
+            self.tile_size.height,
+        )
+    }
+
+    /// cut the tileset image into tiles
+    /// keyed by their global ids
+    ///
+    /// the results can be inserted into a
+    /// `TextureAtlasMapBuilder<u32>`
+    pub fn slice(&self, image: &RgbaImage) -> Vec<(u32, RgbaImage)> {
+        (0..self.tile_count)
+            .map(|id| (id, self.tile_rect(id)))
+            .filter(|(_, rect)| {
+                rect.x + rect.width <= image.width() && rect.y + rect.height <= image.height()
+            })
+            .map(|(id, rect)| {
+                let tile = imageops::crop_imm(image, rect.x, rect.y, rect.width, rect.height);
+                (self.first_gid + id, tile.to_image())
+            })
+            .collect()
+    }
+
+    fn convert(tileset: JsonTileset) -> Self {
+        Self {
+            name: tileset.name,
+            first_gid: tileset.firstgid,
+            source: tileset.source,
+            tile_size: Rect::new(tileset.tilewidth, tileset.tileheight),
+            columns: tileset.columns,
+            tile_count: tileset.tilecount,
+            margin: tileset.margin,
+            spacing: tileset.spacing,
+            image: tileset.image,
+            tiles: tileset
+                .tiles
+                .into_iter()
+                .map(|tile| {
+                    let tileset_tile = TilesetTile {
+                        image: tile.image,
+                        class: tile.class,
+                        properties: properties(tile.properties),
+                    };
+                    (tile.id, tileset_tile)
+                })
+                .collect(),
+            properties: properties(tileset.properties),
+        }
+    }
+}
+
+impl Parent {
+    fn inherit(self, layer: JsonLayerCommon) -> (String, Self, Properties) {
+        let tint = layer
+            .tintcolor
+            .as_deref()
+            .and_then(parse_color)
+            .unwrap_or(Color::WHITE);
+        let mut color = self.color * tint;
+        color.a *= layer.opacity;
+
+        let parent = Self {
+            visible: self.visible && layer.visible,
+            color,
+            offset: self.offset + Vec2::new(layer.offsetx, -layer.offsety),
+        };
+        (layer.name, parent, properties(layer.properties))
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<roxmltree::Error> for TiledError {
+    fn from(err: roxmltree::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+fn yes() -> bool {
+    true
+}
+
+// tile layer data as global tile ids
+fn decode(data: JsonData, compression: Option<&str>) -> Result<Vec<u32>, TiledError> {
+    let base64 = match data {
+        JsonData::Array(gids) => return Ok(gids),
+        JsonData::Base64(base64) => base64,
+    };
+
+    let bytes = base64::decode(base64.trim()).map_err(|err| TiledError::Data(err.to_string()))?;
+    let mut decompressed = vec![];
+    let result = match compression.unwrap_or_default() {
+        "" => {
+            decompressed = bytes;
+            Ok(0)
+        }
+        "zlib" => ZlibDecoder::new(&bytes[..]).read_to_end(&mut decompressed),
+        "gzip" => GzDecoder::new(&bytes[..]).read_to_end(&mut decompressed),
+        other => return Err(TiledError::Unsupported(format!("{other} compression"))),
+    };
+    result.map_err(|err| TiledError::Data(err.to_string()))?;
+
+    Ok(decompressed
+        .chunks_exact(4)
+        .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+        .collect())
+}
+
+fn properties(properties: Vec<JsonProperty>) -> Properties {
+    properties
+        .into_iter()
+        .map(|JsonProperty { name, ty, value }| (name, property(&ty, value)))
+        .collect()
+}
+
+fn property(ty: &str, value: Value) -> Property {
+    match (ty, value) {
+        ("int", Value::Number(n)) => Property::Int(n.as_i64().unwrap_or_default()),
+        ("float", Value::Number(n)) => Property::Float(n.as_f64().unwrap_or_default()),
+        ("color", Value::String(s)) => Property::Color(parse_color(&s).unwrap_or_default()),
+        ("file", Value::String(s)) => Property::File(s),
+        ("object", Value::Number(n)) => Property::Object(n.as_u64().unwrap_or_default() as u32),
+        (_, Value::Bool(b)) => Property::Bool(b),
+        (_, Value::Number(n)) => match n.as_i64() {
+            Some(i) => Property::Int(i),
+            None => Property::Float(n.as_f64().unwrap_or_default()),
+        },
+        (_, Value::String(s)) => Property::String(s),
+        (_, Value::Object(members)) => Property::Class(
+            members
+                .into_iter()
+                .map(|(name, value)| (name, property("", value)))
+                .collect(),
+        ),
+        (_, value) => Property::String(value.to_string()),
+    }
+}
+
+// `#AARRGGBB` or `#RRGGBB`
+fn parse_color(s: &str) -> Option<Color> {
+    let hex = s.trim_start_matches('#');
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        8 => Some(Color::from(value.rotate_left(8))),
+        6 => Some(Color::from(value << 8 | 0xff)),
+        _ => None,
+    }
+}
+
+// the TMX formats are converted to
+// the JSON formats and parsed from there
+
+fn tmx_map(node: Node) -> Result<Value, TiledError> {
+    let mut map = tmx_attributes(node);
+    map.insert("layers".into(), tmx_layers(node)?);
+    map.insert(
+        "tilesets".into(),
+        node.children()
+            .filter(|child| child.has_tag_name("tileset"))
+            .map(tmx_tileset)
+            .collect(),
+    );
+    map.insert("properties".into(), tmx_properties(node));
+    Ok(Value::Object(map))
+}
+
+fn tmx_layers(node: Node) -> Result<Value, TiledError> {
+    let mut layers = vec![];
+    for child in node.children().filter(Node::is_element) {
+        let ty = match child.tag_name().name() {
+            "layer" => "tilelayer",
+            "objectgroup" => "objectgroup",
+            "group" => "group",
+            "imagelayer" => "imagelayer",
+            _ => continue,
+        };
+
+        let mut layer = tmx_attributes(child);
+        layer.insert("type".into(), ty.into());
+        layer.insert("properties".into(), tmx_properties(child));
+        match ty {
+            "tilelayer" => {
+                if let Some(data) = child.children().find(|c| c.has_tag_name("data")) {
+                    tmx_data(data, &mut layer)?;
+                }
+            }
+            "objectgroup" => {
+                let objects = child
+                    .children()
+                    .filter(|c| c.has_tag_name("object"))
+                    .map(tmx_object)
+                    .collect();
+                layer.insert("objects".into(), objects);
+            }
+            "group" => {
+                layer.insert("layers".into(), tmx_layers(child)?);
+            }
+            _ => {}
+        }
+        layers.push(Value::Object(layer));
+    }
+    Ok(Value::Array(layers))
+}
+
+fn tmx_data(data: Node, layer: &mut Map<String, Value>) -> Result<(), TiledError> {
+    let encoding = data.attribute("encoding").unwrap_or_default();
+    if let Some(compression) = data.attribute("compression") {
+        layer.insert("compression".into(), compression.into());
+    }
+
+    let gids = |node: Node| -> Result<Value, TiledError> {
+        let text = node.text().unwrap_or_default().trim();
+        match encoding {
+            "csv" => text
+                .split(',')
+                .map(|gid| gid.trim().parse::<u32>().map(Value::from))
+                .collect::<Result<_, _>>()
+                .map_err(|err| TiledError::Data(err.to_string())),
+            "base64" => Ok(text.into()),
+            "" => Ok(node
+                .children()
+                .filter(|c| c.has_tag_name("tile"))
+                .map(|tile| tile.attribute("gid").and_then(|gid| gid.parse().ok()))
+                .map(|gid: Option<u32>| Value::from(gid.unwrap_or_default()))
+                .collect()),
+            other => Err(TiledError::Unsupported(format!("{other} encoding"))),
+        }
+    };
+
+    let chunks: Vec<_> = data
+        .children()
+        .filter(|c| c.has_tag_name("chunk"))
+        .map(|chunk| {
+            let mut attributes = tmx_attributes(chunk);
+            attributes.insert("data".into(), gids(chunk)?);
+            Ok(Value::Object(attributes))
+        })
+        .collect::<Result<_, TiledError>>()?;
+
+    if chunks.is_empty() {
+        layer.insert("data".into(), gids(data)?);
+    } else {
+        layer.insert("chunks".into(), chunks.into());
+    }
+    Ok(())
+}
+
+fn tmx_object(node: Node) -> Value {
+    let mut object = tmx_attributes(node);
+    object.insert("properties".into(), tmx_properties(node));
+    for child in node.children().filter(Node::is_element) {
+        let name = child.tag_name().name();
+        match name {
+            "ellipse" | "point" => {
+                object.insert(name.into(), true.into());
+            }
+            "polygon" | "polyline" => {
+                let points = child
+                    .attribute("points")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .filter_map(|point| point.split_once(','))
+                    .map(|(x, y)| {
+                        let x: f64 = x.parse().unwrap_or_default();
+                        let y: f64 = y.parse().unwrap_or_default();
+                        serde_json::json!({ "x": x, "y": y })
+                    })
+                    .collect();
+                object.insert(name.into(), points);
+            }
+            _ => {}
+        }
+    }
+    Value::Object(object)
+}
+
+fn tmx_tileset(node: Node) -> Value {
+    let mut tileset = tmx_attributes(node);
+    if let Some(image) = tmx_image(node) {
+        tileset.insert("image".into(), image);
+    }
+    tileset.insert(
+        "tiles".into(),
+        node.children()
+            .filter(|c| c.has_tag_name("tile"))
+            .map(|tile| {
+                let mut attributes = tmx_attributes(tile);
+                if let Some(image) = tmx_image(tile) {
+                    attributes.insert("image".into(), image);
+                }
+                attributes.insert("properties".into(), tmx_properties(tile));
+                Value::Object(attributes)
+            })
+            .collect(),
+    );
+    tileset.insert("properties".into(), tmx_properties(node));
+    Value::Object(tileset)
+}
+
+fn tmx_image(node: Node) -> Option<Value> {
+    let image = node.children().find(|c| c.has_tag_name("image"))?;
+    Some(image.attribute("source")?.into())
+}
+
+fn tmx_properties(node: Node) -> Value {
+    let Some(properties) = node.children().find(|c| c.has_tag_name("properties")) else {
+        return Value::Array(vec![]);
+    };
+
+    properties
+        .children()
+        .filter(|c| c.has_tag_name("property"))
+        .map(|property| {
+            let ty = property.attribute("type").unwrap_or("string");
+            let value = tmx_property_value(property, ty);
+            serde_json::json!({
+                "name": property.attribute("name").unwrap_or_default(),
+                "type": ty,
+                "value": value,
+            })
+        })
+        .collect()
+}
+
+fn tmx_property_value(property: Node, ty: &str) -> Value {
+    if ty == "class" {
+        let members = tmx_properties(property);
+        let members = members
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|member| {
+                Some((
+                    member["name"].as_str()?.to_string(),
+                    member["value"].clone(),
+                ))
+            });
+        return Value::Object(members.collect());
+    }
+
+    // multiline strings are in the text
+    let value = property
+        .attribute("value")
+        .or_else(|| property.text())
+        .unwrap_or_default();
+    match ty {
+        "int" | "object" => value.parse::<i64>().map_or(Value::Null, Value::from),
+        "float" => value.parse::<f64>().map_or(Value::Null, Value::from),
+        "bool" => Value::Bool(value == "true"),
+        _ => value.into(),
+    }
+}
+
+fn tmx_attributes(node: Node) -> Map<String, Value> {
+    node.attributes()
+        .map(|attribute| {
+            let (name, value) = (attribute.name(), attribute.value());
+            let value = if NUMBERS.contains(&name) {
+                value
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .or_else(|_| value.parse::<f64>().map(Value::from))
+                    .unwrap_or_else(|_| value.into())
+            } else if BOOLS.contains(&name) {
+                Value::Bool(value != "0")
+            } else {
+                value.into()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ObjectShape, TiledMap};
+    use crate::{prelude::Color, tilemap::Property};
+    use glam::{IVec2, Vec2};
+
+    const JSON: &str = r##"{
+        "width": 3, "height": 2, "tilewidth": 16, "tileheight": 16,
+        "orientation": "orthogonal", "infinite": false,
+        "properties": [{ "name": "music", "type": "file", "value": "level1.ogg" }],
+        "tilesets": [
+            { "firstgid": 1, "name": "terrain", "tilewidth": 16, "tileheight": 16,
+              "columns": 4, "tilecount": 8, "margin": 0, "spacing": 0, "image": "terrain.png",
+              "tiles": [{ "id": 1, "properties": [{ "name": "solid", "type": "bool", "value": true }] }] },
+            { "firstgid": 9, "source": "props.tsj" }
+        ],
+        "layers": [
+            { "type": "group", "name": "world", "offsetx": 4, "offsety": 8, "opacity": 0.5, "layers": [
+                { "type": "tilelayer", "name": "ground", "width": 3, "height": 2,
+                  "x": 0, "y": 0, "opacity": 1, "visible": true,
+                  "data": [1, 2, 0, 0, 2147483651, 9] }
+            ] },
+            { "type": "objectgroup", "name": "entities", "objects": [
+                { "id": 1, "name": "spawn", "type": "player", "x": 8, "y": 24,
+                  "width": 0, "height": 0, "rotation": 0, "visible": true, "point": true,
+                  "properties": [
+                      { "name": "hp", "type": "int", "value": 3 },
+                      { "name": "tint", "type": "color", "value": "#ff00ff00" }
+                  ] },
+                { "id": 2, "name": "", "x": 0, "y": 0, "width": 0, "height": 0, "rotation": 0,
+                  "visible": true, "polygon": [{ "x": 0, "y": 0 }, { "x": 16, "y": 8 }] }
+            ] }
+        ]
+    }"##;
+
+    // the same map in TMX, but with base64
+    // and zlib encoded tile layer data
+    const TMX: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.9" orientation="orthogonal" renderorder="right-down" width="3" height="2" tilewidth="16" tileheight="16" infinite="0">
+ <properties>
+  <property name="music" type="file" value="level1.ogg"/>
+ </properties>
+ <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="16" tilecount="8" columns="4">
+  <image source="terrain.png" width="64" height="32"/>
+  <tile id="1">
+   <properties>
+    <property name="solid" type="bool" value="true"/>
+   </properties>
+  </tile>
+ </tileset>
+ <tileset firstgid="9" source="props.tsx"/>
+ <group name="world" offsetx="4" offsety="8" opacity="0.5">
+  <layer name="ground" width="3" height="2">
+   <data encoding="base64" compression="zlib">
+    eJxjZGBgYGJAAGYGhgZOIA0AAxQAkA==
+   </data>
+  </layer>
+ </group>
+ <objectgroup name="entities">
+  <object id="1" name="spawn" type="player" x="8" y="24">
+   <properties>
+    <property name="hp" type="int" value="3"/>
+    <property name="tint" type="color" value="#ff00ff00"/>
+   </properties>
+   <point/>
+  </object>
+  <object id="2" x="0" y="0">
+   <polygon points="0,0 16,8"/>
+  </object>
+ </objectgroup>
+</map>
+"##;
+
+    fn check(map: &TiledMap) {
+        let tiles = &map.tile_map;
+        assert_eq!(tiles.get_tile_size(), Vec2::new(16.0, 16.0));
+        assert_eq!(tiles.layers().len(), 1);
+
+        let ground = &tiles.layers()[0];
+        assert_eq!(ground.name, "ground");
+        assert_eq!(ground.offset, Vec2::new(4.0, -8.0));
+        assert_eq!(ground.color.a, 0.5);
+
+        assert_eq!(tiles.get(0, IVec2::new(0, -1)), Some(&1));
+        assert_eq!(tiles.get(0, IVec2::new(1, -1)), Some(&2));
+        assert_eq!(tiles.get(0, IVec2::new(2, -1)), None);
+        assert_eq!(tiles.get(0, IVec2::new(2, -2)), Some(&9));
+        let flipped = tiles.get_tile(0, IVec2::new(1, -2)).unwrap();
+        assert_eq!(tiles.key(flipped), Some(&3));
+        assert!(flipped.flip_x && !flipped.flip_y);
+
+        assert_eq!(map.properties["music"], Property::File("level1.ogg".into()));
+        assert_eq!(map.tileset(5).unwrap().name, "terrain");
+        assert!(map.tileset(9).unwrap().source.is_some());
+        assert_eq!(
+            map.tilesets[0].tiles[&1].properties["solid"],
+            Property::Bool(true)
+        );
+
+        let entities = &map.object_layers[0];
+        assert_eq!(entities.depth, 1);
+        let spawn = &entities.objects[0];
+        assert_eq!(spawn.class, "player");
+        assert_eq!(spawn.pos, Vec2::new(8.0, -24.0));
+        assert_eq!(spawn.shape, ObjectShape::Point);
+        assert_eq!(spawn.properties["hp"], Property::Int(3));
+        assert_eq!(
+            spawn.properties["tint"],
+            Property::Color(Color::new(0.0, 1.0, 0.0, 1.0))
+        );
+        assert_eq!(
+            entities.objects[1].shape,
+            ObjectShape::Polygon(vec![Vec2::ZERO, Vec2::new(16.0, -8.0)])
+        );
+    }
+
+    #[test]
+    pub fn test_json() {
+        check(&TiledMap::from_json(JSON).unwrap());
+    }
+
+    #[test]
+    pub fn test_tmx() {
+        check(&TiledMap::from_tmx(TMX).unwrap());
+    }
+
+    #[test]
+    pub fn test_serde() {
+        let json = serde_json::to_string(&TiledMap::from_json(JSON).unwrap()).unwrap();
+        check(&serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{plugin::Plugin, prelude::Time, sprite::Sprite, World};
+use legion::system;
+use serde::{Deserialize, Serialize};
+use srs2dge_core::prelude::TexturePosition;
+#[cfg(feature = "sheet")]
+use srs2dge_core::prelude::{FrameTag, SpriteSheet, TagDirection};
+use std::{collections::HashMap, hash::Hash};
+
+//
+
+/// How an [`AnimatedSprite`] continues
+/// after the last frame of a clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum AnimationMode {
+    /// start over from the first frame
+    #[default]
+    Loop,
+
+    /// play the frames backwards and forwards
+    PingPong,
+
+    /// stop at the last frame
+    Once,
+}
+
+/// One frame of an [`AnimationClip`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationFrame<K> {
+    /// the key of the frame in a texture atlas map
+    pub key: K,
+
+    /// seconds
+    pub duration: f32,
+}
+
+/// A sequence of sprite sheet frames
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimationClip<K> {
+    pub frames: Vec<AnimationFrame<K>>,
+
+    #[serde(default)]
+    pub mode: AnimationMode,
+}
+
+/// Named [`AnimationClip`]s
+///
+/// This is serializeable and can be stored
+/// in RON next to the `SerializeableTextureAtlasMap`
+/// that has the frames:
+///
+/// ```ron
+/// {
+///     "walk": (
+///         frames: [
+///             (key: "walk_0", duration: 0.1),
+///             (key: "walk_1", duration: 0.1),
+///         ],
+///         mode: PingPong,
+///     ),
+/// }
+/// ```
+pub type AnimationClips<K> = HashMap<String, AnimationClip<K>>;
+
+/// duration of sprite sheet frames
+/// that don't have one, in seconds
+pub const DEFAULT_FRAME_DURATION: f32 = 0.1;
+
+/// The resource of all clips that [`AnimatedSprite`]s can play
+///
+/// The frames are stored as [`TexturePosition`]s,
+/// so this has to be rebuilt if the texture atlas
+/// changes (`TextureAtlasMap::generation`).
+#[derive(Debug, Clone, Default)]
+pub struct SpriteAnimations {
+    clips: HashMap<String, ResolvedClip>,
+}
+
+/// Plays a clip from [`SpriteAnimations`]
+/// by changing the [`Sprite`] of the same entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimatedSprite {
+    clip: String,
+
+    /// playback speed multiplier
+    pub speed: f32,
+
+    /// pauses the animation if false
+    pub playing: bool,
+
+    #[serde(default)]
+    frame: usize,
+    #[serde(default)]
+    elapsed: f32,
+    #[serde(default)]
+    backwards: bool,
+    #[serde(default)]
+    finished: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SpriteAnimationPlugin;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ResolvedClip {
+    frames: Vec<(TexturePosition, f32)>,
+    mode: AnimationMode,
+}
+
+//
+
+#[cfg(feature = "sheet")]
+impl AnimationClip<String> {
+    /// a clip from a frame tag of an imported sprite sheet
+    ///
+    /// reverse directions reverse the frames and
+    /// a finite repeat count repeats them in an
+    /// [`AnimationMode::Once`] clip, ping pong
+    /// clips always play forever
+    pub fn from_tag(sheet: &SpriteSheet, tag: &FrameTag) -> Self {
+        let mut frames: Vec<_> = tag
+            .frames
+            .iter()
+            .map(|key| AnimationFrame {
+                key: key.clone(),
+                duration: sheet
+                    .frame(key)
+                    .and_then(|frame| frame.duration)
+                    .unwrap_or(DEFAULT_FRAME_DURATION),
+            })
+            .collect();
+
+        let (reverse, ping_pong) = match tag.direction {
+            TagDirection::Forward => (false, false),
+            TagDirection::Reverse => (true, false),
+            TagDirection::PingPong => (false, true),
+            TagDirection::PingPongReverse => (true, true),
+        };
+        if reverse {
+            frames.reverse();
+        }
+
+        let mode = match tag.repeat {
+            _ if ping_pong => AnimationMode::PingPong,
+            Some(repeat) => {
+                let count = frames.len() * repeat.max(1) as usize;
+                frames = frames.into_iter().cycle().take(count).collect();
+                AnimationMode::Once
+            }
+            None => AnimationMode::Loop,
+        };
+
+        Self { frames, mode }
+    }
+
+    /// clips from all frame tags of an imported sprite sheet
+    pub fn from_sprite_sheet(sheet: &SpriteSheet) -> AnimationClips<String> {
+        sheet
+            .tags
+            .iter()
+            .map(|tag| (tag.name.clone(), Self::from_tag(sheet, tag)))
+            .collect()
+    }
+}
+
+impl SpriteAnimations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// find the frames of all `clips` with `get`,
+    /// for example `|key| atlas.get(key)`
+    ///
+    /// the frames have to be on the first page of a
+    /// multi page atlas like all [`Sprite`]s, for example
+    /// `|key| atlas.get_paged(key).filter(|tex| tex.page == 0).map(|tex| tex.pos)`
+    ///
+    /// returns the key of the first missing frame
+    pub fn from_clips<K, F>(clips: &AnimationClips<K>, get: F) -> Result<Self, K>
+    where
+        K: Clone,
+        F: FnMut(&K) -> Option<TexturePosition>,
+    {
+        let mut animations = Self::new();
+        animations.insert_clips(clips, get)?;
+        Ok(animations)
+    }
+
+    /// add (or replace) all `clips`, see [`Self::from_clips`]
+    pub fn insert_clips<K, F>(&mut self, clips: &AnimationClips<K>, mut get: F) -> Result<(), K>
+    where
+        K: Clone,
+        F: FnMut(&K) -> Option<TexturePosition>,
+    {
+        for (name, clip) in clips {
+            let frames = clip
+                .frames
+                .iter()
+                .map(|frame| {
+                    Ok((
+                        get(&frame.key).ok_or_else(|| frame.key.clone())?,
+                        frame.duration,
+                    ))
+                })
+                .collect::<Result<_, K>>()?;
+            self.clips.insert(
+                name.clone(),
+                ResolvedClip {
+                    frames,
+                    mode: clip.mode,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// add (or replace) a clip
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        frames: Vec<(TexturePosition, f32)>,
+        mode: AnimationMode,
+    ) {
+        self.clips
+            .insert(name.into(), ResolvedClip { frames, mode });
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.clips.remove(name);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.clips.contains_key(name)
+    }
+}
+
+impl AnimatedSprite {
+    pub fn new(clip: impl Into<String>) -> Self {
+        Self {
+            clip: clip.into(),
+            speed: 1.0,
+            playing: true,
+            frame: 0,
+            elapsed: 0.0,
+            backwards: false,
+            finished: false,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// switch to another clip
+    ///
+    /// does nothing if the clip is already playing
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.clip = clip.to_string();
+            self.restart();
+        }
+    }
+
+    /// start the current clip from the first frame
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.backwards = false;
+        self.finished = false;
+        self.playing = true;
+    }
+
+    pub fn clip(&self) -> &str {
+        &self.clip
+    }
+
+    /// index of the current frame
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// true after the last frame of
+    /// an [`AnimationMode::Once`] clip
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// advance by `delta` seconds and
+    /// return the current frame
+    fn advance(&mut self, clip: &ResolvedClip, delta: f32) -> Option<TexturePosition> {
+        let count = clip.frames.len();
+        if count == 0 {
+            return None;
+        }
+        self.frame %= count;
+
+        let total: f32 = clip.frames.iter().map(|(_, duration)| duration).sum();
+        if self.playing && !self.finished && total > 0.0 {
+            self.elapsed += delta * self.speed;
+            while self.elapsed >= clip.frames[self.frame].1 {
+                self.elapsed -= clip.frames[self.frame].1;
+                self.step(clip.mode, count);
+                if self.finished {
+                    self.elapsed = 0.0;
+                    break;
+                }
+            }
+        }
+
+        Some(clip.frames[self.frame].0)
+    }
+
+    fn step(&mut self, mode: AnimationMode, count: usize) {
+        let last = count - 1;
+        match mode {
+            AnimationMode::Loop => self.frame = (self.frame + 1) % count,
+            AnimationMode::Once if self.frame == last => self.finished = true,
+            AnimationMode::Once => self.frame += 1,
+            AnimationMode::PingPong if last == 0 => {}
+            AnimationMode::PingPong => {
+                if self.frame == last {
+                    self.backwards = true;
+                } else if self.frame == 0 {
+                    self.backwards = false;
+                }
+
+                if self.backwards {
+                    self.frame -= 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+    }
+}
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, world: &mut World) {
+        if !world.resources.contains::<SpriteAnimations>() {
+            world.resources.insert(SpriteAnimations::new());
+        }
+        world.updates.insert_internal(150, animate_system);
+    }
+}
+
+#[system(for_each)]
+fn animate(
+    animated: &mut AnimatedSprite,
+    sprite: &mut Sprite,
+    #[resource] animations: &SpriteAnimations,
+    #[resource] time: &Time,
+) {
+    let Some(clip) = animations.clips.get(&animated.clip) else {
+        return;
+    };
+
+    if let Some(frame) = animated.advance(clip, time.delta_mult()) {
+        sprite.sprite = frame;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AnimatedSprite, AnimationMode, ResolvedClip};
+    use srs2dge_core::prelude::TexturePosition;
+
+    fn clip(mode: AnimationMode) -> ResolvedClip {
+        ResolvedClip {
+            frames: vec![(TexturePosition::default(), 0.1); 3],
+            mode,
+        }
+    }
+
+    fn frames(mode: AnimationMode, steps: usize) -> Vec<usize> {
+        let clip = clip(mode);
+        let mut animated = AnimatedSprite::new("");
+        (0..steps)
+            .map(|_| {
+                animated.advance(&clip, 0.1001);
+                animated.frame()
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_modes() {
+        assert_eq!(frames(AnimationMode::Loop, 5), [1, 2, 0, 1, 2]);
+        assert_eq!(frames(AnimationMode::PingPong, 6), [1, 2, 1, 0, 1, 2]);
+        assert_eq!(frames(AnimationMode::Once, 4), [1, 2, 2, 2]);
+
+        let mut animated = AnimatedSprite::new("").with_speed(2.0);
+        animated.advance(&clip(AnimationMode::Once), 1.0);
+        assert!(animated.is_finished());
+        assert_eq!(animated.frame(), 2);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{plugin::Plugin, prelude::Time, transform::Transform2D, World};
+use legion::system;
+use serde::{Deserialize, Serialize};
+use srs2dge_core::{camera::Camera2D, glam::Vec2, main_game_loop::state::window::WindowState};
+
+//
+
+/// Moves the [`Camera2D`] resource
+/// smoothly to this entity
+///
+/// Only one entity should have this.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CameraFollow {
+    /// world space offset from the entity
+    pub offset: Vec2,
+
+    /// seconds it takes for the camera to move
+    /// about 63% of the way to the entity,
+    /// `0.0` locks the camera to the entity
+    pub smoothing: f32,
+
+    #[serde(skip)]
+    position: Option<Vec2>,
+    #[serde(skip)]
+    velocity: Vec2,
+}
+
+/// Inserts the [`Camera2D`] resource and
+/// moves it to the [`CameraFollow`] entity
+///
+/// The camera is resized to the [`WindowState`]
+/// resource every frame, insert it like the
+/// other input states before `World::run`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CameraPlugin;
+
+//
+
+impl CameraFollow {
+    pub fn new(smoothing: f32) -> Self {
+        Self {
+            smoothing,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    fn step(&mut self, target: Vec2, delta: f32) {
+        let old = self.position.unwrap_or(target);
+        let new = if self.smoothing <= 0.0 {
+            target
+        } else {
+            old + (target - old) * (1.0 - (-delta / self.smoothing).exp())
+        };
+
+        self.velocity = if delta > 0.0 {
+            (new - old) / delta
+        } else {
+            Vec2::ZERO
+        };
+        self.position = Some(new);
+    }
+}
+
+impl Plugin for CameraPlugin {
+    fn build(&self, world: &mut World) {
+        if !world.resources.contains::<Camera2D>() {
+            world.resources.insert(Camera2D::default());
+        }
+        if !world.resources.contains::<WindowState>() {
+            world.resources.insert(WindowState::default());
+        }
+        world.updates.insert_internal(250, follow_system);
+        world.frames.insert_internal(202, set_camera_system);
+        world.frames.insert_internal(202, resize_camera_system);
+    }
+}
+
+#[system(for_each)]
+fn follow(follow: &mut CameraFollow, transform: &Transform2D, #[resource] time: &Time) {
+    follow.step(transform.translation + follow.offset, time.delta_mult());
+}
+
+// interpolated between updates like the sprites
+#[system(for_each)]
+fn set_camera(follow: &CameraFollow, #[resource] camera: &mut Camera2D, #[resource] time: &Time) {
+    if let Some(position) = follow.position {
+        camera.position = position + follow.velocity * time.delta_mult();
+    }
+}
+
+// minimized windows and the default
+// window state have no size
+#[system]
+fn resize_camera(#[resource] camera: &mut Camera2D, #[resource] ws: &WindowState) {
+    if ws.size.width != 0 && ws.size.height != 0 {
+        camera.resize(ws.size);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CameraFollow;
+    use srs2dge_core::glam::Vec2;
+
+    #[test]
+    pub fn test_follow() {
+        let target = Vec2::new(4.0, 0.0);
+
+        let mut locked = CameraFollow::new(0.0);
+        locked.step(Vec2::ZERO, 0.1);
+        locked.step(target, 0.1);
+        assert_eq!(locked.position, Some(target));
+        assert_eq!(locked.velocity, Vec2::new(40.0, 0.0));
+
+        let mut smooth = CameraFollow::new(0.5);
+        smooth.step(Vec2::ZERO, 0.1);
+        smooth.step(target, 0.5);
+        let position = smooth.position.unwrap();
+        assert!((position.x - 4.0 * (1.0 - (-1.0f32).exp())).abs() < 1e-5);
+    }
+}
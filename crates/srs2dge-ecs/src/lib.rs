@@ -9,7 +9,7 @@ use plugin::Plugin;
 use prelude::{systems::Systems, time::Time};
 use serde::{de::DeserializeSeed, Deserialize, Serialize};
 use srs2dge_core::{
-    batch::BatchRenderer,
+    batch::{instanced::InstancedBatch, quad::TransformedQuadMesh, BatchRenderer},
     main_game_loop::{
         report::Reporter,
         update::{UpdateLoop, UpdateRate},
@@ -27,6 +27,8 @@ pub use legion;
 
 //
 
+pub mod animation;
+pub mod follow;
 pub mod plugin;
 pub mod prelude;
 pub mod rigidbody;
@@ -74,14 +76,32 @@ impl World {
         plugin.build(self);
     }
 
-    pub fn get_batcher(&self) -> AtomicRef<BatchRenderer> {
+    /// the sprites of [`sprite::SpritePlugin`]
+    ///
+    /// the meshes are [`TransformedQuadMesh`]es
+    /// (not `QuadMesh`es) to support rotation,
+    /// `QuadMesh::into` converts the old ones
+    pub fn get_batcher(&self) -> AtomicRef<BatchRenderer<TransformedQuadMesh>> {
         self.resources.get().expect("FramePlugin is missing")
     }
 
-    pub fn get_batcher_mut(&self) -> AtomicRefMut<BatchRenderer> {
+    pub fn get_batcher_mut(&self) -> AtomicRefMut<BatchRenderer<TransformedQuadMesh>> {
         self.resources.get_mut().expect("FramePlugin is missing")
     }
 
+    /// the sprites of [`sprite::InstancedSpritePlugin`]
+    pub fn get_instanced_batch(&self) -> AtomicRef<InstancedBatch> {
+        self.resources
+            .get()
+            .expect("InstancedSpritePlugin is missing")
+    }
+
+    pub fn get_instanced_batch_mut(&self) -> AtomicRefMut<InstancedBatch> {
+        self.resources
+            .get_mut()
+            .expect("InstancedSpritePlugin is missing")
+    }
+
     /// returns a bool that is true if update systems ran
     pub fn run(&mut self) -> bool {
         let old_update_rate = self.update_rate;
@@ -1,6 +1,9 @@
-use crate::{prelude::RigidBody2DPlugin, sprite::SpritePlugin, World};
+use crate::{
+    animation::SpriteAnimationPlugin, follow::CameraPlugin, prelude::RigidBody2DPlugin,
+    sprite::SpritePlugin, World,
+};
 use srs2dge_core::{
-    batch::BatchRenderer, buffer::DefaultVertex, prelude::QuadMesh, target::Target,
+    batch::BatchRenderer, buffer::DefaultVertex, prelude::TransformedQuadMesh, target::Target,
 };
 use std::fmt::Debug;
 
@@ -19,6 +22,11 @@ pub struct DefaultClientPlugins<'a>(pub &'a Target);
 pub struct DefaultServerPlugins;
 
 /// Required for rendering
+///
+/// inserts a `BatchRenderer<TransformedQuadMesh>`
+/// resource, the batcher used to hold `QuadMesh`es, so
+/// systems that take `&mut BatchRenderer` as
+/// a resource need to name the mesh type now
 #[derive(Clone, Copy)]
 pub struct FramePlugin<'a>(pub &'a Target);
 
@@ -28,7 +36,9 @@ impl<'a> Plugin for DefaultClientPlugins<'a> {
     fn build(&self, world: &mut World) {
         world.add_plugin(FramePlugin(self.0));
         world.add_plugin(SpritePlugin);
+        world.add_plugin(SpriteAnimationPlugin);
         world.add_plugin(RigidBody2DPlugin);
+        world.add_plugin(CameraPlugin);
     }
 }
 
@@ -48,7 +58,9 @@ impl<'a> Plugin for FramePlugin<'a> {
     fn build(&self, world: &mut World) {
         world
             .resources
-            .insert(BatchRenderer::<QuadMesh, DefaultVertex>::new(self.0));
+            .insert(BatchRenderer::<TransformedQuadMesh, DefaultVertex>::new(
+                self.0,
+            ));
         world.frame_plugin = true;
     }
 }
@@ -1 +1 @@
-pub use crate::{plugin::*, rigidbody::*, sprite::*, transform::*, *};
+pub use crate::{animation::*, follow::*, plugin::*, rigidbody::*, sprite::*, transform::*, *};
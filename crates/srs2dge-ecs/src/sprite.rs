@@ -1,13 +1,20 @@
 use crate::{plugin::Plugin, prelude::Time, rigidbody::RigidBody2D, transform::Transform2D, World};
 use legion::{component, maybe_changed, system};
 use serde::{Deserialize, Serialize};
-use srs2dge_core::{
-    glam::{Vec2, Vec4},
-    prelude::{BatchRenderer, Color, Idx, QuadMesh, TexturePosition},
+use srs2dge_core::prelude::{
+    BatchRenderer, Color, Idx, InstancedBatch, SpriteInstance, Target, TexturePosition,
+    TransformedQuadMesh,
 };
+use std::fmt::Debug;
 
 //
 
+/// A textured quad
+///
+/// all sprites are drawn with one texture,
+/// so with a multi page texture atlas only
+/// the first page can be used, see
+/// `TextureAtlasMap::get_paged`
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Sprite {
     pub sprite: TexturePosition,
@@ -19,9 +26,19 @@ pub struct Sprite {
     pub lerp_transform: Transform2D,
 }
 
+/// Renders [`Sprite`]s as [`TransformedQuadMesh`]es
+/// in the [`BatchRenderer`] resource
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct SpritePlugin;
 
+/// Renders [`Sprite`]s as [`SpriteInstance`]s
+/// in the [`InstancedBatch`] resource,
+/// which this plugin inserts
+///
+/// use this instead of [`SpritePlugin`]
+#[derive(Clone, Copy)]
+pub struct InstancedSpritePlugin<'a>(pub &'a Target);
+
 //
 
 impl Plugin for SpritePlugin {
@@ -32,6 +49,25 @@ impl Plugin for SpritePlugin {
     }
 }
 
+impl<'a> Plugin for InstancedSpritePlugin<'a> {
+    fn build(&self, world: &mut World) {
+        world
+            .resources
+            .insert(InstancedBatch::<SpriteInstance>::new(self.0));
+        world.updates.insert_internal(200, set_pos_static_system);
+        world.frames.insert_internal(200, set_pos_body_system);
+        world
+            .frames
+            .insert_internal(201, set_sprite_instanced_system);
+    }
+}
+
+impl<'a> Debug for InstancedSpritePlugin<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("InstancedSpritePlugin").finish()
+    }
+}
+
 #[cfg_attr(
     any(target_arch = "wasm32", not(feature = "parallel")),
     legion::system(for_each)
@@ -70,43 +106,45 @@ fn set_pos_body(
 
 #[system(for_each)]
 #[filter(maybe_changed::<Sprite>())]
-fn set_sprite(sprite: &mut Sprite, #[resource] batcher: &mut BatchRenderer) {
+fn set_sprite(sprite: &mut Sprite, #[resource] batcher: &mut BatchRenderer<TransformedQuadMesh>) {
     let Transform2D {
-        translation, scale, ..
+        translation,
+        scale,
+        rotation,
     } = sprite.lerp_transform;
 
+    let new = TransformedQuadMesh::new_centered(translation, scale, sprite.color, sprite.sprite)
+        .with_rotation(rotation);
+
     // println!("set sprite");
     if let Some(idx) = sprite.idx {
-        let mesh = batcher.get(idx).unwrap();
-        if (mesh.pos - translation + scale * 0.5)
-            .abs()
-            .cmpgt(Vec2::splat(f32::EPSILON))
-            .any()
-            || (mesh.size - scale)
-                .abs()
-                .cmpgt(Vec2::splat(f32::EPSILON))
-                .any()
-            || (mesh.col.into_vec4() - sprite.color.into_vec4())
-                .abs()
-                .cmpgt(Vec4::splat(f32::EPSILON))
-                .any()
-            || (mesh.tex.to_vec4() - sprite.sprite.to_vec4())
-                .abs()
-                .cmpgt(Vec4::splat(f32::EPSILON))
-                .any()
-        {
-            let mesh = batcher.get_mut(idx).unwrap();
-            mesh.pos = translation - scale * 0.5;
-            mesh.size = scale;
-            mesh.col = sprite.color;
-            mesh.tex = sprite.sprite;
+        // only mark modified meshes for upload
+        if batcher.get(idx) != Some(&new) {
+            *batcher.get_mut(idx).unwrap() = new;
+        }
+    } else {
+        sprite.idx = Some(batcher.push_with(new));
+    }
+}
+
+#[system(for_each)]
+#[filter(maybe_changed::<Sprite>())]
+fn set_sprite_instanced(sprite: &mut Sprite, #[resource] batcher: &mut InstancedBatch) {
+    let Transform2D {
+        translation,
+        scale,
+        rotation,
+    } = sprite.lerp_transform;
+
+    let new = SpriteInstance::new(translation, scale, sprite.color, sprite.sprite)
+        .with_rotation(rotation);
+
+    if let Some(idx) = sprite.idx {
+        // only mark modified instances for upload
+        if batcher.get(idx) != Some(&new) {
+            *batcher.get_mut(idx).unwrap() = new;
         }
     } else {
-        sprite.idx = Some(batcher.push_with(QuadMesh::new_top_left(
-            translation - scale * 0.5,
-            scale,
-            sprite.color,
-            sprite.sprite,
-        )));
+        sprite.idx = Some(batcher.push_with(new));
     }
 }
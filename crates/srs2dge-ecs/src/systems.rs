@@ -61,14 +61,19 @@ impl Systems {
     /// Updates:
     ///  - ..100 : **FREE**
     ///  - 100 : `RigidBody2D`
-    ///  - 101..200 : **FREE**
+    ///  - 101..150 : **FREE**
+    ///  - 150 : `AnimatedSprite`
+    ///  - 151..200 : **FREE**
     ///  - 200 : `Sprite`
-    ///  - 201.. : **FREE**
+    ///  - 201..250 : **FREE**
+    ///  - 250 : `CameraFollow`
+    ///  - 251.. : **FREE**
     ///
     /// Frames:
     ///  - ..200 : **FREE**
     ///  - 200..202 : `Sprite`
-    ///  - 202.. : **FREE**
+    ///  - 202 : `CameraFollow`
+    ///  - 203.. : **FREE**
     pub fn insert_internal<R: ParallelRunnable + 'static, S: FnMut() -> R + 'static>(
         &mut self,
         index: u32,
@@ -110,6 +110,9 @@ impl Gizmos {
     /// Defaults to:
     /// `Mat4::orthographic_rh(-aspect, aspect, 1.0, -1.0, -100.0, 100.0)`
     /// if not set
+    ///
+    /// see `Camera2D::mvp`, the text is drawn
+    /// y down like with `Camera2D::with_y_down`
     pub fn set_mvp(&mut self, mvp: Mat4) {
         self.mat = Some(mvp);
     }
@@ -137,7 +140,19 @@ impl Gizmos {
 
     /// A slow way to convert screen space coordinates
     /// to world space coordinates
+    #[deprecated(note = "use `Camera2D::screen_to_world`")]
     pub fn screen_to_world<T>(mvp: Mat4, ws: &WindowState, pos: PhysicalPosition<T>) -> Option<Vec2>
+    where
+        T: Pixel,
+    {
+        Self::unproject(mvp, ws, pos)
+    }
+
+    pub(crate) fn unproject<T>(
+        mvp: Mat4,
+        ws: &WindowState,
+        pos: PhysicalPosition<T>,
+    ) -> Option<Vec2>
     where
         T: Pixel,
     {
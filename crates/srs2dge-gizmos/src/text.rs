@@ -30,11 +30,11 @@ impl<'s> GizmosText<'s> {
         let font = base.font.as_ref().ok_or("No font set")?;
 
         let mvp = base.mvp(self.ws);
-        let px = Gizmos::screen_to_world(mvp, self.ws, PhysicalPosition::new(28, 0))
+        let px = Gizmos::unproject(mvp, self.ws, PhysicalPosition::new(28, 0))
             .ok_or("Invalid projection")?
             .x;
         let px = px
-            - Gizmos::screen_to_world(mvp, self.ws, PhysicalPosition::new(0, 0))
+            - Gizmos::unproject(mvp, self.ws, PhysicalPosition::new(0, 0))
                 .ok_or("Invalid projection")?
                 .x;
         let sf = font.scale_factor(px);
@@ -1,7 +1,7 @@
 use srs2dge_core::{
     batch::mesh::Mesh,
     buffer::{DefaultIndex, DefaultVertex},
-    prelude::QuadMesh,
+    prelude::{NineSliceMesh, QuadMesh},
     wgpu::PrimitiveTopology,
 };
 
@@ -10,6 +10,7 @@ use srs2dge_core::{
 #[derive(Debug, Clone, Copy)]
 pub enum GuiGeom {
     Quad(QuadMesh),
+    NineSlice(NineSliceMesh),
 }
 
 //
@@ -17,45 +18,53 @@ pub enum GuiGeom {
 impl Mesh for GuiGeom {
     const PRIM: PrimitiveTopology = PrimitiveTopology::TriangleStrip;
 
-    type VertexIter = IterEnum<<QuadMesh as Mesh>::VertexIter, DefaultVertex>;
-    type IndexIter = IterEnum<<QuadMesh as Mesh>::IndexIter, DefaultIndex>;
+    type VertexIter = IterEnum<
+        <QuadMesh as Mesh>::VertexIter,
+        <NineSliceMesh as Mesh>::VertexIter,
+        DefaultVertex,
+    >;
+    type IndexIter =
+        IterEnum<<QuadMesh as Mesh>::IndexIter, <NineSliceMesh as Mesh>::IndexIter, DefaultIndex>;
 
     fn vertices(&self) -> Self::VertexIter {
         match self {
             GuiGeom::Quad(m) => IterEnum::A(m.vertices()),
+            GuiGeom::NineSlice(m) => IterEnum::B(m.vertices()),
         }
     }
 
     fn indices(&self, offset: u32) -> Self::IndexIter {
         match self {
             GuiGeom::Quad(m) => IterEnum::A(m.indices(offset)),
+            GuiGeom::NineSlice(m) => IterEnum::B(m.indices(offset)),
         }
     }
 
     fn index_step(&self) -> u32 {
         match self {
             GuiGeom::Quad(m) => m.index_step(),
+            GuiGeom::NineSlice(m) => m.index_step(),
         }
     }
 }
 
 //
 
-pub enum IterEnum<A, /* B, C, */ I>
+pub enum IterEnum<A, B, /* C, */ I>
 where
     A: Iterator<Item = I>,
-    // B: Iterator<Item = I>,
+    B: Iterator<Item = I>,
     // C: Iterator<Item = I>,
 {
     A(A),
-    // B(B),
+    B(B),
     // C(C),
 }
 
-impl<A, /* B, C, */ I> Iterator for IterEnum<A, /* B, C, */ I>
+impl<A, B, /* C, */ I> Iterator for IterEnum<A, B, /* C, */ I>
 where
     A: Iterator<Item = I>,
-    // B: Iterator<Item = I>,
+    B: Iterator<Item = I>,
     // C: Iterator<Item = I>,
 {
     type Item = I;
@@ -63,7 +72,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             IterEnum::A(a) => a.next(),
-            // IterEnum::B(b) => b.next(),
+            IterEnum::B(b) => b.next(),
             // IterEnum::C(c) => c.next(),
         }
     }
@@ -6,7 +6,7 @@ use srs2dge_core::{
     prelude::{Frame, Rect},
     shader::Layout,
     target::Target,
-    texture::Texture,
+    texture::{sampler::Sampler, Texture},
     wgpu::TextureView,
 };
 use srs2dge_presets::{TextShader, Texture2DShader};
@@ -19,6 +19,7 @@ pub struct GuiGraphics {
     pub(crate) ubo: UniformBuffer,
     pub(crate) texture_shader: Texture2DShader,
     pub(crate) text_shader: TextShader,
+    pub(crate) sampler: Sampler,
     pub texture_batcher: GuiRenderer,
     pub text_batcher: GuiRenderer,
 
@@ -34,6 +35,7 @@ impl GuiGraphics {
             ubo: UniformBuffer::new(target, 1),
             texture_shader: Texture2DShader::new(target),
             text_shader: TextShader::new(target),
+            sampler: Sampler::nearest(target),
             texture_batcher: GuiRenderer::default(),
             text_batcher: GuiRenderer::default(),
 
@@ -69,7 +71,7 @@ impl GuiGraphics {
             (&self.texture_shader, &self.text_shader),
             target,
             frame,
-            (texture, &self.glyphs),
+            (texture, &self.sampler, &self.glyphs),
         )
     }
 
@@ -119,7 +121,7 @@ impl GuiGraphics {
         (texture_shader, text_shader): (&'a Texture2DShader, &'a TextShader),
         target: &mut Target,
         frame: &mut Frame,
-        (texture, glyphs): (&TextureView, &TextureView),
+        (texture, sampler, glyphs): (&TextureView, &Sampler, &TextureView),
     ) -> GeneratedGui<'a> {
         let (texture_vbo, texture_ibo, texture_indices) = texture_batcher.generate(target, frame);
         let (text_vbo, text_ibo, text_indices) = text_batcher.generate(target, frame);
@@ -129,7 +131,7 @@ impl GuiGraphics {
             texture_vbo,
             texture_ibo,
             texture_indices,
-            texture_bindings: texture_shader.bind_group((ubo, texture)),
+            texture_bindings: texture_shader.bind_group((ubo, texture, sampler)),
 
             text_shader,
             text_vbo,
@@ -15,6 +15,7 @@ impl MergeStyles for Style<Mergeable> {
         Self {
             color: other.color.merge(self.color),
             texture: other.texture.merge(self.texture),
+            nine_slice: other.nine_slice.merge(self.nine_slice),
             size: other.size.merge(self.size),
             offset: other.offset.merge(self.offset),
             text_align: other.text_align.merge(self.text_align),
@@ -27,6 +28,7 @@ impl<'a> MergeStyles for Style<Ref<'a>> {
         Self {
             color: other.color.merge(self.color),
             texture: other.texture.merge(self.texture),
+            nine_slice: other.nine_slice.merge(self.nine_slice),
             size: other.size.merge(self.size),
             offset: other.offset.merge(self.offset),
             text_align: other.text_align.merge(self.text_align),
@@ -11,7 +11,7 @@ use self::{
     layout::{Offset, Size},
     merge::MergeStyles,
 };
-use srs2dge_core::prelude::{Color, TexturePosition};
+use srs2dge_core::prelude::{Color, NineSlice, TexturePosition};
 use srs2dge_text::prelude::TextAlign;
 
 //
@@ -51,6 +51,7 @@ pub struct Style<T = Mergeable>
 where
     T: StyleField<Color>,
     T: StyleField<TexturePosition>,
+    T: StyleField<NineSlice>,
     T: StyleField<Size>,
     T: StyleField<Offset>,
     T: StyleField<TextAlign>,
@@ -66,6 +67,11 @@ where
     ///
     /// TODO: Texture manager
     pub texture: <T as StyleField<TexturePosition>>::Type,
+    /// Texture borders that are not stretched
+    ///
+    /// Used by fill, the texture is drawn
+    /// as a plain quad if there are no borders
+    pub nine_slice: <T as StyleField<NineSlice>>::Type,
 
     // layout styling
     /// Size calculator of the widget
@@ -93,6 +99,7 @@ impl Style {
         Style {
             color: self.color.as_ref(),
             texture: self.texture.as_ref(),
+            nine_slice: self.nine_slice.as_ref(),
             size: self.size.as_ref(),
             offset: self.offset.as_ref(),
             text_align: self.text_align.as_ref(),
@@ -103,6 +110,7 @@ impl Style {
         Style {
             color: self.color.unwrap_or_default(),
             texture: self.texture.unwrap_or_default(),
+            nine_slice: self.nine_slice.unwrap_or_default(),
             size: self.size.unwrap_or_default(),
             offset: self.offset.unwrap_or_default(),
             text_align: self.text_align.unwrap_or_default(),
@@ -134,6 +142,7 @@ impl<'a> Style<Ref<'a>> {
         Style {
             color: self.color.copied().unwrap_or_default(),
             texture: self.texture.copied().unwrap_or_default(),
+            nine_slice: self.nine_slice.copied().unwrap_or_default(),
             size: self.size.cloned().unwrap_or_default(),
             offset: self.offset.cloned().unwrap_or_default(),
             text_align: self.text_align.copied().unwrap_or_default(),
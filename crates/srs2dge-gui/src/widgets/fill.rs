@@ -2,7 +2,7 @@ use crate::{
     gui::geom::GuiGeom,
     prelude::{GuiDraw, Ref, Style, StyleSheet, Widget, WidgetBuilder, WidgetCore},
 };
-use srs2dge_core::prelude::QuadMesh;
+use srs2dge_core::prelude::{NineSliceMesh, QuadMesh};
 use std::any::{type_name, Any};
 
 //
@@ -31,14 +31,26 @@ impl Widget for Fill {
     fn draw(&mut self, draw: &mut GuiDraw) {
         // tracing::debug!("fill {layout:?}");
 
-        draw.graphics
-            .texture_batcher
-            .push_with(GuiGeom::Quad(QuadMesh::new_top_left(
-                self.core.layout.offset,
-                self.core.layout.size,
-                self.core.style.color,
-                self.core.style.texture,
-            )));
+        let style = &self.core.style;
+        let layout = &self.core.layout;
+        let geom = if style.nine_slice.is_empty() {
+            GuiGeom::Quad(QuadMesh::new_top_left(
+                layout.offset,
+                layout.size,
+                style.color,
+                style.texture,
+            ))
+        } else {
+            GuiGeom::NineSlice(NineSliceMesh::new_top_left(
+                layout.offset,
+                layout.size,
+                style.color,
+                style.texture,
+                style.nine_slice,
+            ))
+        };
+
+        draw.graphics.texture_batcher.push_with(geom);
     }
 
     fn name(&self) -> &'static str {
@@ -1,3 +1,4 @@
+use crate::PresetOptions;
 use srs2dge_core::{
     buffer::{DefaultIndex, DefaultVertex, Index, UniformBuffer},
     glam::Mat4,
@@ -37,8 +38,12 @@ where
     I: Index,
 {
     pub fn new(target: &Target) -> Self {
+        Self::new_with(target, PresetOptions::primary(target))
+    }
+
+    pub fn new_with(target: &Target, options: PresetOptions) -> Self {
         let module = Self::built_in(target);
-        Self::new_custom(target, &module, "vs_main", &module, "fs_main")
+        Self::new_custom_with(target, options, &module, "vs_main", &module, "fs_main")
     }
 
     pub fn new_custom_vert(
@@ -72,6 +77,24 @@ where
         vert_entry: &str,
         frag_module: &ShaderModule,
         frag_entry: &str,
+    ) -> Self {
+        Self::new_custom_with(
+            target,
+            PresetOptions::primary(target),
+            vert_module,
+            vert_entry,
+            frag_module,
+            frag_entry,
+        )
+    }
+
+    pub fn new_custom_with(
+        target: &Target,
+        options: PresetOptions,
+        vert_module: &ShaderModule,
+        vert_entry: &str,
+        frag_module: &ShaderModule,
+        frag_entry: &str,
     ) -> Self {
         let layout = Self::bind_group_layout(&target.get_device());
 
@@ -79,7 +102,9 @@ where
             inner: Shader::builder()
                 .with_vertex(vert_module, vert_entry)
                 .with_fragment(frag_module, frag_entry)
-                .with_format(target.get_format())
+                .with_format(options.format)
+                .with_multisample(options.sample_count)
+                .with_blend(options.blend)
                 .with_baked_layout(PipelineLayoutDescriptor {
                     label: label!(),
                     bind_group_layouts: &[&layout],
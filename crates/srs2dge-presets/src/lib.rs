@@ -1,13 +1,19 @@
 pub use colored_2d::*;
 pub use line::*;
+pub use options::*;
 pub use sdf::*;
+pub use sprite_instanced::*;
 pub use text::*;
 pub use texture_2d::*;
+pub use texture_2d_array::*;
 
 //
 
 pub mod colored_2d;
 pub mod line;
+pub mod options;
 pub mod sdf;
+pub mod sprite_instanced;
 pub mod text;
 pub mod texture_2d;
+pub mod texture_2d_array;
@@ -1,3 +1,4 @@
+use crate::PresetOptions;
 use srs2dge_core::{
     buffer::{DefaultIndex, DefaultVertex, Index, UniformBuffer},
     glam::Mat4,
@@ -37,6 +38,10 @@ where
     I: Index,
 {
     pub fn new(target: &Target, strip: bool) -> Self {
+        Self::new_with(target, PresetOptions::primary(target), strip)
+    }
+
+    pub fn new_with(target: &Target, options: PresetOptions, strip: bool) -> Self {
         let module =
             ShaderModule::new_wgsl_source(target, Cow::Borrowed(srs2dge_res::shader::COLORED_2D))
                 .unwrap_or_else(|err| panic!("{err}"));
@@ -47,7 +52,9 @@ where
             inner: Shader::builder()
                 .with_vertex(&module, "vs_main")
                 .with_fragment(&module, "fs_main")
-                .with_format(target.get_format())
+                .with_format(options.format)
+                .with_multisample(options.sample_count)
+                .with_blend(options.blend)
                 .with_baked_layout(PipelineLayoutDescriptor {
                     label: label!(),
                     bind_group_layouts: &[&layout],
@@ -0,0 +1,77 @@
+use srs2dge_core::{
+    shader::blend::BlendMode, target::Target, texture::Texture, wgpu::TextureFormat,
+};
+
+//
+
+/// Render pass state that the preset
+/// shaders are built for
+///
+/// a shader has to match the render pass
+/// it is bound to, see
+/// [`srs2dge_core::frame::render_pass::RenderPass::bind_shader`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresetOptions {
+    pub format: TextureFormat,
+    pub sample_count: u32,
+    pub blend: BlendMode,
+
+    /// are the sampled textures filterable and
+    /// the samplers filtering, if not only
+    /// non-filtering samplers can be bound,
+    /// see [`srs2dge_core::texture::sampler::SamplerBuilder::is_filtering`]
+    pub filtering: bool,
+}
+
+//
+
+impl PresetOptions {
+    /// for primary render passes of `target`
+    pub fn primary(target: &Target) -> Self {
+        Self {
+            format: target.get_format(),
+            sample_count: target.get_sample_count(),
+            blend: BlendMode::default(),
+            filtering: true,
+        }
+    }
+
+    /// for secondary render passes
+    /// that draw into `texture`
+    pub fn secondary<const USAGE: u32>(texture: &Texture<USAGE>) -> Self {
+        Self {
+            format: texture.get_format(),
+            sample_count: texture.get_sample_count(),
+            blend: BlendMode::default(),
+            filtering: true,
+        }
+    }
+
+    pub fn with_format(mut self, format: TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: impl Into<BlendMode>) -> Self {
+        self.blend = blend.into();
+        self
+    }
+
+    pub fn with_filtering(mut self, filtering: bool) -> Self {
+        self.filtering = filtering;
+        self
+    }
+
+    /// filtering only if textures of `format`
+    /// are filterable on the adapter of `target`,
+    /// `Rgba32Float` and `R32Float` usually aren't
+    pub fn with_sampled_format(mut self, target: &Target, format: TextureFormat) -> Self {
+        self.filtering = target.is_filterable(format);
+        self
+    }
+}
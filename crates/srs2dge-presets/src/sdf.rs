@@ -1,3 +1,4 @@
+use crate::PresetOptions;
 use bytemuck::{Pod, Zeroable};
 use srs2dge_core::{
     buffer::{DefaultIndex, DefaultVertex, Index, UniformBuffer},
@@ -5,11 +6,11 @@ use srs2dge_core::{
     label,
     shader::{module::ShaderModule, Layout, Shader},
     target::Target,
+    texture::sampler::Sampler,
     wgpu::{
-        AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
-        BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
-        BufferBindingType, Device, FilterMode, PipelineLayoutDescriptor, Sampler,
-        SamplerBindingType, SamplerDescriptor, ShaderStages, TextureSampleType, TextureView,
+        BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+        BindGroupLayoutEntry, BindingResource, BindingType, BufferBindingType, Device,
+        PipelineLayoutDescriptor, SamplerBindingType, ShaderStages, TextureSampleType, TextureView,
         TextureViewDimension,
     },
 };
@@ -81,7 +82,6 @@ where
 {
     inner: Internal<I>,
     layout: BindGroupLayout,
-    sampler: Sampler,
 
     device: Arc<Device>,
 }
@@ -91,31 +91,22 @@ where
     I: Index,
 {
     pub fn new(target: &Target) -> Self {
+        Self::new_with(target, PresetOptions::primary(target))
+    }
+
+    pub fn new_with(target: &Target, options: PresetOptions) -> Self {
         let module = ShaderModule::new_wgsl_source(target, Cow::Borrowed(srs2dge_res::shader::SDF))
             .unwrap_or_else(|err| panic!("{err}"));
 
         let layout = Self::bind_group_layout(&target.get_device());
 
-        let sampler = target.get_device().create_sampler(&SamplerDescriptor {
-            label: label!(),
-            address_mode_u: AddressMode::ClampToEdge,
-            address_mode_v: AddressMode::ClampToEdge,
-            address_mode_w: AddressMode::ClampToEdge,
-            mag_filter: FilterMode::Linear, // important!
-            min_filter: FilterMode::Linear,
-            mipmap_filter: FilterMode::Linear,
-            lod_min_clamp: 0.0,
-            lod_max_clamp: f32::MAX,
-            compare: None,
-            anisotropy_clamp: None,
-            border_color: None,
-        });
-
         Self {
             inner: Shader::builder()
                 .with_vertex(&module, "vs_main")
                 .with_fragment(&module, "fs_main")
-                .with_format(target.get_format())
+                .with_format(options.format)
+                .with_multisample(options.sample_count)
+                .with_blend(options.blend)
                 .with_baked_layout(PipelineLayoutDescriptor {
                     label: label!(),
                     bind_group_layouts: &[&layout],
@@ -124,7 +115,6 @@ where
                 .with_label(label!())
                 .build(target),
             layout,
-            sampler,
 
             device: target.get_device(),
         }
@@ -135,7 +125,9 @@ impl<'a, I> Layout<'a> for SdfShader<I>
 where
     I: Index,
 {
-    type Bindings = (&'a UniformBuffer<SdfUniform>, &'a TextureView);
+    /// the sampler has to be filtering,
+    /// for example [`Sampler::linear`]
+    type Bindings = (&'a UniformBuffer<SdfUniform>, &'a TextureView, &'a Sampler);
 
     fn bind_group_layout(device: &Device) -> BindGroupLayout {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
@@ -171,7 +163,12 @@ where
         })
     }
 
-    fn bind_group(&self, (uniform, texture): Self::Bindings) -> BindGroup {
+    fn bind_group(&self, (uniform, texture, sampler): Self::Bindings) -> BindGroup {
+        assert!(
+            sampler.get_desc().is_filtering(),
+            "SdfShader requires a filtering sampler"
+        );
+
         self.device.create_bind_group(&BindGroupDescriptor {
             label: label!(),
             layout: &self.layout,
@@ -186,7 +183,7 @@ where
                 },
                 BindGroupEntry {
                     binding: 2,
-                    resource: BindingResource::Sampler(&self.sampler),
+                    resource: BindingResource::Sampler(sampler),
                 },
             ],
         })
@@ -0,0 +1,197 @@
+use crate::PresetOptions;
+use srs2dge_core::{
+    batch::instanced::SpriteInstance,
+    buffer::UniformBuffer,
+    glam::Mat4,
+    label,
+    shader::{module::ShaderModule, Layout, Shader},
+    target::Target,
+    texture::sampler::Sampler,
+    wgpu::{
+        BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+        BindGroupLayoutEntry, BindingResource, BindingType, BufferBindingType, Device,
+        PipelineLayoutDescriptor, SamplerBindingType, ShaderStages, TextureSampleType, TextureView,
+        TextureViewDimension,
+    },
+};
+use std::{
+    borrow::Cow,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+//
+
+type Internal = Shader<SpriteInstance, ()>;
+
+//
+
+/// Draws [`srs2dge_core::batch::InstancedBatch`]es
+///
+/// Same bindings as [`crate::Texture2DShader`]
+#[derive(Debug)]
+pub struct InstancedSpriteShader {
+    inner: Internal,
+    layout: BindGroupLayout,
+
+    device: Arc<Device>,
+}
+
+impl InstancedSpriteShader {
+    pub fn new(target: &Target) -> Self {
+        Self::new_with(target, PresetOptions::primary(target))
+    }
+
+    pub fn new_with(target: &Target, options: PresetOptions) -> Self {
+        let module = Self::built_in(target);
+        Self::new_custom_with(target, options, &module, "vs_main", &module, "fs_main")
+    }
+
+    pub fn new_custom_frag(
+        target: &Target,
+        module: &ShaderModule,
+        entry: &str,
+    ) -> Result<Self, String> {
+        target.catch_error(|target| {
+            Self::new_custom(target, &Self::built_in(target), "vs_main", module, entry)
+        })
+    }
+
+    pub fn built_in(target: &Target) -> ShaderModule {
+        ShaderModule::new_wgsl_source(target, Cow::Borrowed(srs2dge_res::shader::SPRITE_INSTANCED))
+            .unwrap_or_else(|err| panic!("Built in shader compilation failed: {err}"))
+    }
+
+    pub fn new_custom(
+        target: &Target,
+        vert_module: &ShaderModule,
+        vert_entry: &str,
+        frag_module: &ShaderModule,
+        frag_entry: &str,
+    ) -> Self {
+        Self::new_custom_with(
+            target,
+            PresetOptions::primary(target),
+            vert_module,
+            vert_entry,
+            frag_module,
+            frag_entry,
+        )
+    }
+
+    pub fn new_custom_with(
+        target: &Target,
+        options: PresetOptions,
+        vert_module: &ShaderModule,
+        vert_entry: &str,
+        frag_module: &ShaderModule,
+        frag_entry: &str,
+    ) -> Self {
+        let layout = Self::bind_group_layout_with(&target.get_device(), options.filtering);
+
+        Self {
+            inner: Shader::builder()
+                .with_vertex(vert_module, vert_entry)
+                .with_fragment(frag_module, frag_entry)
+                .with_format(options.format)
+                .with_multisample(options.sample_count)
+                .with_blend(options.blend)
+                .with_baked_layout(PipelineLayoutDescriptor {
+                    label: label!(),
+                    bind_group_layouts: &[&layout],
+                    push_constant_ranges: &[],
+                })
+                .with_label(label!())
+                .build(target),
+            layout,
+
+            device: target.get_device(),
+        }
+    }
+
+    /// the layout for filterable or
+    /// unfilterable textures and samplers,
+    /// see [`PresetOptions::filtering`]
+    pub fn bind_group_layout_with(device: &Device, filtering: bool) -> BindGroupLayout {
+        device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: label!(),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float {
+                            filterable: filtering,
+                        },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(if filtering {
+                        SamplerBindingType::Filtering
+                    } else {
+                        SamplerBindingType::NonFiltering
+                    }),
+                    count: None,
+                },
+            ],
+        })
+    }
+}
+
+impl<'a> Layout<'a> for InstancedSpriteShader {
+    type Bindings = (&'a UniformBuffer<Mat4>, &'a TextureView, &'a Sampler);
+
+    fn bind_group_layout(device: &Device) -> BindGroupLayout {
+        Self::bind_group_layout_with(device, true)
+    }
+
+    fn bind_group(&self, (uniform, texture, sampler): Self::Bindings) -> BindGroup {
+        self.device.create_bind_group(&BindGroupDescriptor {
+            label: label!(),
+            layout: &self.layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: uniform.inner().as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(texture),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+}
+
+impl Deref for InstancedSpriteShader {
+    type Target = Internal;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for InstancedSpriteShader {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
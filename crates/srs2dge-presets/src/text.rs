@@ -1,8 +1,11 @@
-use crate::Texture2DShader;
+use crate::{PresetOptions, Texture2DShader};
 use srs2dge_core::{
-    buffer::{DefaultIndex, Index},
-    shader::module::ShaderModule,
+    buffer::{DefaultIndex, Index, UniformBuffer},
+    glam::Mat4,
+    shader::{module::ShaderModule, Layout},
     target::Target,
+    texture::sampler::Sampler,
+    wgpu::{BindGroup, BindGroupLayout, Device, TextureView},
 };
 use std::{
     borrow::Cow,
@@ -11,7 +14,7 @@ use std::{
 
 //
 
-type Internal<I> = Texture2DShader<true, I>;
+type Internal<I> = Texture2DShader<I>;
 
 //
 
@@ -21,6 +24,7 @@ where
     I: Index,
 {
     inner: Internal<I>,
+    sampler: Sampler,
 }
 
 impl<I> TextShader<I>
@@ -28,9 +32,21 @@ where
     I: Index,
 {
     pub fn new(target: &Target) -> Self {
+        Self::new_with(target, PresetOptions::primary(target))
+    }
+
+    pub fn new_with(target: &Target, options: PresetOptions) -> Self {
         let module = Self::built_in(target);
         Self {
-            inner: Internal::new_custom(target, &module, "vs_main", &module, "fs_main"),
+            inner: Internal::new_custom_with(
+                target, options, &module, "vs_main", &module, "fs_main",
+            ),
+            // unfilterable layouts only take nearest samplers
+            sampler: if options.filtering {
+                Sampler::linear(target)
+            } else {
+                Sampler::nearest(target)
+            },
         }
     }
 
@@ -41,6 +57,7 @@ where
     ) -> Result<Self, String> {
         target.catch_error(|target| Self {
             inner: Internal::new_custom(target, module, entry, &Self::built_in(target), "fs_main"),
+            sampler: Sampler::linear(target),
         })
     }
 
@@ -51,6 +68,7 @@ where
     ) -> Result<Self, String> {
         target.catch_error(|target| Self {
             inner: Internal::new_custom(target, &Self::built_in(target), "vs_main", module, entry),
+            sampler: Sampler::linear(target),
         })
     }
 
@@ -60,6 +78,25 @@ where
     }
 }
 
+/// Same bindings as [`Texture2DShader`],
+/// but samples with linear filtering (or
+/// nearest if [`PresetOptions::filtering`]
+/// is off)
+impl<'a, I> Layout<'a> for TextShader<I>
+where
+    I: Index,
+{
+    type Bindings = (&'a UniformBuffer<Mat4>, &'a TextureView);
+
+    fn bind_group_layout(device: &Device) -> BindGroupLayout {
+        Internal::<I>::bind_group_layout(device)
+    }
+
+    fn bind_group(&self, (uniform, texture): Self::Bindings) -> BindGroup {
+        self.inner.bind_group((uniform, texture, &self.sampler))
+    }
+}
+
 impl<I> Deref for TextShader<I>
 where
     I: Index,
@@ -79,3 +116,46 @@ where
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::TextShader;
+    use crate::PresetOptions;
+    use srs2dge_core::{
+        buffer::UniformBuffer,
+        glam::Mat4,
+        packer::rect::Rect,
+        prelude::Engine,
+        shader::Layout,
+        texture::Texture,
+        wgpu::{RequestAdapterOptions, TextureFormat},
+    };
+
+    #[test]
+    pub fn test_unfilterable() {
+        srs2dge_core::init_tokio().block_on(async {
+            let engine = Engine::new();
+            let adapter = engine
+                .get_instance()
+                .request_adapter(&RequestAdapterOptions::default())
+                .await;
+            if adapter.is_none() {
+                tracing::warn!("No GPU, skipping the unfilterable text shader test");
+                return;
+            }
+
+            let target = engine.new_target_headless().await;
+            let shader: TextShader = TextShader::new_with(
+                &target,
+                PresetOptions::primary(&target).with_filtering(false),
+            );
+            let ubo = UniformBuffer::new_single(&target, Mat4::IDENTITY);
+            let texture: Texture =
+                Texture::new_format(&target, Rect::new(4, 4), TextureFormat::R32Float, None);
+
+            target
+                .catch_error(|_| shader.bind_group((&ubo, &texture)))
+                .unwrap();
+        });
+    }
+}
@@ -1,14 +1,15 @@
+use crate::PresetOptions;
 use srs2dge_core::{
     buffer::{DefaultIndex, DefaultVertex, Index, UniformBuffer},
     glam::Mat4,
     label,
     shader::{module::ShaderModule, Layout, Shader},
     target::Target,
+    texture::sampler::Sampler,
     wgpu::{
-        AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
-        BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
-        BufferBindingType, Device, FilterMode, PipelineLayoutDescriptor, Sampler,
-        SamplerBindingType, SamplerDescriptor, ShaderStages, TextureSampleType, TextureView,
+        BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+        BindGroupLayoutEntry, BindingResource, BindingType, BufferBindingType, Device,
+        PipelineLayoutDescriptor, SamplerBindingType, ShaderStages, TextureSampleType, TextureView,
         TextureViewDimension,
     },
 };
@@ -25,24 +26,27 @@ type Internal<I> = Shader<DefaultVertex, I>;
 //
 
 #[derive(Debug)]
-pub struct Texture2DShader<const FILTER: bool = false, I = DefaultIndex>
+pub struct Texture2DShader<I = DefaultIndex>
 where
     I: Index,
 {
     inner: Internal<I>,
     layout: BindGroupLayout,
-    sampler: Sampler,
 
     device: Arc<Device>,
 }
 
-impl<const FILTER: bool, I> Texture2DShader<FILTER, I>
+impl<I> Texture2DShader<I>
 where
     I: Index,
 {
     pub fn new(target: &Target) -> Self {
+        Self::new_with(target, PresetOptions::primary(target))
+    }
+
+    pub fn new_with(target: &Target, options: PresetOptions) -> Self {
         let module = Self::built_in(target);
-        Self::new_custom(target, &module, "vs_main", &module, "fs_main")
+        Self::new_custom_with(target, options, &module, "vs_main", &module, "fs_main")
     }
 
     pub fn new_custom_vert(
@@ -77,33 +81,33 @@ where
         frag_module: &ShaderModule,
         frag_entry: &str,
     ) -> Self {
-        let layout = Self::bind_group_layout(&target.get_device());
-
-        let filter = if FILTER {
-            FilterMode::Linear
-        } else {
-            FilterMode::Nearest
-        };
-        let sampler = target.get_device().create_sampler(&SamplerDescriptor {
-            label: label!(),
-            address_mode_u: AddressMode::ClampToEdge,
-            address_mode_v: AddressMode::ClampToEdge,
-            address_mode_w: AddressMode::ClampToEdge,
-            mag_filter: filter,
-            min_filter: filter,
-            mipmap_filter: filter,
-            lod_min_clamp: 0.0,
-            lod_max_clamp: f32::MAX,
-            compare: None,
-            anisotropy_clamp: None,
-            border_color: None,
-        });
+        Self::new_custom_with(
+            target,
+            PresetOptions::primary(target),
+            vert_module,
+            vert_entry,
+            frag_module,
+            frag_entry,
+        )
+    }
+
+    pub fn new_custom_with(
+        target: &Target,
+        options: PresetOptions,
+        vert_module: &ShaderModule,
+        vert_entry: &str,
+        frag_module: &ShaderModule,
+        frag_entry: &str,
+    ) -> Self {
+        let layout = Self::bind_group_layout_with(&target.get_device(), options.filtering);
 
         Self {
             inner: Shader::builder()
                 .with_vertex(vert_module, vert_entry)
                 .with_fragment(frag_module, frag_entry)
-                .with_format(target.get_format())
+                .with_format(options.format)
+                .with_multisample(options.sample_count)
+                .with_blend(options.blend)
                 .with_baked_layout(PipelineLayoutDescriptor {
                     label: label!(),
                     bind_group_layouts: &[&layout],
@@ -112,20 +116,15 @@ where
                 .with_label(label!())
                 .build(target),
             layout,
-            sampler,
 
             device: target.get_device(),
         }
     }
-}
 
-impl<'a, const FILTER: bool, I> Layout<'a> for Texture2DShader<FILTER, I>
-where
-    I: Index,
-{
-    type Bindings = (&'a UniformBuffer<Mat4>, &'a TextureView);
-
-    fn bind_group_layout(device: &Device) -> BindGroupLayout {
+    /// the layout for filterable or
+    /// unfilterable textures and samplers,
+    /// see [`PresetOptions::filtering`]
+    pub fn bind_group_layout_with(device: &Device, filtering: bool) -> BindGroupLayout {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: label!(),
             entries: &[
@@ -143,7 +142,9 @@ where
                     binding: 1,
                     visibility: ShaderStages::FRAGMENT,
                     ty: BindingType::Texture {
-                        sample_type: TextureSampleType::Float { filterable: FILTER },
+                        sample_type: TextureSampleType::Float {
+                            filterable: filtering,
+                        },
                         view_dimension: TextureViewDimension::D2,
                         multisampled: false,
                     },
@@ -152,7 +153,7 @@ where
                 BindGroupLayoutEntry {
                     binding: 2,
                     visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(if FILTER {
+                    ty: BindingType::Sampler(if filtering {
                         SamplerBindingType::Filtering
                     } else {
                         SamplerBindingType::NonFiltering
@@ -162,8 +163,19 @@ where
             ],
         })
     }
+}
+
+impl<'a, I> Layout<'a> for Texture2DShader<I>
+where
+    I: Index,
+{
+    type Bindings = (&'a UniformBuffer<Mat4>, &'a TextureView, &'a Sampler);
+
+    fn bind_group_layout(device: &Device) -> BindGroupLayout {
+        Self::bind_group_layout_with(device, true)
+    }
 
-    fn bind_group(&self, (uniform, texture): Self::Bindings) -> BindGroup {
+    fn bind_group(&self, (uniform, texture, sampler): Self::Bindings) -> BindGroup {
         self.device.create_bind_group(&BindGroupDescriptor {
             label: label!(),
             layout: &self.layout,
@@ -178,14 +190,14 @@ where
                 },
                 BindGroupEntry {
                     binding: 2,
-                    resource: BindingResource::Sampler(&self.sampler),
+                    resource: BindingResource::Sampler(sampler),
                 },
             ],
         })
     }
 }
 
-impl<const FILTER: bool, I> Deref for Texture2DShader<FILTER, I>
+impl<I> Deref for Texture2DShader<I>
 where
     I: Index,
 {
@@ -196,7 +208,7 @@ where
     }
 }
 
-impl<const FILTER: bool, I> DerefMut for Texture2DShader<FILTER, I>
+impl<I> DerefMut for Texture2DShader<I>
 where
     I: Index,
 {
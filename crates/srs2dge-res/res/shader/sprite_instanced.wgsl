@@ -0,0 +1,56 @@
+struct VertexInput {
+	@location(0) corner: vec2<f32>,
+};
+
+struct InstanceInput {
+	@location(1) pos: vec2<f32>,
+	@location(2) size: vec2<f32>,
+	@location(3) col: vec4<f32>,
+	// top_left.xy, bottom_right.xy
+	@location(4) tex: vec4<f32>,
+	@location(5) rotation: f32,
+};
+
+struct FragmentInput {
+	@builtin(position) pos: vec4<f32>,
+	@location(0) col: vec4<f32>,
+	@location(1) uv: vec2<f32>,
+};
+
+struct UniformInput {
+	mvp: mat4x4<f32>,
+};
+
+@group(0)
+@binding(0)
+var<uniform> ubo: UniformInput;
+
+@group(0)
+@binding(1)
+var t_texture: texture_2d<f32>;
+
+@group(0)
+@binding(2)
+var s_texture: sampler;
+
+@vertex
+fn vs_main(vin: VertexInput, iin: InstanceInput) -> FragmentInput {
+	let c = cos(iin.rotation);
+	let s = sin(iin.rotation);
+	let local = vin.corner * iin.size;
+	let pos = iin.pos + vec2<f32>(local.x * c - local.y * s, local.x * s + local.y * c);
+
+	// same uv orientation as QuadMesh
+	let t = vin.corner + 0.5;
+
+	var fin: FragmentInput;
+	fin.pos = ubo.mvp * vec4<f32>(pos, 0.0, 1.0);
+	fin.col = iin.col;
+	fin.uv = vec2<f32>(mix(iin.tex.x, iin.tex.z, t.x), mix(iin.tex.w, iin.tex.y, t.y));
+	return fin;
+}
+
+@fragment
+fn fs_main(fin: FragmentInput) -> @location(0) vec4<f32> {
+	return textureSample(t_texture, s_texture, fin.uv) * fin.col;
+}
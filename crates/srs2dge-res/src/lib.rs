@@ -6,8 +6,10 @@ pub mod font {
 pub mod shader {
     pub const COLORED_2D: &str = include_str!("../res/shader/colored_2d.wgsl");
     pub const SDF: &str = include_str!("../res/shader/sdf.wgsl");
+    pub const SPRITE_INSTANCED: &str = include_str!("../res/shader/sprite_instanced.wgsl");
     pub const TEXT: &str = include_str!("../res/shader/text.wgsl");
     pub const TEXTURE_2D: &str = include_str!("../res/shader/texture_2d.wgsl");
+    pub const TEXTURE_2D_ARRAY: &str = include_str!("../res/shader/texture_2d_array.wgsl");
 }
 
 pub mod texture {
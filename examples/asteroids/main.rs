@@ -117,12 +117,17 @@ impl App {
     }
 
     async fn draw(&mut self) {
-        let mvp = Mat4::orthographic_rh(-self.ws.aspect, self.ws.aspect, -1.0, 1.0, -1.0, 1.0);
+        let camera = {
+            let mut camera = self.world.resources.get_mut::<Camera2D>().unwrap();
+            camera.resize(self.ws.size);
+            *camera
+        };
+        let mvp = camera.mvp();
 
         // update
         let cursor = if self.ws.cursor_pos != self.old_cursor_pos {
             self.old_cursor_pos = self.ws.cursor_pos;
-            Gizmos::screen_to_world(mvp, &self.ws, self.ws.cursor_pos)
+            Some(camera.screen_to_world(self.ws.cursor_pos))
         } else {
             None
         };
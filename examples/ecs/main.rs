@@ -13,6 +13,7 @@ struct App {
 
     ubo: UniformBuffer<Mat4>,
     shader: Texture2DShader,
+    sampler: Sampler,
 
     world: World,
 }
@@ -57,6 +58,7 @@ impl App {
 
         let ubo = UniformBuffer::new(&target, 1);
         let shader = Texture2DShader::new(&target);
+        let sampler = Sampler::nearest(&target);
 
         let mut world = World::new().with_plugin(DefaultClientPlugins(&target));
         world.updates.insert(random_movement_system);
@@ -86,6 +88,7 @@ impl App {
 
             ubo,
             shader,
+            sampler,
             world,
         }
     }
@@ -138,7 +141,11 @@ impl App {
             .primary_render_pass()
             .bind_vbo(vbo)
             .bind_ibo(ibo)
-            .bind_group(&self.shader.bind_group((&self.ubo, &self.texture)))
+            .bind_group(
+                &self
+                    .shader
+                    .bind_group((&self.ubo, &self.texture, &self.sampler)),
+            )
             .bind_shader(&self.shader)
             .draw_indexed(0..i, 0, 0..1);
 
@@ -1,5 +1,5 @@
 use instant::Instant;
-use winit::{dpi::PhysicalPosition, event::WindowEvent};
+use winit::event::WindowEvent;
 
 use srs2dge::prelude::*;
 
@@ -8,6 +8,7 @@ use srs2dge::prelude::*;
 struct App {
     target: Target,
     ws: WindowState,
+    camera: Camera2D,
     timer: Instant,
     debug: Gizmos,
 }
@@ -16,6 +17,8 @@ impl App {
     async fn init(target: &EventLoopTarget) -> Self {
         let target = Engine::new().new_target_default(target).await.unwrap();
         let ws = WindowState::new(&target.get_window().unwrap());
+        // the same y down projection as the gizmos default
+        let camera = Camera2D::default().with_y_down(true);
         let timer = Instant::now();
         let mut debug = Gizmos::new(&target);
         debug.set_font_bytes(res::font::FIRA).unwrap();
@@ -23,6 +26,7 @@ impl App {
         Self {
             target,
             ws,
+            camera,
             timer,
             debug,
         }
@@ -45,6 +49,9 @@ impl App {
     async fn draw(&mut self) {
         let t = self.timer.elapsed().as_secs_f32();
 
+        self.camera.resize(self.ws.size);
+        self.debug.set_mvp(self.camera.mvp());
+
         let r_a = Vec2::ONE * 0.5;
         let r_b = Vec2::ONE * 0.33;
         let r_c = Vec2::ONE * 0.3;
@@ -80,26 +87,19 @@ impl App {
             .add_circle(GizmosCircle::new(Vec2::ZERO, r_d, Color::WHITE));
 
         // cursor follower
-        (|| {
-            let mvp = self.debug.mvp(&self.ws);
-
-            // position at cursor
-            let middle = Gizmos::screen_to_world(mvp, &self.ws, self.ws.cursor_pos)?;
-
-            // crude 10px radius
-            let radius = Gizmos::screen_to_world(mvp, &self.ws, PhysicalPosition::new(10, 10))?;
-            let radius =
-                radius - Gizmos::screen_to_world(mvp, &self.ws, PhysicalPosition::new(0, 0))?;
-
-            self.debug
-                .add_circle(GizmosCircle::new(middle, radius, Color::ORANGE));
-            self.debug.add_box(GizmosBox::new(
-                middle,
-                Vec2::ONE * radius * 1.5,
-                Color::ROSE,
-            ));
-            Some(())
-        })();
+        // position at cursor
+        let middle = self.camera.screen_to_world(self.ws.cursor_pos);
+
+        // 10px radius
+        let radius = Vec2::splat(10.0) / self.camera.scale();
+
+        self.debug
+            .add_circle(GizmosCircle::new(middle, radius, Color::ORANGE));
+        self.debug.add_box(GizmosBox::new(
+            middle,
+            Vec2::ONE * radius * 1.5,
+            Color::ROSE,
+        ));
 
         let mut frame = self.target.get_frame();
         self.debug.prepare(&mut self.target, &mut frame, &self.ws);
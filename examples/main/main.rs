@@ -12,6 +12,7 @@ struct App {
 
     texture_shader: Texture2DShader,
     text_shader: TextShader,
+    sampler: Sampler,
 
     quad: Quad,
     text: Text,
@@ -66,6 +67,7 @@ impl App {
 
         let texture_shader = Texture2DShader::new(&target);
         let text_shader = TextShader::new(&target);
+        let sampler = Sampler::nearest(&target);
 
         let vbo = VertexBuffer::new_with(
             &target,
@@ -258,6 +260,7 @@ impl App {
 
             texture_shader,
             text_shader,
+            sampler,
 
             quad,
             text,
@@ -366,18 +369,16 @@ impl App {
                     * Mat4::from_rotation_z(self.quad.a + self.quad.speed * delta),
             ],
         );
-        self.text.ubo.upload(
-            &mut self.target,
-            frame,
-            &[Mat4::orthographic_rh(
-                0.0,
-                self.ws.size.width as _,
-                -(self.ws.size.height as f32),
-                0.0,
-                -100.0,
-                100.0,
-            )],
-        );
+
+        // one world unit is one pixel and the
+        // origin is at the top left corner
+        let size = Vec2::new(self.ws.size.width as _, self.ws.size.height as _);
+        let camera = Camera2D::new(size, ScalingMode::Stretch)
+            .with_position(size * Vec2::new(0.5, -0.5))
+            .with_window_size(self.ws.size);
+        self.text
+            .ubo
+            .upload(&mut self.target, frame, &[camera.mvp()]);
     }
 
     async fn draw(&mut self) {
@@ -401,11 +402,11 @@ impl App {
             // quad draw
             .bind_ibo(&self.quad.ibo)
             .bind_vbo(&self.quad.vbo)
-            .bind_group(
-                &self
-                    .texture_shader
-                    .bind_group((&self.quad.ubo, &self.quad.texture)),
-            )
+            .bind_group(&self.texture_shader.bind_group((
+                &self.quad.ubo,
+                &self.quad.texture,
+                &self.sampler,
+            )))
             .bind_shader(&self.texture_shader)
             .draw_indexed(0..6, 0, 0..1)
             // static text draw
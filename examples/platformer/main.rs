@@ -1,5 +1,5 @@
 use components::{Collider, CollisionResolver, CustomPlugin, Player};
-use legion::{component, IntoQuery};
+use legion::{component, Entity, IntoQuery};
 use ron::ser::{to_string_pretty, PrettyConfig};
 use serde::de::DeserializeSeed;
 use std::ops::Deref;
@@ -12,10 +12,6 @@ mod components;
 
 //
 
-const CAM_FOLLOW_PLAYER: bool = true;
-
-//
-
 struct App {
     target: Target,
 
@@ -27,6 +23,7 @@ struct App {
 
     ubo: UniformBuffer<Mat4>,
     shader: Texture2DShader,
+    sampler: Sampler,
 
     world: World,
 }
@@ -61,6 +58,7 @@ impl App {
 
         let ubo = UniformBuffer::new(&target, 1);
         let shader = Texture2DShader::new(&target);
+        let sampler = Sampler::nearest(&target);
 
         let mut world = World::new()
             .with_plugin(DefaultClientPlugins(&target))
@@ -169,6 +167,17 @@ impl App {
                 .unwrap();
         }
 
+        // the camera follows the player
+        let player = *<Entity>::query()
+            .filter(component::<Player>())
+            .iter(world.deref())
+            .next()
+            .unwrap();
+        world
+            .entry(player)
+            .unwrap()
+            .add_component(CameraFollow::new(0.1));
+
         Self {
             target,
 
@@ -180,6 +189,7 @@ impl App {
 
             ubo,
             shader,
+            sampler,
 
             world,
         }
@@ -198,6 +208,7 @@ impl App {
     }
 
     async fn draw(&mut self) {
+        self.world.resources.insert(self.ws);
         self.world.resources.insert(self.ks.clone());
         self.world.resources.insert(self.gs.clone());
         if self.world.run() {
@@ -208,30 +219,8 @@ impl App {
         // frames
         let mut frame = self.target.get_frame();
 
-        let player_pos = <&Sprite>::query()
-            .filter(component::<Player>())
-            .iter(self.world.deref())
-            .next()
-            .unwrap()
-            .lerp_transform
-            .translation;
-
-        self.ubo.upload(
-            &mut self.target,
-            &mut frame,
-            &[Mat4::orthographic_rh(
-                -1.0 * self.ws.aspect,
-                1.0 * self.ws.aspect,
-                -1.0,
-                1.0,
-                -1.0,
-                1.0,
-            ) * if CAM_FOLLOW_PLAYER {
-                Mat4::from_translation(Vec3::new(-player_pos.x, -player_pos.y, 0.0))
-            } else {
-                Mat4::IDENTITY
-            }],
-        );
+        let mvp = self.world.resources.get::<Camera2D>().unwrap().mvp();
+        self.ubo.upload(&mut self.target, &mut frame, &[mvp]);
 
         let mut batcher = self.world.get_batcher_mut();
         let (vbo, ibo, i) = batcher.generate(&mut self.target, &mut frame);
@@ -240,7 +229,11 @@ impl App {
             .primary_render_pass()
             .bind_vbo(vbo)
             .bind_ibo(ibo)
-            .bind_group(&self.shader.bind_group((&self.ubo, &self.texture_atlas)))
+            .bind_group(
+                &self
+                    .shader
+                    .bind_group((&self.ubo, &self.texture_atlas, &self.sampler)),
+            )
             .bind_shader(&self.shader)
             .draw_indexed(0..i, 0, 0..1);
     }
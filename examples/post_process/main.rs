@@ -8,24 +8,32 @@ const POST_PROCESSOR: &str = include_str!("main.wgsl");
 
 struct App {
     target: Target,
-    secondary_target: RenderTargetTexture,
 
     ws: WindowState,
 
-    // logo
-    texture: Texture,
+    // logo pass into "scene"
+    // and post processing to screen
+    graph: RenderGraph,
+}
 
-    // logo quad and screen quad
+/// draws the logo into the `scene` texture
+struct ScenePass {
     vbo: VertexBuffer,
     ibo: IndexBuffer,
-
-    // drawing to texture
     ubo: UniformBuffer<Mat4>,
     shader: Texture2DShader,
+    bind_group: wgpu::BindGroup,
+}
 
-    // drawing to screen
-    identity_ubo: UniformBuffer<Mat4>,
-    custom_shader: Texture2DShader,
+/// draws the `scene` texture to the screen
+/// with the custom fragment shader
+struct PostProcessPass {
+    vbo: VertexBuffer,
+    ibo: IndexBuffer,
+    ubo: UniformBuffer<Mat4>,
+    shader: Texture2DShader,
+    sampler: Sampler,
+    bind_group: Option<wgpu::BindGroup>,
 }
 
 //
@@ -35,14 +43,8 @@ impl App {
         let target = Engine::new().new_target_default(target).await.unwrap();
 
         let ws = WindowState::new(&target.get_window().unwrap());
-        let secondary_target = RenderTargetTexture::new_format(
-            &target,
-            Rect::new(ws.size.width, ws.size.height),
-            target.get_format(), // secondary target format set to same as primary target format to be able to use a single pipeline
-            None,
-        );
 
-        let texture = Texture::new_rgba_with(
+        let texture: Texture = Texture::new_rgba_with(
             &target,
             &image::load_from_memory(res::texture::RUST)
                 .unwrap()
@@ -62,50 +64,42 @@ impl App {
             Color::WHITE,
             TexturePosition::default(),
         );
-        let vbo = VertexBuffer::new_with(
-            &target,
-            &[quad_a, quad_b]
-                .into_iter()
-                .flat_map(|q| q.vertices())
-                .collect::<Box<_>>(),
-        );
-        let mut i = 0;
-        let ibo = IndexBuffer::new_with(
+
+        let ubo = UniformBuffer::new(&target, 1);
+        // "scene" is drawn in a secondary pass, which
+        // is never multisampled
+        let shader = Texture2DShader::new_with(
             &target,
-            &[quad_a, quad_b]
-                .into_iter()
-                .flat_map(|q| {
-                    let offset = i;
-                    i += q.index_step();
-                    q.indices(offset)
-                })
-                .collect::<Box<_>>(),
+            PresetOptions::primary(&target).with_sample_count(1),
         );
-        let ubo = UniformBuffer::new(&target, 1);
-        let shader = Texture2DShader::new(&target);
-
-        let identity_ubo = UniformBuffer::new_single(&target, Mat4::IDENTITY);
-        let custom_frag = ShaderModule::new_wgsl_source(&target, POST_PROCESSOR.into())
-            .unwrap_or_else(|err| panic!("Custom module compilation failed: {err}"));
-        let custom_shader = Texture2DShader::new_custom_frag(&target, &custom_frag, "main")
-            .unwrap_or_else(|err| panic!("Custom module incompatible: {err}"));
-
-        Self {
-            target,
-            secondary_target,
-
-            ws,
-
-            texture,
-
-            vbo,
-            ibo,
+        let bind_group = shader.bind_group((&ubo, &texture, &Sampler::nearest(&target)));
+        let scene = ScenePass {
+            vbo: VertexBuffer::new_with(&target, &quad_a.vertices().collect::<Box<_>>()),
+            ibo: IndexBuffer::new_with(&target, &quad_a.indices(0).collect::<Box<_>>()),
             ubo,
             shader,
+            bind_group,
+        };
 
-            identity_ubo,
-            custom_shader,
-        }
+        let custom_frag = ShaderModule::new_wgsl_source(&target, POST_PROCESSOR.into())
+            .unwrap_or_else(|err| panic!("Custom module compilation failed: {err}"));
+        let post_process = PostProcessPass {
+            vbo: VertexBuffer::new_with(&target, &quad_b.vertices().collect::<Box<_>>()),
+            ibo: IndexBuffer::new_with(&target, &quad_b.indices(0).collect::<Box<_>>()),
+            ubo: UniformBuffer::new_single(&target, Mat4::IDENTITY),
+            shader: Texture2DShader::new_custom_frag(&target, &custom_frag, "main")
+                .unwrap_or_else(|err| panic!("Custom module incompatible: {err}")),
+            sampler: Sampler::nearest(&target),
+            bind_group: None,
+        };
+
+        // the graph resizes "scene" with the window
+        let graph = RenderGraph::new()
+            .with_texture("scene", None, 1.0)
+            .with_pass("scene", &[], "scene", scene)
+            .with_composite("post process", &["scene"], post_process);
+
+        Self { target, ws, graph }
     }
 
     async fn event(&mut self, event: Event<'_>, _: &EventLoopTarget, control: &mut ControlFlow) {
@@ -116,57 +110,42 @@ impl App {
         if self.ws.should_close {
             *control = ControlFlow::Exit;
         }
-
-        if let Event::WindowEvent {
-            event: WindowEvent::Resized(s),
-            ..
-        } = event
-        {
-            self.secondary_target = RenderTargetTexture::new_format(
-                &self.target,
-                s.into(),
-                self.target.get_format(),
-                None,
-            );
-        }
     }
 
     async fn draw(&mut self) {
         let mut frame = self.target.get_frame();
 
-        self.ubo.upload(
-            &mut self.target,
-            &mut frame,
-            &[Mat4::orthographic_rh(
-                -self.ws.aspect,
-                self.ws.aspect,
-                -1.0,
-                1.0,
-                -100.0,
-                100.0,
-            )],
-        );
+        self.graph.draw(&mut self.target, &mut frame).unwrap();
+    }
+}
+
+impl GraphPass for ScenePass {
+    fn prepare(&mut self, target: &mut Target, frame: &mut Frame, _: &GraphTextures) {
+        let camera = Camera2D::default().with_window_size(frame.get_dim());
+        self.ubo.upload(target, frame, &[camera.mvp()]);
+    }
 
-        frame
-            .secondary_render_pass(&self.secondary_target)
-            .unwrap()
-            .bind_vbo(&self.vbo)
+    fn draw<'e>(&'e self, pass: RenderPass<'e>, _: &'e GraphTextures) {
+        pass.bind_vbo(&self.vbo)
             .bind_ibo(&self.ibo)
-            .bind_group(&self.shader.bind_group((&self.ubo, &self.texture)))
+            .bind_group(&self.bind_group)
             .bind_shader(&self.shader)
             .draw_indexed(0..5, 0, 0..1);
+    }
+}
 
-        frame
-            .primary_render_pass()
-            .bind_vbo(&self.vbo)
+impl GraphPass for PostProcessPass {
+    fn prepare(&mut self, _: &mut Target, _: &mut Frame, textures: &GraphTextures) {
+        let scene = textures.get("scene").unwrap();
+        self.bind_group = Some(self.shader.bind_group((&self.ubo, scene, &self.sampler)));
+    }
+
+    fn draw<'e>(&'e self, pass: RenderPass<'e>, _: &'e GraphTextures) {
+        pass.bind_vbo(&self.vbo)
             .bind_ibo(&self.ibo)
-            .bind_group(
-                &self
-                    .shader
-                    .bind_group((&self.identity_ubo, &self.secondary_target)),
-            )
-            .bind_shader(&self.custom_shader)
-            .draw_indexed(4..9, 0, 0..1);
+            .bind_group(self.bind_group.as_ref().unwrap())
+            .bind_shader(&self.shader)
+            .draw_indexed(0..5, 0, 0..1);
     }
 }
 
@@ -16,6 +16,7 @@ struct App {
     ibo: IndexBuffer,
     ubo: UniformBuffer<SdfUniform>,
     sdf_shader: SdfShader,
+    sampler: Sampler,
 }
 
 //
@@ -65,6 +66,7 @@ impl App {
         let ibo = IndexBuffer::new_with(&target, &i);
         let ubo = UniformBuffer::new(&target, 1);
         let sdf_shader = SdfShader::new(&target);
+        let sampler = Sampler::linear(&target);
 
         Self {
             target,
@@ -77,6 +79,7 @@ impl App {
             ibo,
             ubo,
             sdf_shader,
+            sampler,
         }
     }
 
@@ -95,24 +98,27 @@ impl App {
 
         let mut frame = self.target.get_frame();
 
+        // one world unit is one pixel and the
+        // origin is at the bottom left corner
+        let size = Vec2::new(self.ws.size.width as _, self.ws.size.height as _);
+        let camera = Camera2D::new(size, ScalingMode::Stretch)
+            .with_position(size * 0.5)
+            .with_window_size(self.ws.size);
         self.ubo.upload(
             &mut self.target,
             &mut frame,
-            &[SdfUniform::new_defaults(Mat4::orthographic_rh(
-                0.0,
-                self.ws.size.width as _,
-                0.0,
-                self.ws.size.height as _,
-                -100.0,
-                100.0,
-            ))],
+            &[SdfUniform::new_defaults(camera.mvp())],
         );
 
         frame
             .primary_render_pass()
             .bind_vbo(&self.vbo)
             .bind_ibo(&self.ibo)
-            .bind_group(&self.sdf_shader.bind_group((&self.ubo, &self.glyphs)))
+            .bind_group(
+                &self
+                    .sdf_shader
+                    .bind_group((&self.ubo, &self.glyphs, &self.sampler)),
+            )
             .bind_shader(&self.sdf_shader)
             .draw_indexed(0..self.ibo.capacity() as _, 0, 0..1);
     }
@@ -30,6 +30,7 @@ struct App {
     ibos: IndexBuffer,
     screen_ubo: UniformBuffer<SdfUniform>,
     text_shader: SdfShader,
+    text_sampler: Sampler,
 
     board: Board,
     score: usize,
@@ -70,6 +71,7 @@ impl App {
         .unwrap();
         let screen_ubo = UniformBuffer::new(&target, 1);
         let text_shader = SdfShader::new(&target);
+        let text_sampler = Sampler::linear(&target);
 
         let vbos = VertexBuffer::new(&target, 0);
         let ibos = IndexBuffer::new(&target, 0);
@@ -95,6 +97,7 @@ impl App {
             ibos,
             screen_ubo,
             text_shader,
+            text_sampler,
 
             board,
             score: 999,
@@ -288,11 +291,11 @@ impl App {
             .draw_indexed(0..i, 0, 0..1)
             .bind_vbo(&self.vbos)
             .bind_ibo(&self.ibos)
-            .bind_group(
-                &self
-                    .text_shader
-                    .bind_group((&self.screen_ubo, &self.glyphs)),
-            )
+            .bind_group(&self.text_shader.bind_group((
+                &self.screen_ubo,
+                &self.glyphs,
+                &self.text_sampler,
+            )))
             .bind_shader(&self.text_shader)
             .draw_indexed(0..self.ibos.capacity() as _, 0, 0..1);
     }
@@ -121,18 +121,14 @@ impl App {
     async fn draw(&mut self) {
         let mut frame = self.target.get_frame();
 
-        self.ubo.upload(
-            &mut self.target,
-            &mut frame,
-            &[Mat4::orthographic_rh(
-                0.0,
-                self.ws.size.width as _,
-                0.0,
-                self.ws.size.height as _,
-                -100.0,
-                100.0,
-            )],
-        );
+        // one world unit is one pixel and the
+        // origin is at the bottom left corner
+        let size = Vec2::new(self.ws.size.width as _, self.ws.size.height as _);
+        let camera = Camera2D::new(size, ScalingMode::Stretch)
+            .with_position(size * 0.5)
+            .with_window_size(self.ws.size);
+        self.ubo
+            .upload(&mut self.target, &mut frame, &[camera.mvp()]);
 
         frame
             .primary_render_pass()
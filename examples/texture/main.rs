@@ -13,6 +13,7 @@ struct App {
     ibo: IndexBuffer,
     ubo: UniformBuffer<Mat4>,
     shader: Texture2DShader,
+    sampler: Sampler,
 }
 
 //
@@ -41,6 +42,7 @@ impl App {
         let ibo = IndexBuffer::new_with(&target, &quad.indices(0).collect::<Box<_>>());
         let ubo = UniformBuffer::new(&target, 1);
         let shader = Texture2DShader::new(&target);
+        let sampler = Sampler::nearest(&target);
 
         Self {
             target,
@@ -53,6 +55,7 @@ impl App {
             ibo,
             ubo,
             shader,
+            sampler,
         }
     }
 
@@ -86,7 +89,11 @@ impl App {
             .primary_render_pass()
             .bind_vbo(&self.vbo)
             .bind_ibo(&self.ibo)
-            .bind_group(&self.shader.bind_group((&self.ubo, &self.texture)))
+            .bind_group(
+                &self
+                    .shader
+                    .bind_group((&self.ubo, &self.texture, &self.sampler)),
+            )
             .bind_shader(&self.shader)
             .draw_indexed(0..4, 0, 0..1);
     }